@@ -210,6 +210,10 @@ extern "C" {
         return builder->CreateStructGEP(base, val, index);
     }
 
+    llvm::Value *BuilderCreateExtractValue(llvm::IRBuilder<> *builder, llvm::Value *agg, unsigned int index) {
+        return builder->CreateExtractValue(agg, index);
+    }
+
     llvm::Value *BuilderCreateXorVV(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
         return builder->CreateXor(lhs, rhs);
     }
@@ -230,10 +234,30 @@ extern "C" {
         return builder->CreateBr(bb);
     }
 
+    llvm::Value *BuilderCreateUnreachable(llvm::IRBuilder<> *builder) {
+        return builder->CreateUnreachable();
+    }
+
     llvm::Value *BuilderCreateCondBr(llvm::IRBuilder<> *builder, llvm::Value *cond, llvm::BasicBlock *true_bb, llvm::BasicBlock *false_bb) {
         return builder->CreateCondBr(cond, true_bb, false_bb);
     }
 
+    llvm::SwitchInst *BuilderCreateSwitch(llvm::IRBuilder<> *builder, llvm::Value *value, llvm::BasicBlock *default_bb, unsigned int num_cases) {
+        return builder->CreateSwitch(value, default_bb, num_cases);
+    }
+
+    void SwitchInstAddCase(llvm::SwitchInst *self, llvm::ConstantInt *on_val, llvm::BasicBlock *dest) {
+        self->addCase(on_val, dest);
+    }
+
+    llvm::PHINode *BuilderCreatePHI(llvm::IRBuilder<> *builder, llvm::Type *ty, unsigned int num_values) {
+        return builder->CreatePHI(ty, num_values);
+    }
+
+    void PHINodeAddIncoming(llvm::PHINode *self, llvm::Value *value, llvm::BasicBlock *bb) {
+        self->addIncoming(value, bb);
+    }
+
     llvm::Value *BuilderCreateAdd(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
         return builder->CreateAdd(lhs, rhs);
     }
@@ -275,7 +299,95 @@ extern "C" {
     }
 
     llvm::Value *BuilderCreateICmpSGE(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
-        return builder->CreateICmpSGT(lhs, rhs);
+        return builder->CreateICmpSGE(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateICmpUGE(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateICmpUGE(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateICmpULE(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateICmpULE(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateUDiv(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateUDiv(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateURem(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateURem(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateFAdd(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateFAdd(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateFSub(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateFSub(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateFMul(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateFMul(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateFDiv(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateFDiv(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateFRem(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateFRem(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateICmpULT(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateICmpULT(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateICmpUGT(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateICmpUGT(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateFCmpOEQ(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateFCmpOEQ(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateFCmpUNE(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateFCmpUNE(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateFCmpOLT(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateFCmpOLT(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateFCmpOLE(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateFCmpOLE(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateFCmpOGT(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateFCmpOGT(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateFCmpOGE(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateFCmpOGE(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateAnd(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateAnd(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateOr(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateOr(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateShl(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateShl(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateLShr(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateLShr(lhs, rhs);
+    }
+
+    llvm::Value *BuilderCreateAShr(llvm::IRBuilder<> *builder, llvm::Value *lhs, llvm::Value *rhs) {
+        return builder->CreateAShr(lhs, rhs);
     }
 
     llvm::Value *BuilderCreateGlobalStringPointer(llvm::IRBuilder<> *builder, const char *string) {
@@ -415,6 +527,10 @@ extern "C" {
         return args;
     }
 
+    void FunctionEraseFromParent(llvm::Function *func) {
+        func->eraseFromParent();
+    }
+
     bool FunctionVerify(llvm::Function *func) {
         return llvm::verifyFunction(*func, &llvm::errs());
     }
@@ -435,10 +551,26 @@ extern "C" {
         return new llvm::GlobalVariable(*mod, ty, is_constant, llvm::GlobalVariable::InternalLinkage, value);
     }
 
+    llvm::GlobalVariable *GlobalVariableCreateNamed(llvm::Module *mod, llvm::Type *ty, bool is_constant, llvm::Constant *value, const char *name) {
+        return new llvm::GlobalVariable(*mod, ty, is_constant, llvm::GlobalVariable::InternalLinkage, value, name);
+    }
+
+    llvm::GlobalVariable *ModuleGetGlobal(llvm::Module *mod, const char *name) {
+        return mod->getNamedGlobal(name);
+    }
+
+    llvm::Constant *ConstantExprGetPointerCast(llvm::Constant *value, llvm::Type *ty) {
+        return llvm::ConstantExpr::getPointerCast(value, ty);
+    }
+
     llvm::Constant *GetConstantInt(llvm::Type *typ, int value) {
         return llvm::ConstantInt::get(typ, value);
     }
 
+    llvm::Constant *GetConstantInt64(llvm::Type *typ, uint64_t value, bool sign_extend) {
+        return llvm::ConstantInt::get(typ, value, sign_extend);
+    }
+
     llvm::Constant *GetConstantFP(llvm::Type *typ, double value) {
         return llvm::ConstantFP::get(typ, value);
     }
@@ -57,6 +57,7 @@ extern "C" {
     fn DataLayoutGetTypeAllocSizeInBits(dl: *mut (), ty: *mut ()) -> usize;
 
     fn ModuleGetFunction(module: *mut (), name: CStr) -> *mut ();
+    fn ModuleGetGlobal(module: *mut (), name: CStr) -> *mut ();
 
     fn CreateIRBuilder(ctx: *mut ()) -> *mut ();
     fn DestroyIRBuilder(builder: *mut ());
@@ -75,6 +76,7 @@ extern "C" {
     fn BuilderCreateCall(builder: *mut (), callee: *mut (), args: *mut *mut (), args_size: usize) -> *mut ();
     fn BuilderCreateGEP(builder: *mut (), base: *mut (), val: *mut (), idx_list: *mut *mut (), idx_count: usize, in_bounds: bool) -> *mut ();
     fn BuilderCreateStructGEP(builder: *mut (), base: *mut (), val: *mut (), index: u32) -> *mut ();
+    fn BuilderCreateExtractValue(builder: *mut (), agg: *mut (), index: u32) -> *mut ();
     fn BuilderCreateXorVV(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
     fn BuilderCreateXorVL(builder: *mut (), lhs: *mut (), rhs: u64) -> *mut ();
     fn BuilderCreateCast(builder: *mut (), op: CastOps, src: *mut (), dest_ty: *mut ()) -> *mut ();
@@ -90,19 +92,48 @@ extern "C" {
     fn BuilderCreateICmpSLE(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
     fn BuilderCreateICmpSGT(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
     fn BuilderCreateICmpSGE(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateICmpUGE(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateICmpULE(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateUDiv(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateURem(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateFAdd(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateFSub(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateFMul(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateFDiv(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateFRem(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateICmpULT(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateICmpUGT(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateFCmpOEQ(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateFCmpUNE(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateFCmpOLT(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateFCmpOLE(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateFCmpOGT(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateFCmpOGE(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateAnd(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateOr(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateShl(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateLShr(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
+    fn BuilderCreateAShr(builder: *mut (), lhs: *mut (), rhs: *mut ()) -> *mut ();
     fn BuilderCreateGlobalStringPointer(builder: *mut (), string: CStr) -> *mut ();
 
 
     fn BuilderCreateBr(builder: *mut (), bb: *mut ()) -> *mut ();
+    fn BuilderCreateUnreachable(builder: *mut ()) -> *mut ();
     fn BuilderCreateCondBr(builder: *mut (), cond: *mut (), true_bb: *mut (), false_bb: *mut ()) -> *mut ();
+    fn BuilderCreateSwitch(builder: *mut (), value: *mut (), default_bb: *mut (), num_cases: u32) -> *mut ();
+    fn SwitchInstAddCase(switch: *mut (), on_val: *mut (), dest: *mut ());
+    fn BuilderCreatePHI(builder: *mut (), ty: *mut (), num_values: u32) -> *mut ();
+    fn PHINodeAddIncoming(phi: *mut (), value: *mut (), bb: *mut ());
 
     fn GetPointerType(ty: *mut(), address_space: u32) -> *mut ();
 
     fn GetIntType(ctx: *mut (), width: usize) -> *mut ();
     fn GetConstantInt(typ: *mut (), value: i32) -> *mut ();
+    fn GetConstantInt64(typ: *mut (), value: u64, sign_extend: bool) -> *mut ();
     fn GetConstantFP(ty: *mut (), value: f64) -> *mut ();
     fn GetConstantStruct(ty: *mut (), constatns: *mut *mut (), constatns_len: usize) -> *mut ();
     fn GetConstantArr(ty: *mut (), values: *mut *mut (), values_len: usize) -> *mut ();
+    fn ConstantExprGetPointerCast(value: *mut (), ty: *mut ()) -> *mut ();
 
     fn FunctionTypeGet(ret: *mut (), params: *mut *mut (), len: usize, is_var_arg: bool) -> *mut ();
 
@@ -136,12 +167,14 @@ extern "C" {
 
     fn FunctionCreate(typ: *mut (), name: CStr, module: *mut ()) -> *mut ();
     fn FunctionGetArgs(func: *mut ()) -> FuncArgs;
+    fn FunctionEraseFromParent(func: *mut ());
     fn FunctionVerify(func: *mut ()) -> bool;
     fn FunctionIsVarArg(func: *mut ()) -> bool;
     fn FunctionGetType(func: *mut ()) -> *mut ();
 
 
     fn GlobalVariableCreate(m: *mut (), ty: *mut (), is_constant: bool, val: *mut ()) -> *mut ();
+    fn GlobalVariableCreateNamed(m: *mut (), ty: *mut (), is_constant: bool, val: *mut (), name: CStr) -> *mut ();
 
 
     fn CreateBasicBlock(ctx: *mut (), name: CStr, parent: *mut (), before: *mut ()) -> *mut ();
@@ -407,6 +440,10 @@ impl Function {
         return vec.iter().map(|i| ValueRef::new(*i)).collect();
     }
 
+    pub fn erase_from_parent(&self) {
+        unsafe{ FunctionEraseFromParent(self.0) };
+    }
+
     pub fn verify(&self) -> bool {
         return unsafe{ FunctionVerify(self.0) };
     }
@@ -443,6 +480,10 @@ impl GlobalVariable {
         let val = if let Some(v) = value { v.0 } else { std::ptr::null_mut() };
         Self{ 0: unsafe{ GlobalVariableCreate(m.ptr, typ.0, is_constant, val) } }
     }
+
+    pub fn with_name(m: &Module, typ: &TypeRef, value: &ValueRef, is_constant: bool, name: &str) -> Self {
+        Self{ 0: unsafe{ GlobalVariableCreateNamed(m.ptr, typ.0, is_constant, value.0, to_cstr!(name)) } }
+    }
 }
 
 impl Into<ValueRef> for GlobalVariable {
@@ -484,6 +525,38 @@ impl BasicBlock {
     }
 }
 
+#[repr(C)]
+#[derive(Debug)]
+pub struct SwitchInst(*mut ());
+
+impl SwitchInst {
+    pub fn add_case(&self, on_val: &ValueRef, dest: &BasicBlock) {
+        unsafe{ SwitchInstAddCase(self.0, on_val.0, dest.0) };
+    }
+}
+
+impl Into<ValueRef> for SwitchInst {
+    fn into(self) -> ValueRef {
+        ValueRef{ 0: self.0 }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct PHINode(*mut ());
+
+impl PHINode {
+    pub fn add_incoming(&self, value: &ValueRef, bb: &BasicBlock) {
+        unsafe{ PHINodeAddIncoming(self.0, value.0, bb.0) };
+    }
+}
+
+impl Into<ValueRef> for PHINode {
+    fn into(self) -> ValueRef {
+        ValueRef{ 0: self.0 }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct InsertPoint(*mut ());
@@ -561,6 +634,15 @@ impl<'a> Module<'a> {
         }
     }
 
+    pub fn get_global(&self, name: &str) -> Option<GlobalVariable> {
+        let ret = unsafe{ ModuleGetGlobal(self.ptr, to_cstr!(name)) };
+        if ret.is_null() {
+            return None;
+        }else {
+            return Some(GlobalVariable{ 0: ret });
+        }
+    }
+
     pub fn get_data_layout(&self) -> DataLayoutRef {
         DataLayoutRef{ 0: unsafe{ ModuleGetDataLayout(self.ptr) }}
     }
@@ -639,6 +721,10 @@ impl<'a> IRBuilder<'a> {
         return ValueRef::new(unsafe{ BuilderCreateStructGEP(self.ptr, base.0, val.0, index) });
     }
 
+    pub fn create_extract_value(&self, agg: &ValueRef, index: u32) -> ValueRef {
+        return ValueRef::new(unsafe{ BuilderCreateExtractValue(self.ptr, agg.0, index) });
+    }
+
     pub fn create_xor_vv(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
         return ValueRef::new(unsafe{ BuilderCreateXorVV(self.ptr, lhs.0, rhs.0) });
     }
@@ -659,10 +745,22 @@ impl<'a> IRBuilder<'a> {
         return ValueRef::new(unsafe{ BuilderCreateBr(self.ptr, bb.0) });
     }
 
+    pub fn create_unreachable(&self) -> ValueRef {
+        return ValueRef::new(unsafe{ BuilderCreateUnreachable(self.ptr) });
+    }
+
     pub fn create_cond_br(&self, cond: &ValueRef, true_bb: &BasicBlock, false_bb: &BasicBlock) -> ValueRef {
         return ValueRef::new(unsafe{ BuilderCreateCondBr(self.ptr, cond.0, true_bb.0, false_bb.0) });
     }
 
+    pub fn create_switch(&self, value: &ValueRef, default_bb: &BasicBlock, num_cases: u32) -> SwitchInst {
+        return SwitchInst{ 0: unsafe{ BuilderCreateSwitch(self.ptr, value.0, default_bb.0, num_cases) } };
+    }
+
+    pub fn create_phi(&self, ty: &TypeRef, num_values: u32) -> PHINode {
+        return PHINode{ 0: unsafe{ BuilderCreatePHI(self.ptr, ty.0, num_values) } };
+    }
+
     pub fn create_add(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
         ValueRef::new(unsafe{BuilderCreateAdd(self.ptr, lhs.0, rhs.0)})
     }
@@ -683,6 +781,54 @@ impl<'a> IRBuilder<'a> {
         ValueRef::new(unsafe{BuilderCreateRem(self.ptr, lhs.0, rhs.0)})
     }
 
+    pub fn create_udiv(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        ValueRef::new(unsafe{BuilderCreateUDiv(self.ptr, lhs.0, rhs.0)})
+    }
+
+    pub fn create_urem(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        ValueRef::new(unsafe{BuilderCreateURem(self.ptr, lhs.0, rhs.0)})
+    }
+
+    pub fn create_fadd(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        ValueRef::new(unsafe{BuilderCreateFAdd(self.ptr, lhs.0, rhs.0)})
+    }
+
+    pub fn create_fsub(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        ValueRef::new(unsafe{BuilderCreateFSub(self.ptr, lhs.0, rhs.0)})
+    }
+
+    pub fn create_fmul(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        ValueRef::new(unsafe{BuilderCreateFMul(self.ptr, lhs.0, rhs.0)})
+    }
+
+    pub fn create_fdiv(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        ValueRef::new(unsafe{BuilderCreateFDiv(self.ptr, lhs.0, rhs.0)})
+    }
+
+    pub fn create_frem(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        ValueRef::new(unsafe{BuilderCreateFRem(self.ptr, lhs.0, rhs.0)})
+    }
+
+    pub fn create_and(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        ValueRef::new(unsafe{BuilderCreateAnd(self.ptr, lhs.0, rhs.0)})
+    }
+
+    pub fn create_or(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        ValueRef::new(unsafe{BuilderCreateOr(self.ptr, lhs.0, rhs.0)})
+    }
+
+    pub fn create_shl(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        ValueRef::new(unsafe{BuilderCreateShl(self.ptr, lhs.0, rhs.0)})
+    }
+
+    pub fn create_lshr(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        ValueRef::new(unsafe{BuilderCreateLShr(self.ptr, lhs.0, rhs.0)})
+    }
+
+    pub fn create_ashr(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        ValueRef::new(unsafe{BuilderCreateAShr(self.ptr, lhs.0, rhs.0)})
+    }
+
     pub fn create_icmp_eq(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
         return ValueRef::new(unsafe{ BuilderCreateICmpEQ(self.ptr, lhs.0, rhs.0) });
     }
@@ -707,6 +853,46 @@ impl<'a> IRBuilder<'a> {
         return ValueRef::new(unsafe{ BuilderCreateICmpSGE(self.ptr, lhs.0, rhs.0) });
     }
 
+    pub fn create_icmp_uge(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        return ValueRef::new(unsafe{ BuilderCreateICmpUGE(self.ptr, lhs.0, rhs.0) });
+    }
+
+    pub fn create_icmp_ule(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        return ValueRef::new(unsafe{ BuilderCreateICmpULE(self.ptr, lhs.0, rhs.0) });
+    }
+
+    pub fn create_icmp_ult(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        return ValueRef::new(unsafe{ BuilderCreateICmpULT(self.ptr, lhs.0, rhs.0) });
+    }
+
+    pub fn create_icmp_ugt(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        return ValueRef::new(unsafe{ BuilderCreateICmpUGT(self.ptr, lhs.0, rhs.0) });
+    }
+
+    pub fn create_fcmp_oeq(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        return ValueRef::new(unsafe{ BuilderCreateFCmpOEQ(self.ptr, lhs.0, rhs.0) });
+    }
+
+    pub fn create_fcmp_une(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        return ValueRef::new(unsafe{ BuilderCreateFCmpUNE(self.ptr, lhs.0, rhs.0) });
+    }
+
+    pub fn create_fcmp_olt(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        return ValueRef::new(unsafe{ BuilderCreateFCmpOLT(self.ptr, lhs.0, rhs.0) });
+    }
+
+    pub fn create_fcmp_ole(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        return ValueRef::new(unsafe{ BuilderCreateFCmpOLE(self.ptr, lhs.0, rhs.0) });
+    }
+
+    pub fn create_fcmp_ogt(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        return ValueRef::new(unsafe{ BuilderCreateFCmpOGT(self.ptr, lhs.0, rhs.0) });
+    }
+
+    pub fn create_fcmp_oge(&self, lhs: &ValueRef, rhs: &ValueRef) -> ValueRef {
+        return ValueRef::new(unsafe{ BuilderCreateFCmpOGE(self.ptr, lhs.0, rhs.0) });
+    }
+
     pub fn create_global_string_pointer<S: AsRef<str>>(&self, s: S) -> ValueRef {
         return ValueRef::new(unsafe{ BuilderCreateGlobalStringPointer(self.ptr, to_cstr!(s.as_ref())) });
     }
@@ -723,6 +909,14 @@ impl ConstantInt {
     pub fn get(typ: &TypeRef, value: i32) -> ValueRef {
         return ValueRef{ 0: unsafe{ GetConstantInt(typ.0, value) } };
     }
+
+    /*
+     *  NOTE(S): `value` is truncated to the width of `typ`, `sign_extend` only matters for types
+     *  wider than 64 bits
+     */
+    pub fn get_u64(typ: &TypeRef, value: u64, sign_extend: bool) -> ValueRef {
+        return ValueRef{ 0: unsafe{ GetConstantInt64(typ.0, value, sign_extend) } };
+    }
 }
 
 pub struct ConstantFP;
@@ -740,6 +934,13 @@ impl ConstantStruct {
     }
 }
 
+pub struct ConstantExpr;
+impl ConstantExpr {
+    pub fn get_pointer_cast(value: &ValueRef, typ: &TypeRef) -> ValueRef {
+        return ValueRef{ 0: unsafe{ ConstantExprGetPointerCast(value.0, typ.0) } };
+    }
+}
+
 pub struct ConstantArray;
 impl ConstantArray {
     pub fn get(typ: TypeRef, values: &[ValueRef]) -> ValueRef {
@@ -1,11 +1,11 @@
-use crate::ast::{Generate, AST};
+use crate::ast::{symbol, Generate, AST};
 use crate::lexer::Tagged;
-use crate::log::{error_msg_label, error_msg_label_info, ErrorLabel};
-use crate::types::{Path, Type};
+use crate::log::{error_msg_label, error_msg_label_info, error_msg_labels, ErrorLabel};
+use crate::types::{ArraySlice, Path, Pointer, Type, Tuple};
 
 #[derive(Debug)]
 pub struct MemberExpr {
-    obj: Box<AST>,
+    pub obj: Box<AST>,
     pub member: Option<Box<AST>>,
     deref: bool,
     use_index: bool,
@@ -23,15 +23,102 @@ impl MemberExpr {
         }
     }
 
-    pub fn new_index(obj: Box<AST>, index: usize) -> Self {
+    pub fn new_index(obj: Box<AST>, index: usize, deref: bool) -> Self {
         Self {
             obj,
             member: None,
-            deref: false,
+            deref,
             use_index: true,
             index,
         }
     }
+
+    /*
+     *  returns the tuple whose element is accessed, looking through references and `->`
+     */
+    fn tuple_type(&self, obj_ty: &Type) -> Option<Tuple> {
+        return match obj_ty {
+            Type::Tuple(tuple) => Some(tuple.clone()),
+            Type::Pointer(ptr) if ptr.is_ref || self.deref => match &*ptr.typ {
+                Type::Tuple(tuple) => Some(tuple.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+    }
+
+    /*
+     *  returns the slice whose `data` or `len` is accessed, looking through references and `->`
+     */
+    fn slice_type(&self, obj_ty: &Type) -> Option<ArraySlice> {
+        return match obj_ty {
+            Type::Slice(slice) => Some(slice.clone()),
+            Type::Pointer(ptr) if ptr.is_ref || self.deref => match &*ptr.typ {
+                Type::Slice(slice) => Some(slice.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+    }
+
+    /*
+     *  NOTE(S): a slice is `{ data: *T, len: u64 }`
+     */
+    fn slice_field(&self, slice: &ArraySlice) -> (usize, Type) {
+        let member = self.member.as_ref().map(|member| member.get_value()).unwrap_or_default();
+        return match member.as_str() {
+            "data" => (0, Type::Pointer(Pointer::new(slice.loc.clone(), slice.typ.clone(), false, true))),
+            "len" => (1, Type::Path(Path::new(slice.loc.clone(), "u64".to_owned()))),
+            _ => error_msg_label_info(
+                format!("unknown field `{}` in type `{}`", member, Type::Slice(slice.clone()).get_full_name()).as_str(),
+                ErrorLabel::from(self.member.as_ref().map_or(self.obj.loc(), |member| member.loc()), "unknown field"),
+                "available fields: [`data`, `len`]",
+            ),
+        };
+    }
+
+    fn tuple_index(&self, tuple: &Tuple) -> usize {
+        if !self.use_index {
+            error_msg_label(
+                format!("tuple `{}` has no named fields", Type::Tuple(tuple.clone()).get_full_name()).as_str(),
+                ErrorLabel::from(self.member.as_ref().unwrap().loc(), "tuple elements are accessed by position, e.g. `.0`"),
+            );
+        }
+        if self.index >= tuple.elems.len() {
+            error_msg_label(
+                format!("no element `{}` in tuple `{}`", self.index, Type::Tuple(tuple.clone()).get_full_name()).as_str(),
+                ErrorLabel::from(self.obj.loc(), format!("tuple has `{}` elements", tuple.elems.len())),
+            );
+        }
+        return self.index;
+    }
+
+    fn field_index(&self, obj_ty: &Type, scope: &super::Scope) -> usize {
+        if self.use_index {
+            return self.index;
+        }
+
+        let type_name = obj_ty.get_name();
+        let struct_fields = &scope.get_struct(&Tagged::new(self.obj.loc().clone(), type_name.clone())).fields;
+        let member_name = self.member.as_ref().unwrap().get_value();
+        if let Some(index) = struct_fields.iter().position(|field| *field.id.inner() == member_name) {
+            return index;
+        }
+
+        let mut fields_avail = "available fields: [".to_string();
+        for (i, field) in struct_fields.iter().enumerate() {
+            fields_avail += &("`".to_string() + field.id.inner() + "`");
+            if i + 1 != struct_fields.len() {
+                fields_avail += ", ";
+            }
+        }
+        fields_avail += "]";
+        error_msg_label_info(
+            format!("unknown field `{}` in type `{}`", member_name, type_name).as_str(),
+            ErrorLabel::from(self.member.as_ref().unwrap().loc(), "unknown field"),
+            &fields_avail
+        );
+    }
 }
 
 impl Generate for MemberExpr {
@@ -49,6 +136,9 @@ impl Generate for MemberExpr {
         if self.deref && !obj.get_type().matches(&scope.resolve_type(&obj_ty, ctx)) {
             obj = obj.try_load(&scope.resolve_type(&obj_ty, ctx), ctx.builder);
         }
+        if self.deref {
+            super::checks::gen_null_check(&obj, self.obj.loc(), ctx);
+        }
 
         if let Type::Pointer(ptr) = &obj_ty {
             if ptr.is_ref {
@@ -56,35 +146,18 @@ impl Generate for MemberExpr {
                 obj = obj.try_load(&scope.resolve_type(base, ctx), ctx.builder);
             }
         }
-        let type_name = obj_ty.get_name();
-        let struct_fields =scope.get_struct(&Tagged::new(self.obj.loc().clone(), type_name.clone()));
-        let index = if !self.use_index {
-            let mut index_internal = None;
-            let member_name = self.member.as_ref().unwrap().get_value();
-            for (i, field) in struct_fields.fields.iter().enumerate() {
-                if field.id.inner().clone() == member_name {
-                    index_internal = Some(i);
-                }
+        if let Some(slice) = self.slice_type(&obj_ty) {
+            let (index, _) = self.slice_field(&slice);
+            if !obj.get_type().is_pointer_ty() {
+                let alloc = ctx.builder.create_alloca(&obj.get_type(), None);
+                ctx.builder.create_store(&obj, &alloc);
+                obj = alloc;
             }
-
-            if index_internal.is_none() {
-                let mut fields_avail = "available fieds: [".to_string();
-                for (i, field) in struct_fields.fields.iter().enumerate() {
-                    fields_avail += &("`".to_string() + field.id.inner() + "`");
-                    if i + 1 != struct_fields.fields.len() {
-                        fields_avail += ", ";
-                    }
-                }
-                fields_avail += "]";
-                error_msg_label_info(
-                    format!("unknown field `{}` in type `{}`", member_name, type_name).as_str(),
-                    ErrorLabel::from(self.member.as_ref().unwrap().loc(), "unknown field"),
-                    &fields_avail
-                );
-            }
-            index_internal.unwrap()
-        }else {
-            self.index
+            return Some(ctx.builder.create_struct_gep(&scope.resolve_type(&Type::Slice(slice), ctx), &obj, index as u32));
+        }
+        let index = match self.tuple_type(&obj_ty) {
+            Some(tuple) => self.tuple_index(&tuple),
+            None => self.field_index(&obj_ty, scope),
         };
 
         if let Some(mem) = &self.member {
@@ -137,36 +210,14 @@ impl Generate for MemberExpr {
 
     fn get_type(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<crate::types::Type> {
         let obj_ty = self.obj.get_type(scope, ctx).unwrap();
-        let struct_fields = &scope.get_struct(&Tagged::new(self.obj.loc().clone(), obj_ty.get_name())).fields;
-        let index = if !self.use_index {
-            let mut index_internal = None;
-            let member_name = self.member.as_ref().unwrap().get_value();
-            for (i, field) in struct_fields.iter().enumerate() {
-                if field.id.inner().clone() == member_name {
-                    index_internal = Some(i);
-                }
-            }
-
-            if index_internal.is_none() {
-                let mut fields_avail = "available fieds: [".to_string();
-                for (i, field) in struct_fields.iter().enumerate() {
-                    fields_avail += &("`".to_string() + field.id.inner() + "`");
-                    if i + 1 != struct_fields.len() {
-                        fields_avail += ", ";
-                    }
-                }
-                fields_avail += "]";
-                error_msg_label_info(
-                    format!("unknown field `{}` in type `{}`", member_name, obj_ty.get_name()).as_str(),
-                    ErrorLabel::from(self.member.as_ref().unwrap().loc(), "unknown field"),
-                    &fields_avail
-                );
-            }
-            index_internal.unwrap()
-        }else {
-            self.index
-        };
-        return Some(struct_fields[index].typ.clone());
+        if let Some(tuple) = self.tuple_type(&obj_ty) {
+            return Some(tuple.elems[self.tuple_index(&tuple)].clone());
+        }
+        if let Some(slice) = self.slice_type(&obj_ty) {
+            return Some(self.slice_field(&slice).1);
+        }
+        let index = self.field_index(&obj_ty, scope);
+        return Some(scope.get_struct(&Tagged::new(self.obj.loc().clone(), obj_ty.get_name())).fields[index].typ.clone());
     }
 
     fn collect_symbols(&mut self, _scope: &mut super::Scope) {}
@@ -174,7 +225,7 @@ impl Generate for MemberExpr {
 
 #[derive(Debug)]
 pub struct AccessExpr {
-    obj: Box<AST>,
+    pub obj: Box<AST>,
     index: Box<AST>,
 }
 
@@ -182,6 +233,118 @@ impl AccessExpr {
     pub fn new(obj: Box<AST>, index: Box<AST>) -> Self {
         Self{ obj, index }
     }
+
+    fn index_method(&self, strct: &Tagged<String>, scope: &super::Scope) -> symbol::Function {
+        let op = Tagged::new(self.index.loc().clone(), "[]".to_owned());
+        let func = super::binary::operator_method(strct, "Index", "index", &op, scope);
+        if func.ret_type.get_base().is_none() {
+            error_msg_label(
+                format!("`{}::index` has to return a pointer to the element", strct.inner()).as_str(),
+                ErrorLabel::from(&func.ret_type.get_loc(), format!("found `{}`", func.ret_type.get_full_name())),
+            );
+        }
+        return func;
+    }
+
+    /*
+     *  NOTE(S): `obj[idx]` on a struct calls `Type_index(&obj, idx)`, the returned pointer is used
+     *  as the address of the element
+     */
+    fn gen_overloaded(&mut self, strct: &Tagged<String>, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+        let func = self.index_method(strct, scope);
+        let obj = super::binary::gen_operand(&mut self.obj, scope, ctx);
+
+        let mut index = self.index.gen_code(scope, ctx).unwrap();
+        let index_ty = self.index.get_type(scope, ctx).unwrap();
+        if self.index.should_load() {
+            index = index.try_load(&scope.resolve_type(&index_ty, ctx), ctx.builder);
+        }
+        let param = &func.args[1].typ;
+        let param_ty = scope.resolve_type(param, ctx);
+        index = ctx.builder.create_cast(crate::ast::get_cast_ops(self.index.loc(), &index.get_type(), &param.get_loc(), &param_ty), &index, &param_ty);
+
+        return super::binary::gen_operator_call(&func, &[obj, index], scope, ctx);
+    }
+
+    /*
+     *  returns the array or slice that is indexed, looking through references
+     */
+    fn buffer_type(obj_ty: &Type) -> Option<&Type> {
+        let buffer = match obj_ty {
+            Type::Pointer(ptr) if ptr.is_ref => &*ptr.typ,
+            ty => ty,
+        };
+        return match buffer {
+            Type::Array(_) | Type::Slice(_) => Some(buffer),
+            _ => None,
+        };
+    }
+
+    /*
+     *  returns the element type, the pointer to the first element and the length of an array or
+     *  a slice
+     */
+    fn gen_buffer(&mut self, obj_ty: &Type, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<(Type, llvm::ValueRef, llvm::ValueRef)> {
+        let buffer = Self::buffer_type(obj_ty)?.clone();
+        let mut obj = super::binary::gen_operand(&mut self.obj, scope, ctx);
+        if let Type::Pointer(_) = obj_ty {
+            obj = obj.try_load(&scope.resolve_type(obj_ty, ctx), ctx.builder);
+        }
+
+        let buffer_ty = scope.resolve_type(&buffer, ctx);
+        let len_ty = llvm::TypeRef::get_int(ctx.ctx, 64);
+        return match buffer {
+            Type::Array(arr) => {
+                let zero = llvm::ConstantInt::get(&len_ty, 0);
+                let data = ctx.builder.create_gep(&buffer_ty, &obj, &[zero.clone(), zero], true);
+                Some((*arr.typ, data, llvm::ConstantInt::get(&len_ty, arr.elems as i32)))
+            },
+            Type::Slice(slice) => {
+                let data_ty = llvm::TypeRef::get_ptr(scope.resolve_type(&slice.typ, ctx), 0);
+                let data = ctx.builder.create_struct_gep(&buffer_ty, &obj, 0).try_load(&data_ty, ctx.builder);
+                let len = ctx.builder.create_struct_gep(&buffer_ty, &obj, 1).try_load(&len_ty, ctx.builder);
+                Some((*slice.typ, data, len))
+            },
+            _ => unreachable!(),
+        };
+    }
+
+    /*
+     *  NOTE(S): `s[lo..hi]` creates a new slice into the same buffer, the bounds are checked at
+     *  runtime. the result is a temporary so it can be loaded like any other element
+     */
+    fn gen_subslice(&mut self, elem: Type, data: llvm::ValueRef, len: llvm::ValueRef, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+        let loc = self.index.loc().clone();
+        let AST::RangeExpr(range) = &mut *self.index else { unreachable!() };
+        let lo = gen_index(&mut range.lo, scope, ctx);
+        let mut hi = gen_index(&mut range.hi, scope, ctx);
+        if range.inclusive {
+            hi = ctx.builder.create_add(&hi, &llvm::ConstantInt::get(&hi.get_type(), 1));
+        }
+
+        let ok = ctx.builder.create_and(&ctx.builder.create_icmp_ule(&lo, &hi), &ctx.builder.create_icmp_ule(&hi, &len));
+        super::checks::gen_check(&ok, "range out of bounds: the len is %lu but the range is %lu..%lu", &[len, lo.clone(), hi.clone()], &loc, ctx);
+
+        let elem_ty = scope.resolve_type(&elem, ctx);
+        let slice_ty = scope.resolve_type(&Type::Slice(ArraySlice::new(loc, elem)), ctx);
+        let slice = ctx.builder.create_alloca(&slice_ty, None);
+        ctx.builder.create_store(&ctx.builder.create_gep(&elem_ty, &data, &[lo.clone()], true), &ctx.builder.create_struct_gep(&slice_ty, &slice, 0));
+        ctx.builder.create_store(&ctx.builder.create_sub(&hi, &lo), &ctx.builder.create_struct_gep(&slice_ty, &slice, 1));
+        return slice;
+    }
+}
+
+/*
+ *  generates an index or a bound of a range as `i64`
+ */
+fn gen_index(index: &mut AST, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+    let index_ty = index.get_type(scope, ctx).unwrap();
+    let mut value = index.gen_code(scope, ctx).unwrap();
+    if index.should_load() {
+        value = value.try_load(&scope.resolve_type(&index_ty, ctx), ctx.builder);
+    }
+    let len_ty = llvm::TypeRef::get_int(ctx.ctx, 64);
+    return ctx.builder.create_cast(crate::ast::get_cast_ops(index.loc(), &value.get_type(), index.loc(), &len_ty), &value, &len_ty);
 }
 
 impl Generate for AccessExpr {
@@ -194,16 +357,29 @@ impl Generate for AccessExpr {
     }
 
     fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
-        let mut obj = self.obj.gen_code(scope, ctx).unwrap();
-        let mut obj_type = self.obj.get_type(scope, ctx).unwrap();
-        /*if !crate::types::is_array_type(&obj.get_type()) {
-            obj = obj.try_load(ctx.builder);
-        }*/
-        match self.obj.get_type(scope, ctx).unwrap() {
-            Type::Pointer(ptr) => {
-                obj = obj.try_load(&scope.resolve_type(&Type::Pointer(ptr), ctx), ctx.builder);
+        let obj_ty = self.obj.get_type(scope, ctx).unwrap();
+        if let Some(strct) = super::binary::overloaded_struct(&obj_ty, scope) {
+            return Some(self.gen_overloaded(&strct, scope, ctx));
+        }
+
+        /*
+         *  NOTE(S): slice accesses are always bounds checked, arrays only with `--checks`
+         */
+        if let Some((elem, data, len)) = self.gen_buffer(&obj_ty, scope, ctx) {
+            if let AST::RangeExpr(_) = &*self.index {
+                return Some(self.gen_subslice(elem, data, len, scope, ctx));
             }
-            _ => {}
+            let index = gen_index(&mut self.index, scope, ctx);
+            if ctx.checks || matches!(AccessExpr::buffer_type(&obj_ty), Some(Type::Slice(_))) {
+                super::checks::gen_check(&ctx.builder.create_icmp_ult(&index, &len), "index out of bounds: the len is %lu but the index is %lu", &[len, index.clone()], self.index.loc(), ctx);
+            }
+            return Some(ctx.builder.create_gep(&scope.resolve_type(&elem, ctx), &data, &[index], true));
+        }
+
+        let mut obj = self.obj.gen_code(scope, ctx).unwrap();
+        let mut obj_type = obj_ty;
+        if let Type::Pointer(ptr) = &obj_type {
+            obj = obj.try_load(&scope.resolve_type(&Type::Pointer(ptr.clone()), ctx), ctx.builder);
         }
 
         let mut index = self.index.gen_code(scope, ctx).unwrap();
@@ -212,28 +388,26 @@ impl Generate for AccessExpr {
             index = index.try_load(&scope.resolve_type(&base, ctx), ctx.builder);
         }
 
-        let zero = llvm::ConstantInt::get(&llvm::TypeRef::get_int(ctx.ctx, 64), 0);
-
-        let mut idx_list = vec![index];
-        if let Type::Array(_) = &obj_type {
-            idx_list.insert(0, zero);
-        }
-
         if let Type::Pointer(ptr) = obj_type {
             obj_type = *ptr.typ.clone();
         }
 
-        return Some(ctx.builder.create_gep(&scope.resolve_type(&obj_type, ctx), &obj, &idx_list, true));
-
-        //error_msg_label(
-        //    "cannot deref value type",
-        //    ErrorLabel::from(self.loc(), format!("cannot deref value type `{}`", self.obj.get_type(scope, ctx).unwrap().get_full_name()).as_str())
-        //);
+        return Some(ctx.builder.create_gep(&scope.resolve_type(&obj_type, ctx), &obj, &[index], true));
     }
 
     fn get_type(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<crate::types::Type> {
-        match self.obj.get_type(scope, ctx).unwrap() {
-            Type::Array(arr) => return Some(*arr.typ),
+        let obj_ty = self.obj.get_type(scope, ctx).unwrap();
+        if let Some(strct) = super::binary::overloaded_struct(&obj_ty, scope) {
+            return self.index_method(&strct, scope).ret_type.get_base().cloned();
+        }
+        if let Some(buffer) = Self::buffer_type(&obj_ty) {
+            let elem = buffer.get_base().unwrap().clone();
+            if let AST::RangeExpr(_) = &*self.index {
+                return Some(Type::Slice(ArraySlice::new(self.index.loc().clone(), elem)));
+            }
+            return Some(elem);
+        }
+        match obj_ty {
             Type::Pointer(ptr) => return Some(*ptr.typ),
             ty => return Some(ty),
         }
@@ -252,6 +426,47 @@ impl ResolutionExpr {
     pub fn new(obj: Box<AST>, member: Box<AST>) -> Self {
         Self{ obj, member }
     }
+
+    fn construct_variant(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext, ev: &symbol::Enum, index: usize, tag: llvm::ValueRef) -> llvm::ValueRef {
+        let variant = &ev.variants[index];
+        let args = match &mut *self.member {
+            AST::CallExpr(call) => &mut call.args,
+            _ => &mut Vec::new(),
+        };
+
+        if args.len() != variant.fields.len() {
+            error_msg_labels(
+                format!("variant `{}::{}` has `{}` fields, but `{}` were supplied", ev.name.inner(), variant.id.inner(), variant.fields.len(), args.len()).as_str(), &[
+                ErrorLabel::from(self.member.loc(), "incorrect number of fields supplied"),
+                ErrorLabel::from(variant.id.loc(), "variant defined here"),
+            ]);
+        }
+
+        let enum_ty = scope.resolve_type(&Type::Path(Path::new(ev.name.loc().clone(), ev.name.inner().clone())), ctx);
+        let variant_ty = scope.resolve_variant_type(ev, index, ctx);
+        let alloca = ctx.builder.create_alloca(&enum_ty, None);
+        ctx.builder.create_store(&tag, &ctx.builder.create_struct_gep(&enum_ty, &alloca, 0));
+        let payload = ctx.builder.create_struct_gep(&enum_ty, &alloca, 1);
+        let payload = ctx.builder.create_pointer_cast(&payload, &llvm::TypeRef::get_ptr(variant_ty.clone(), 0));
+
+        for (i, (arg, field)) in args.iter_mut().zip(variant.fields.iter()).enumerate() {
+            let arg_ty = arg.get_type(scope, ctx).unwrap();
+            if !arg_ty.matches(&field.typ) {
+                error_msg_labels(
+                    format!("invalid field type for variant `{}::{}`", ev.name.inner(), variant.id.inner()).as_str(), &[
+                    ErrorLabel::from(arg.loc(), format!("provided value has type: `{}`", arg_ty.get_full_name())),
+                    ErrorLabel::from(&field.typ.get_loc(), format!("expected type is: `{}`", field.typ.get_full_name())),
+                ]);
+            }
+            let mut value = arg.gen_code(scope, ctx).unwrap();
+            if arg.should_load() {
+                value = value.try_load(&scope.resolve_type(&arg_ty, ctx), ctx.builder);
+            }
+            ctx.builder.create_store(&value, &ctx.builder.create_struct_gep(&variant_ty, &payload, i as u32));
+        }
+
+        return ctx.builder.create_load(&enum_ty, &alloca);
+    }
 }
 
 impl Generate for ResolutionExpr {
@@ -267,11 +482,13 @@ impl Generate for ResolutionExpr {
         let member_value = self.member.get_value();
         let tagged = Tagged::new(self.obj.loc().clone(), self.obj.get_value());
         if scope.is_enum(&tagged) {
-            let ev = scope.get_enum(&tagged);
-            for (i, var) in ev.variants.iter().enumerate() {
-                if *var == member_value {
-                    return Some(llvm::ConstantInt::get(&scope.resolve_type(&ev.typ, ctx), i as i32));
+            let ev = scope.get_enum(&tagged).clone();
+            if let Some((i, _)) = ev.get_variant(&member_value) {
+                let tag = llvm::ConstantInt::get(&scope.resolve_type(&ev.typ, ctx), i as i32);
+                if !ev.has_payload() && !matches!(*self.member, AST::CallExpr(_)) {
+                    return Some(tag);
                 }
+                return Some(self.construct_variant(scope, ctx, &ev, i, tag));
             }
             error_msg_label(
                 format!("unknown enum variant `{}` in enum `{}`", member_value, tagged.inner()).as_str(),
@@ -1,5 +1,6 @@
 use crate::ast::{Generate, AST};
-use crate::lexer::Loc;
+use crate::ast::binary::{gen_binary_op, gen_operand, gen_operator_call, operator_method, operator_trait, overloaded_struct};
+use crate::lexer::{Loc, Tagged};
 use crate::log::{error_msg_labels, ErrorLabel};
 use crate::types::Type;
 
@@ -8,11 +9,47 @@ pub struct AssignmentExpr {
     loc: Loc,
     lhs: Box<AST>,
     rhs: Box<AST>,
+    /*
+     *  the binary operator of a compound assignment, `+` for `a += b`
+     */
+    op: Option<Tagged<String>>,
 }
 
 impl AssignmentExpr {
     pub fn new(lhs: Box<AST>, rhs: Box<AST>) -> Self {
-        Self{ loc: lhs.loc().clone() + rhs.loc().clone(), lhs, rhs }
+        Self{ loc: lhs.loc().clone() + rhs.loc().clone(), lhs, rhs, op: None }
+    }
+
+    pub fn new_compound(lhs: Box<AST>, rhs: Box<AST>, op: Tagged<String>) -> Self {
+        Self{ loc: lhs.loc().clone() + rhs.loc().clone(), lhs, rhs, op: Some(op) }
+    }
+
+    fn type_mismatch(&self, lhs_ty: &Type, rhs_ty: &Type) -> ! {
+        error_msg_labels(
+            "mismatched types", &[
+                ErrorLabel::from(self.lhs.loc(), format!("expected type `{}`", lhs_ty.get_full_name()).as_str()),
+                ErrorLabel::from(self.rhs.loc(), format!("but value has type `{}`", rhs_ty.get_full_name()).as_str()),
+        ]);
+    }
+
+    /*
+     *  NOTE(S): the place is only generated once, `a[f()] += 1` calls `f` a single time
+     */
+    fn gen_compound(&mut self, op: &Tagged<String>, var: &llvm::ValueRef, lhs_ty: &Type, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+        if let Some(strct) = overloaded_struct(lhs_ty, scope) {
+            let (trat, method) = operator_trait(op);
+            let func = operator_method(&strct, trat, method, op, scope);
+            let rhs = gen_operand(&mut self.rhs, scope, ctx);
+            return gen_operator_call(&func, &[var.clone(), rhs], scope, ctx);
+        }
+
+        let current = ctx.builder.create_load(&scope.resolve_type(lhs_ty, ctx), var);
+        let mut rhs = self.rhs.gen_code(scope, ctx).unwrap();
+        if self.rhs.should_load() {
+            let base = self.rhs.get_type(scope, ctx).unwrap();
+            rhs = rhs.try_load(&scope.resolve_type(&base, ctx), ctx.builder);
+        }
+        return gen_binary_op(op, lhs_ty, &current, &rhs, scope, ctx);
     }
 }
 
@@ -27,6 +64,19 @@ impl Generate for AssignmentExpr {
 
     fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
         let var = self.lhs.gen_code(scope, ctx).unwrap();
+        super::lints::mark_assigned(&self.lhs, scope);
+
+        if let Some(op) = self.op.clone() {
+            let lhs_ty = self.lhs.get_type(scope, ctx).unwrap();
+            let rhs_ty = self.rhs.get_type(scope, ctx).unwrap();
+            if !lhs_ty.matches(&rhs_ty) {
+                self.type_mismatch(&lhs_ty, &rhs_ty);
+            }
+            let value = self.gen_compound(&op, &var, &lhs_ty, scope, ctx);
+            scope.gen_reinit(&self.lhs, true, ctx);
+            ctx.builder.create_store(&value, &var);
+            return Some(var);
+        }
 
         if let AST::InitializerList(list) = &mut *self.rhs {
             list.set_typ(self.lhs.get_type(scope, ctx));
@@ -41,14 +91,14 @@ impl Generate for AssignmentExpr {
 
         let lhs_ty = self.lhs.get_type(scope, ctx).unwrap();
         let rhs_ty = self.rhs.get_type(scope, ctx).unwrap();
-        if !lhs_ty.matches(&rhs_ty) {
-            error_msg_labels(
-                "missmatched types", &[
-                    ErrorLabel::from(self.lhs.loc(), format!("expected type `{}`", lhs_ty.get_full_name()).as_str()),
-                    ErrorLabel::from(self.rhs.loc(), format!("but value has type `{}`", rhs_ty.get_full_name()).as_str()),
-            ]);
+        if let Some(coerced) = crate::ast::coerce(&value, &rhs_ty, &lhs_ty, self.rhs.loc(), scope, ctx) {
+            value = coerced;
+        }else if !lhs_ty.matches(&rhs_ty) {
+            self.type_mismatch(&lhs_ty, &rhs_ty);
         }
 
+        scope.gen_move(&self.rhs, ctx);
+        scope.gen_reinit(&self.lhs, scope.owns_value(&self.rhs), ctx);
         ctx.builder.create_store(&value, &var);
         return Some(var);
     }
@@ -11,6 +11,7 @@ pub mod ast {
         Mod(Mod),
         Function(Function),
         VarDecl(VarDecl),
+        TupleDecl(TupleDecl),
 
         AssignmentExpr(AssignmentExpr),
         MemberExpr(MemberExpr),
@@ -25,14 +26,21 @@ pub mod ast {
 
         IfStmt(IfStmt),
         ForStmt(ForStmt),
-        MatchStmt(),
+        ForInStmt(ForInStmt),
+        WhileStmt(WhileStmt),
+        MatchStmt(MatchStmt),
         BreakExpr(BreakExpr),
+        ContinueExpr(ContinueExpr),
+        DeferStmt(DeferStmt),
+        RangeExpr(RangeExpr),
         ClosureExpr(ClosureExpr),
+        TupleExpr(TupleExpr),
 
         StructDef(StructDef),
         EnumDef(EnumDef),
         Impl(Impl),
         Trait(Trait),
+        Generic(Generic),
         Macro(Macro),
         MacroCall(MacroCall),
         TypeAlias(TypeAlias),
@@ -44,6 +52,31 @@ pub mod ast {
     }
 
     impl AST {
+        /*
+         *  NOTE(S): an error while generating a statement only poisons that statement, the state
+         *  of the loops and the current function is reset so the following ones are still checked.
+         *  the variables of a poisoned `let` are still declared, see `VarDecl::declare_poisoned`
+         */
+        pub fn gen_stmt(&mut self, scope: &mut Scope, ctx: &crate::GenerationContext) {
+            let current_function = ctx.current_function;
+            let loops = ctx.current_loop_label.len();
+            if crate::log::recover(|| self.gen_code(scope, ctx)).is_some() {
+                return;
+            }
+            let ctx = unsafe{ crate::types::containers::to_mut(ctx) };
+            ctx.current_function = current_function;
+            ctx.current_break_point.truncate(loops);
+            ctx.current_continue_point.truncate(loops);
+            ctx.current_loop_label.truncate(loops);
+            ctx.current_loop_scope.truncate(loops);
+
+            match self {
+                AST::VarDecl(decl) => decl.declare_poisoned(scope, ctx),
+                AST::TupleDecl(decl) => decl.declare_poisoned(scope, ctx),
+                _ => {},
+            }
+        }
+
         pub fn should_load(&self) -> bool {
             if let AST::MemberExpr(m) = self {
                 if let Some(member) = &m.member {
@@ -79,11 +112,20 @@ pub mod ast {
                 AST::BinaryExpr(bin) => bin.loc(),
                 AST::IfStmt(i) => i.loc(),
                 AST::ForStmt(f) => f.loc(),
+                AST::ForInStmt(f) => f.loc(),
+                AST::WhileStmt(w) => w.loc(),
+                AST::MatchStmt(m) => m.loc(),
                 AST::BreakExpr(b) => b.loc(),
+                AST::ContinueExpr(c) => c.loc(),
+                AST::DeferStmt(d) => d.loc(),
+                AST::TupleExpr(t) => t.loc(),
+                AST::TupleDecl(t) => t.loc(),
+                AST::RangeExpr(r) => r.loc(),
                 AST::ClosureExpr(c) => c.loc(),
                 AST::StructDef(def) => def.loc(),
                 AST::EnumDef(en) => en.loc(),
                 AST::Impl(i) => i.loc(),
+                AST::Generic(g) => g.loc(),
                 AST::Trait(t) => t.loc(),
                 AST::MemberCallExpr(call) => call.loc(),
                 AST::AccessExpr(acc) => acc.loc(),
@@ -97,7 +139,6 @@ pub mod ast {
                 AST::StaticLiteral(stat) => stat.loc(),
                 AST::Id(id) => id.loc(),
                 //AST::Block(b) => b.loc(),
-                _ => todo!("loc -> {:#?}", self),
             }
         }
 
@@ -115,11 +156,20 @@ pub mod ast {
                 AST::BinaryExpr(bin) => bin.get_value(),
                 AST::IfStmt(i) => i.get_value(),
                 AST::ForStmt(f) => f.get_value(),
+                AST::ForInStmt(f) => f.get_value(),
+                AST::WhileStmt(w) => w.get_value(),
+                AST::MatchStmt(m) => m.get_value(),
                 AST::BreakExpr(b) => b.get_value(),
+                AST::ContinueExpr(c) => c.get_value(),
+                AST::DeferStmt(d) => d.get_value(),
+                AST::TupleExpr(t) => t.get_value(),
+                AST::TupleDecl(t) => t.get_value(),
+                AST::RangeExpr(r) => r.get_value(),
                 AST::ClosureExpr(c) => c.get_value(),
                 AST::StructDef(def) => def.get_value(),
                 AST::EnumDef(en) => en.get_value(),
                 AST::Impl(i) => i.get_value(),
+                AST::Generic(g) => g.get_value(),
                 AST::Trait(t) => t.get_value(),
                 AST::MemberCallExpr(call) => call.get_value(),
                 AST::AccessExpr(acc) => acc.get_value(),
@@ -132,7 +182,6 @@ pub mod ast {
                 AST::InitializerList(list) => list.get_value(),
                 AST::StaticLiteral(stat) => stat.get_value(),
                 AST::Id(id) => id.get_value(),
-                _ => todo!("get_value -> {:#?}", self),
             }
         }
 
@@ -150,11 +199,20 @@ pub mod ast {
                 AST::BinaryExpr(bin) => bin.gen_code(scope, ctx),
                 AST::IfStmt(i) => i.gen_code(scope, ctx),
                 AST::ForStmt(f) => f.gen_code(scope, ctx),
+                AST::ForInStmt(f) => f.gen_code(scope, ctx),
+                AST::WhileStmt(w) => w.gen_code(scope, ctx),
+                AST::MatchStmt(m) => m.gen_code(scope, ctx),
                 AST::BreakExpr(b) => b.gen_code(scope, ctx),
+                AST::ContinueExpr(c) => c.gen_code(scope, ctx),
+                AST::DeferStmt(d) => d.gen_code(scope, ctx),
+                AST::TupleExpr(t) => t.gen_code(scope, ctx),
+                AST::TupleDecl(t) => t.gen_code(scope, ctx),
+                AST::RangeExpr(r) => r.gen_code(scope, ctx),
                 AST::ClosureExpr(c) => c.gen_code(scope, ctx),
                 AST::StructDef(def) => def.gen_code(scope, ctx),
                 AST::EnumDef(en) => en.gen_code(scope, ctx),
                 AST::Impl(i) => i.gen_code(scope, ctx),
+                AST::Generic(g) => g.gen_code(scope, ctx),
                 AST::Trait(t) => t.gen_code(scope, ctx),
                 AST::MemberCallExpr(call) => call.gen_code(scope, ctx),
                 AST::AccessExpr(acc) => acc.gen_code(scope, ctx),
@@ -167,7 +225,6 @@ pub mod ast {
                 AST::InitializerList(list) => list.gen_code(scope, ctx),
                 AST::StaticLiteral(stat) => stat.gen_code(scope, ctx),
                 AST::Id(id) => id.gen_code(scope, ctx),
-                _ => todo!("gen_code -> {:#?}", self),
             }
         }
 
@@ -185,11 +242,20 @@ pub mod ast {
                 AST::BinaryExpr(bin) => bin.get_type(scope, ctx),
                 AST::IfStmt(i) => i.get_type(scope, ctx),
                 AST::ForStmt(f) => f.get_type(scope, ctx),
+                AST::ForInStmt(f) => f.get_type(scope, ctx),
+                AST::WhileStmt(w) => w.get_type(scope, ctx),
+                AST::MatchStmt(m) => m.get_type(scope, ctx),
                 AST::BreakExpr(b) => b.get_type(scope, ctx),
+                AST::ContinueExpr(c) => c.get_type(scope, ctx),
+                AST::DeferStmt(d) => d.get_type(scope, ctx),
+                AST::TupleExpr(t) => t.get_type(scope, ctx),
+                AST::TupleDecl(t) => t.get_type(scope, ctx),
+                AST::RangeExpr(r) => r.get_type(scope, ctx),
                 AST::ClosureExpr(c) => c.get_type(scope, ctx),
                 AST::StructDef(def) => def.get_type(scope, ctx),
                 AST::EnumDef(en) => en.get_type(scope, ctx),
                 AST::Impl(i) => i.get_type(scope, ctx),
+                AST::Generic(g) => g.get_type(scope, ctx),
                 AST::Trait(t) => t.get_type(scope, ctx),
                 AST::CallExpr(call) => call.get_type(scope, ctx),
                 AST::MacroCall(call) => call.get_type(scope, ctx),
@@ -202,7 +268,6 @@ pub mod ast {
                 AST::InitializerList(list) => list.get_type(scope, ctx),
                 AST::StaticLiteral(stat) => stat.get_type(scope, ctx),
                 AST::Id(id) => id.get_type(scope, ctx),
-                _ => todo!("get_type -> {:#?}", self),
             }
         }
 
@@ -219,6 +284,7 @@ pub mod ast {
                 AST::EnumDef(v) => v.collect_symbols(scope),
                 AST::Function(v) => v.collect_symbols(scope),
                 AST::Impl(v) => v.collect_symbols(scope),
+                AST::Generic(v) => v.collect_symbols(scope),
                 AST::Macro(_) => return,
                 AST::MacroCall(v) => v.collect_symbols(scope),
                 AST::Trait(v) => v.collect_symbols(scope),
@@ -246,6 +312,14 @@ pub mod ast {
     pub struct Block {
         pub body: Exprs,
         pub returns: bool,
+        /*
+         *  the last expression is not terminated by `;` and is the value of the block
+         */
+        pub yields: bool,
+        /*
+         *  the statements after one that never falls through, they are checked but not generated
+         */
+        pub unreachable: Exprs,
         pub scope: Scope,
     }
 
@@ -254,6 +328,8 @@ pub mod ast {
             Self {
                 body: Vec::new(),
                 returns: false,
+                yields: false,
+                unreachable: Vec::new(),
                 scope: Scope::new(),
             }
         }
@@ -262,6 +338,8 @@ pub mod ast {
             Self {
                 body: exprs,
                 returns: false,
+                yields: false,
+                unreachable: Vec::new(),
                 scope: Scope::new(),
             }
         }
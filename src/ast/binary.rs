@@ -1,7 +1,7 @@
 use crate::lexer::{Tagged, Loc};
-use crate::ast::{AST, Generate};
+use crate::ast::{AST, Generate, symbol};
 use crate::types::{Type, Path};
-use crate::log::{ErrorLabel, error_msg_label, error_msg_labels};
+use crate::log::{ErrorLabel, error_msg_label, error_msg_labels, error_msg_label_info};
 
 #[derive(Debug)]
 pub struct BinaryExpr {
@@ -15,6 +15,223 @@ impl BinaryExpr {
     pub fn new(lhs: Box<AST>, rhs: Box<AST>, op: Tagged<String>) -> Self {
         Self{ loc: lhs.loc().clone() + rhs.loc().clone() , lhs, rhs, op }
     }
+
+    fn gen_condition(operand: &mut AST, op: &Tagged<String>, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+        let ty = operand.get_type(scope, ctx).unwrap();
+        if !matches!(scope.resolve_alias(&ty), Type::Path(path) if path.path == "bool") {
+            error_msg_labels(
+                format!("invalid operand to binary operator `{}`", op.inner()).as_str(), &[
+                    ErrorLabel::from(operand.loc(), format!("expected type `bool`, found `{}`", ty.get_full_name())),
+                    ErrorLabel::from(op.loc(), "operands of logical operators have to be of type `bool`"),
+                ]
+            );
+        }
+        let mut value = operand.gen_code(scope, ctx).unwrap();
+        if operand.should_load() {
+            value = value.try_load(&scope.resolve_type(&ty, ctx), ctx.builder);
+        }
+        return value;
+    }
+
+    /*
+     *  NOTE(S): `&&` and `||` only evaluate the right hand side if the left hand side does not
+     *  already decide the result, the results of both paths are merged with a phi
+     */
+    fn gen_logical(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+        let func = ctx.builder.get_insert_block().get_parent();
+        let is_and = self.op.inner() == "&&";
+
+        let lhs = Self::gen_condition(&mut self.lhs, &self.op, scope, ctx);
+        let lhs_block = ctx.builder.get_insert_block();
+        let rhs_block = llvm::BasicBlock::new(ctx.ctx, "rhs", Some(&func), None);
+        let merge_block = llvm::BasicBlock::new(ctx.ctx, "m", Some(&func), None);
+        if is_and {
+            ctx.builder.create_cond_br(&lhs, &rhs_block, &merge_block);
+        }else {
+            ctx.builder.create_cond_br(&lhs, &merge_block, &rhs_block);
+        }
+
+        ctx.builder.set_insert_point(&rhs_block);
+        let rhs = Self::gen_condition(&mut self.rhs, &self.op, scope, ctx);
+        let rhs_block = ctx.builder.get_insert_block();
+        ctx.builder.create_br(&merge_block);
+
+        ctx.builder.set_insert_point(&merge_block);
+        let bool_ty = llvm::TypeRef::get_int(&ctx.ctx, 1);
+        let phi = ctx.builder.create_phi(&bool_ty, 2);
+        phi.add_incoming(&llvm::ConstantInt::get(&bool_ty, if is_and { 0 } else { 1 }), &lhs_block);
+        phi.add_incoming(&rhs, &rhs_block);
+        return phi.into();
+    }
+
+    fn gen_overloaded(&mut self, strct: &Tagged<String>, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+        let (trat, method) = operator_trait(&self.op);
+        let func = operator_method(strct, trat, method, &self.op, scope);
+        let lhs = gen_operand(&mut self.lhs, scope, ctx);
+        let rhs = gen_operand(&mut self.rhs, scope, ctx);
+        let value = gen_operator_call(&func, &[lhs, rhs], scope, ctx);
+
+        let zero = llvm::ConstantInt::get(&value.get_type(), 0);
+        return match self.op.inner().as_str() {
+            "!=" => ctx.builder.create_xor_vl(&value, 1),
+            "<" => ctx.builder.create_icmp_slt(&value, &zero),
+            "<=" => ctx.builder.create_icmp_sle(&value, &zero),
+            ">" => ctx.builder.create_icmp_sgt(&value, &zero),
+            ">=" => ctx.builder.create_icmp_sge(&value, &zero),
+            _ => value,
+        };
+    }
+}
+
+/*
+ *  the trait and method a binary operator on a struct gets dispatched to
+ */
+pub fn operator_trait(op: &Tagged<String>) -> (&'static str, &'static str) {
+    return match op.inner().as_str() {
+        "+" => ("Add", "add"),
+        "-" => ("Sub", "sub"),
+        "*" => ("Mul", "mul"),
+        "/" => ("Div", "div"),
+        "==" | "!=" => ("Eq", "eq"),
+        "<" | "<=" | ">" | ">=" => ("Ord", "cmp"),
+        _ => error_msg_label(
+                format!("binary operator `{}` cannot be overloaded", op.inner()).as_str(),
+                ErrorLabel::from(op.loc(), "operator cannot be applied to a struct"),
+        ),
+    };
+}
+
+/*
+ *  lowers `lhs op rhs` for two already loaded values of type `typ`
+ */
+pub fn gen_binary_op(op: &Tagged<String>, typ: &Type, lhs: &llvm::ValueRef, rhs: &llvm::ValueRef, scope: &super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+    let kind = NumberKind::of(typ, scope);
+    if let Some(checked) = super::checks::gen_checked_op(op, kind, lhs, rhs, ctx) {
+        return checked;
+    }
+    return match (op.inner().as_str(), kind) {
+        ("+", NumberKind::Float) => ctx.builder.create_fadd(lhs, rhs),
+        ("-", NumberKind::Float) => ctx.builder.create_fsub(lhs, rhs),
+        ("*", NumberKind::Float) => ctx.builder.create_fmul(lhs, rhs),
+        ("/", NumberKind::Float) => ctx.builder.create_fdiv(lhs, rhs),
+        ("%", NumberKind::Float) => ctx.builder.create_frem(lhs, rhs),
+        ("==", NumberKind::Float) => ctx.builder.create_fcmp_oeq(lhs, rhs),
+        ("!=", NumberKind::Float) => ctx.builder.create_fcmp_une(lhs, rhs),
+        ("<", NumberKind::Float) => ctx.builder.create_fcmp_olt(lhs, rhs),
+        ("<=", NumberKind::Float) => ctx.builder.create_fcmp_ole(lhs, rhs),
+        (">", NumberKind::Float) => ctx.builder.create_fcmp_ogt(lhs, rhs),
+        (">=", NumberKind::Float) => ctx.builder.create_fcmp_oge(lhs, rhs),
+
+        ("&" | "|" | "^" | "<<" | ">>", NumberKind::Float) => error_msg_label(
+                format!("binary operator `{}` cannot be applied to type `{}`", op.inner(), typ.get_full_name()).as_str(),
+                ErrorLabel::from(op.loc(), "only integers support bitwise operations"),
+        ),
+
+        ("/", NumberKind::Unsigned) => ctx.builder.create_udiv(lhs, rhs),
+        ("%", NumberKind::Unsigned) => ctx.builder.create_urem(lhs, rhs),
+        ("<", NumberKind::Unsigned) => ctx.builder.create_icmp_ult(lhs, rhs),
+        ("<=", NumberKind::Unsigned) => ctx.builder.create_icmp_ule(lhs, rhs),
+        (">", NumberKind::Unsigned) => ctx.builder.create_icmp_ugt(lhs, rhs),
+        (">=", NumberKind::Unsigned) => ctx.builder.create_icmp_uge(lhs, rhs),
+        (">>", NumberKind::Unsigned) => ctx.builder.create_lshr(lhs, rhs),
+
+        ("+", _) => ctx.builder.create_add(lhs, rhs),
+        ("-", _) => ctx.builder.create_sub(lhs, rhs),
+        ("*", _) => ctx.builder.create_mul(lhs, rhs),
+        ("/", _) => ctx.builder.create_div(lhs, rhs),
+        ("%", _) => ctx.builder.create_rem(lhs, rhs),
+        ("==", _) => ctx.builder.create_icmp_eq(lhs, rhs),
+        ("!=", _) => ctx.builder.create_icmp_ne(lhs, rhs),
+        ("<", _) => ctx.builder.create_icmp_slt(lhs, rhs),
+        ("<=", _) => ctx.builder.create_icmp_sle(lhs, rhs),
+        (">", _) => ctx.builder.create_icmp_sgt(lhs, rhs),
+        (">=", _) => ctx.builder.create_icmp_sge(lhs, rhs),
+        ("&", _) => ctx.builder.create_and(lhs, rhs),
+        ("|", _) => ctx.builder.create_or(lhs, rhs),
+        ("^", _) => ctx.builder.create_xor_vv(lhs, rhs),
+        ("<<", _) => ctx.builder.create_shl(lhs, rhs),
+        (">>", _) => ctx.builder.create_ashr(lhs, rhs),
+        _ => error_msg_label(
+                format!("unknown binary operator `{}`", op.inner()).as_str(),
+                ErrorLabel::from(op.loc(), "unknown binary operator"),
+        ),
+    };
+}
+
+/*
+ *  decides which llvm instruction an arithmetic operator or comparison lowers to, pointers
+ *  and `bool` compare as unsigned
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberKind {
+    Signed,
+    Unsigned,
+    Float,
+}
+
+impl NumberKind {
+    pub fn of(typ: &Type, scope: &super::Scope) -> Self {
+        return match scope.resolve_alias(typ) {
+            Type::Path(path) => match path.path.as_str() {
+                "u8" | "u16" | "u32" | "u64" | "bool" => NumberKind::Unsigned,
+                "f32" | "f64" => NumberKind::Float,
+                _ => NumberKind::Signed,
+            },
+            Type::Pointer(_) => NumberKind::Unsigned,
+            _ => NumberKind::Signed,
+        };
+    }
+}
+
+/*
+ *  returns the struct an operator applied to a value of type `typ` gets dispatched on
+ */
+pub fn overloaded_struct(typ: &Type, scope: &super::Scope) -> Option<Tagged<String>> {
+    if let Type::Path(path) = typ {
+        let name = Tagged::new(path.loc.clone(), path.path.clone());
+        if scope.is_struct(&name) {
+            return Some(name);
+        }
+    }
+    return None;
+}
+
+/*
+ *  looks up the method implementing an operator, `trat` has to be implemented by `strct`
+ */
+pub fn operator_method(strct: &Tagged<String>, trat: &str, method: &str, op: &Tagged<String>, scope: &super::Scope) -> symbol::Function {
+    let sym = scope.get_struct(strct);
+    if !sym.implements_trait(trat) {
+        error_msg_label_info(
+            format!("cannot apply operator `{}` to type `{}`", op.inner(), strct.inner()).as_str(),
+            ErrorLabel::from(op.loc(), format!("`{}` does not implement `{}`", strct.inner(), trat)),
+            &format!("implement `{}` for `{}` to use this operator", trat, strct.inner()),
+        );
+    }
+    return sym.get_function(op.loc(), method).clone();
+}
+
+/*
+ *  operator methods take their operands by reference, values without an address get stored
+ *  in a temporary
+ */
+pub fn gen_operand(operand: &mut AST, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+    let value = operand.gen_code(scope, ctx).unwrap();
+    if operand.should_load() {
+        return value;
+    }
+    let alloc = ctx.builder.create_alloca(&value.get_type(), None);
+    ctx.builder.create_store(&value, &alloc);
+    return alloc;
+}
+
+pub fn gen_operator_call(func: &symbol::Function, args: &[llvm::ValueRef], scope: &super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+    let r#fn = ctx.module.get_function(&func.name_mangeled).unwrap_or_else(|| {
+        let params: Vec<llvm::TypeRef> = func.args.iter().map(|arg| scope.resolve_type(&arg.typ, ctx)).collect();
+        let fn_type = llvm::FunctionTypeRef::get(scope.resolve_type(&func.ret_type, ctx), &params, false);
+        llvm::Function::create(fn_type, &func.name_mangeled, ctx.module)
+    });
+    return ctx.builder.create_call(&r#fn.into(), args);
 }
 
 impl Generate for BinaryExpr {
@@ -27,52 +244,42 @@ impl Generate for BinaryExpr {
     }
 
     fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
-        let lhs_ty = self.lhs.get_type(scope, ctx).unwrap();
-        let mut lhs = self.lhs.gen_code(scope, ctx).unwrap();
-        let rhs_ty = self.rhs.get_type(scope, ctx).unwrap();
-        let mut rhs = self.rhs.gen_code(scope, ctx).unwrap();
-
-        if self.lhs.should_load() {
-            lhs = lhs.try_load(&scope.resolve_type(&lhs_ty, ctx), ctx.builder);
-        }
-
-        if self.rhs.should_load() {
-            rhs = rhs.try_load(&scope.resolve_type(&rhs_ty, ctx), ctx.builder);
+        if self.op.inner() == "&&" || self.op.inner() == "||" {
+            return Some(self.gen_logical(scope, ctx));
         }
 
         let lhs_ty = self.lhs.get_type(scope, ctx).unwrap();
         let rhs_ty = self.rhs.get_type(scope, ctx).unwrap();
         if !lhs_ty.matches(&rhs_ty) {
             error_msg_labels(
-                format!("invalid operant to binary operator `{}`", self.op.inner()).as_str(), &[
+                format!("invalid operand to binary operator `{}`", self.op.inner()).as_str(), &[
                     ErrorLabel::from(self.lhs.loc(), format!("left hand side has type `{}`", lhs_ty.get_full_name())),
                     ErrorLabel::from(self.rhs.loc(), format!("right hand side has type `{}`", rhs_ty.get_full_name())),
                 ]
             )
         }
 
-        return match self.op.inner().as_str() {
-            "+" => Some(ctx.builder.create_add(&lhs, &rhs)),
-            "-" => Some(ctx.builder.create_sub(&lhs, &rhs)),
-            "*" => Some(ctx.builder.create_mul(&lhs, &rhs)),
-            "/" => Some(ctx.builder.create_div(&lhs, &rhs)),
-            "%" => Some(ctx.builder.create_rem(&lhs, &rhs)),
-            "==" => Some(ctx.builder.create_icmp_eq(&lhs, &rhs)),
-            "!=" => Some(ctx.builder.create_icmp_ne(&lhs, &rhs)),
-            "<" => Some(ctx.builder.create_icmp_slt(&lhs, &rhs)),
-            "<=" => Some(ctx.builder.create_icmp_sle(&lhs, &rhs)),
-            ">" => Some(ctx.builder.create_icmp_sgt(&lhs, &rhs)),
-            ">=" => Some(ctx.builder.create_icmp_sge(&lhs, &rhs)),
-            _ => error_msg_label(
-                    format!("unknown binary operator `{}`", self.op.inner()).as_str(),
-                    ErrorLabel::from(&self.op.loc(), "unknown binary operator"),
-            ),
-        };
+        if let Some(strct) = overloaded_struct(&lhs_ty, scope) {
+            return Some(self.gen_overloaded(&strct, scope, ctx));
+        }
+
+        let mut lhs = self.lhs.gen_code(scope, ctx).unwrap();
+        let mut rhs = self.rhs.gen_code(scope, ctx).unwrap();
+
+        if self.lhs.should_load() {
+            lhs = lhs.try_load(&scope.resolve_type(&lhs_ty, ctx), ctx.builder);
+        }
+
+        if self.rhs.should_load() {
+            rhs = rhs.try_load(&scope.resolve_type(&rhs_ty, ctx), ctx.builder);
+        }
+
+        return Some(gen_binary_op(&self.op, &lhs_ty, &lhs, &rhs, scope, ctx));
     }
 
     fn get_type(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<Type> {
         match self.op.inner().as_str() {
-            "==" | "!=" | "<" | ">" | "<=" | ">=" => return Some(Type::Path(Path::new(self.loc.clone(), "bool".to_owned()))),
+            "==" | "!=" | "<" | ">" | "<=" | ">=" | "&&" | "||" => return Some(Type::Path(Path::new(self.loc.clone(), "bool".to_owned()))),
             _ => self.lhs.get_type(scope, ctx),
         }
     }
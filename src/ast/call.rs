@@ -1,12 +1,13 @@
 use crate::ast::{symbol, symbol::Symbol, FunctionArg, Generate, AST};
 use crate::lexer::{Loc, Tagged};
 use crate::log::*;
-use crate::types::Type;
+use crate::ast::generic::{infer_type_args, instance_name};
+use crate::types::{Type, Closure, DynTrait, mangle_name};
 
 #[derive(Debug)]
 pub struct CallExpr {
     pub caller: Box<AST>,
-    args: Vec<AST>,
+    pub args: Vec<AST>,
 }
 
 impl CallExpr {
@@ -14,6 +15,65 @@ impl CallExpr {
         Self{ caller, args }
     }
 
+    /*
+     *  returns the name of the function to call, instantiating generic functions with the
+     *  explicitly supplied (`max::<i32>()`) or inferred type arguments
+     */
+    fn resolve_generic(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Tagged<String> {
+        let name = Tagged::new(self.loc().clone(), self.caller.get_value());
+        if name.inner().contains('<') {
+            scope.instantiate(name.inner(), name.loc());
+            return Tagged::new(name.loc().clone(), mangle_name(name.inner()));
+        }
+
+        let func = match scope.try_resolve_symbol(&name) {
+            Some(Symbol::Function(func)) if func.template.is_some() => func.clone(),
+            _ => return name,
+        };
+
+        if func.args.len() != self.args.len() {
+            error_msg_label(
+                format!("function `{}` expected `{}` args, but `{}` were supplied", name.inner(), func.args.len(), self.args.len()).as_str(),
+                ErrorLabel::from(self.loc(), "incorrect number of arguments supplied"),
+            );
+        }
+        let arg_types: Vec<Type> = self.args.iter().map(|arg| arg.get_type(scope, ctx).unwrap()).collect();
+        let instance = instance_name(name.inner(), &infer_type_args(&func, &name, &arg_types));
+        scope.instantiate(&instance, name.loc());
+        return Tagged::new(name.loc().clone(), mangle_name(instance));
+    }
+
+    /*
+     *  NOTE(S): closures are called through their function pointer with the environment as the
+     *  first argument
+     */
+    fn gen_closure_call(&mut self, closure: &Closure, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+        if closure.params.len() != self.args.len() {
+            error_msg_label(
+                format!("closure expected `{}` args, but `{}` were supplied", closure.params.len(), self.args.len()).as_str(),
+                ErrorLabel::from(self.loc(), "incorrect number of arguments supplied"),
+            );
+        }
+
+        let fat_ty = scope.resolve_type(&Type::Closure(closure.clone()), ctx);
+        let fat = super::binary::gen_operand(&mut self.caller, scope, ctx);
+        let i8_ptr = llvm::TypeRef::get_ptr(llvm::TypeRef::get_int(ctx.ctx, 8), 0);
+        let fn_ptr = ctx.builder.create_load(&i8_ptr, &ctx.builder.create_struct_gep(&fat_ty, &fat, 0));
+        let env = ctx.builder.create_load(&i8_ptr, &ctx.builder.create_struct_gep(&fat_ty, &fat, 1));
+
+        let params: Vec<FunctionArg> = closure.params.iter().map(|param| {
+            FunctionArg{ id: Tagged::new(param.get_loc(), "".to_owned()), typ: param.clone() }
+        }).collect();
+        let mut args = vec![env];
+        args.extend(gen_args(&mut self.args, &params, "closure", scope, ctx));
+
+        let mut param_tys = vec![i8_ptr];
+        param_tys.extend(closure.params.iter().map(|param| scope.resolve_type(param, ctx)));
+        let fn_ty: llvm::TypeRef = llvm::FunctionTypeRef::get(scope.resolve_type(&closure.ret_type, ctx), &param_tys, false).into();
+        let fn_ptr = ctx.builder.create_pointer_cast(&fn_ptr, &llvm::TypeRef::get_ptr(fn_ty.clone(), 0));
+        return ctx.builder.create_ptr_call(&fn_ty, &fn_ptr, &args);
+    }
+
     pub fn gen_code_internal(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext, obj: Option<(&AST, bool)>, obj_value: Option<llvm::ValueRef>) -> Option<llvm::ValueRef> {
         if obj_value.is_some() {
             assert!(
@@ -22,7 +82,15 @@ impl CallExpr {
             );
         }
 
-        let fn_name = Tagged::new(self.loc().clone(), self.caller.get_value());
+        let fn_name = match obj {
+            Some(_) => Tagged::new(self.loc().clone(), self.caller.get_value()),
+            None => self.resolve_generic(scope, ctx),
+        };
+        if obj.is_none() && !matches!(scope.try_resolve_symbol(&fn_name), Some(Symbol::Function(_))) {
+            if let Some(Type::Closure(closure)) = self.caller.get_type(scope, ctx) {
+                return Some(self.gen_closure_call(&closure, scope, ctx));
+            }
+        }
         let fully_qualified_name = match obj {
             Some(obj) => {
                 let struct_name = if obj.1 { obj.0.get_value() } else { obj.0.get_type(scope, ctx).unwrap().get_name() };
@@ -30,7 +98,7 @@ impl CallExpr {
                 let r#fn = strct.get_function(self.caller.loc(), self.caller.get_value());
                 r#fn.name_mangeled.clone()
             }
-            None => self.caller.get_value(),
+            None => fn_name.inner().clone(),
         };
         let mut func: Option<llvm::Function> = ctx.module.get_function(&fully_qualified_name).map(|i| i.into());
 
@@ -81,6 +149,16 @@ impl CallExpr {
             }
         };
 
+        if !is_ptr {
+            super::lints::mark_called(&r#fn.name_mangeled);
+        }
+        /*
+         *  NOTE(S): a method can change the object it is called on through `self`
+         */
+        if let Some((obj, false)) = obj {
+            super::lints::mark_assigned(obj, scope);
+        }
+
         if func.is_none() {
             let args: Vec<llvm::TypeRef> = r#fn.args.iter().map(|t| scope.resolve_type(&t.typ, ctx)).collect();
             let fn_type = llvm::FunctionTypeRef::get(scope.resolve_type(&r#fn.ret_type, ctx), &args, false);
@@ -100,12 +178,20 @@ impl CallExpr {
             }
         }
 
-        let mut args: Vec<llvm::ValueRef> = self.args.iter_mut().map(|arg| {
-            let arg_ty = arg.get_type(scope, ctx).unwrap();
+        let adder = if obj_value.is_some() { 1 } else { 0 };
+        let mut args: Vec<llvm::ValueRef> = self.args.iter_mut().enumerate().map(|(i, arg)| {
+            let (arg_ty, load) = match reborrow(arg, r#fn.args.get(i + adder), scope) {
+                Some(typ) => (typ, false),
+                None => (arg.get_type(scope, ctx).unwrap(), arg.should_load()),
+            };
             let mut val = arg.gen_code(scope, ctx).unwrap();
-            if arg.should_load() {
+            if load {
                 val = val.try_load(&scope.resolve_type(&arg_ty, ctx), ctx.builder);
             }
+            scope.gen_move(arg, ctx);
+            if let Some(coerced) = r#fn.args.get(i + adder).and_then(|expected| crate::ast::coerce(&val, &arg_ty, &expected.typ, arg.loc(), scope, ctx)) {
+                val = coerced;
+            }
 
             // TODO(S): add try to safely cast args to expected type
 
@@ -115,7 +201,6 @@ impl CallExpr {
         })
         .collect();
 
-        let is_memeber = obj_value.is_some();
         if let Some(mut obj_value) = obj_value {
             /*
              *  NOTE(S): store `self` in temporary varialbe to deref it
@@ -129,15 +214,18 @@ impl CallExpr {
                     ctx.builder.create_store(&obj_value, &alloc);
                     obj_value = alloc;
                 }
+            }else if let Some((obj, false)) = obj {
+                scope.gen_move(obj, ctx);
             }
             args.insert(0, obj_value);
         }
 
-        let adder = if is_memeber { 1 } else { 0 };
         let mut iter = adder;
         while iter < r#fn.args.len() && iter < self.args.len() {
             let expected_type = &r#fn.args[iter].typ;
-            if !self.args[iter - adder].get_type(scope, ctx).unwrap().matches(expected_type)
+            let provided_type = self.args[iter - adder].get_type(scope, ctx).unwrap();
+            if !provided_type.matches(expected_type) && !crate::ast::can_coerce(&provided_type, expected_type)
+                && reborrow(&self.args[iter - adder], Some(&r#fn.args[iter]), scope).is_none()
             {
                 error_msg_labels(
                     format!("invalid argument type for function `{}`", fn_name.inner()).as_str(),
@@ -173,6 +261,50 @@ impl CallExpr {
     }
 }
 
+/*
+ *  NOTE(S): a reference binding is dereferenced where it is used, passed to a parameter of the
+ *  same reference type the reference itself is passed -> `a.eq(b)` with `b: &String`. returns the
+ *  type of the reference
+ */
+fn reborrow(arg: &AST, param: Option<&FunctionArg>, scope: &super::Scope) -> Option<Type> {
+    let (AST::Id(id), Some(param)) = (arg, param) else { return None; };
+    let Some(Symbol::Variable(var)) = scope.try_resolve_symbol(&id.id) else { return None; };
+    return match (&var.typ, &param.typ) {
+        (Type::Pointer(ptr), Type::Pointer(_)) if id.deref && ptr.is_ref && var.typ.matches(&param.typ) => Some(var.typ.clone()),
+        _ => None,
+    };
+}
+
+/*
+ *  generates arguments passed by value to an indirectly called function, which can't be
+ *  type checked through the called `llvm::Function`
+ */
+fn gen_args(args: &mut [AST], params: &[FunctionArg], name: &str, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Vec<llvm::ValueRef> {
+    let mut values = Vec::new();
+    for (arg, param) in args.iter_mut().zip(params.iter()) {
+        let (arg_ty, load) = match reborrow(arg, Some(param), scope) {
+            Some(typ) => (typ, false),
+            None => (arg.get_type(scope, ctx).unwrap(), arg.should_load()),
+        };
+        let mut val = arg.gen_code(scope, ctx).unwrap();
+        if load {
+            val = val.try_load(&scope.resolve_type(&arg_ty, ctx), ctx.builder);
+        }
+        scope.gen_move(arg, ctx);
+        if let Some(coerced) = crate::ast::coerce(&val, &arg_ty, &param.typ, arg.loc(), scope, ctx) {
+            val = coerced;
+        }else if !arg_ty.matches(&param.typ) {
+            error_msg_labels(
+                format!("invalid argument type for function `{}`", name).as_str(), &[
+                ErrorLabel::from(arg.loc(), format!("provided value has type: `{}`", arg_ty.get_full_name()).as_str()),
+                ErrorLabel::from(param.id.loc(), format!("expected type is: `{}`", param.typ.get_full_name()).as_str()),
+            ]);
+        }
+        values.push(val);
+    }
+    return values;
+}
+
 impl Generate for CallExpr {
     fn loc(&self) -> &Loc {
         return self.caller.loc();
@@ -187,16 +319,16 @@ impl Generate for CallExpr {
     }
 
     fn get_type(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<Type> {
-        let fn_name = Tagged::new(self.loc().clone(), self.caller.get_value());
-        let ty = if scope.has_symbol(fn_name.inner()) {
-            scope.get_function(&fn_name).ret_type.clone()
-        } else {
-            self.caller.get_type(scope, ctx).unwrap()
+        let fn_name = self.resolve_generic(scope, ctx);
+        let ty = match scope.try_resolve_symbol(&fn_name) {
+            Some(Symbol::Function(func)) => return Some(func.ret_type.clone()),
+            _ => self.caller.get_type(scope, ctx).unwrap(),
+        };
+        return match ty {
+            Type::FuncPtr(ptr) => Some(*ptr.ret_type.clone()),
+            Type::Closure(closure) => Some(*closure.ret_type.clone()),
+            _ => Some(scope.get_function(&fn_name).ret_type.clone()),
         };
-        if let Type::FuncPtr(ptr) = ty {
-            return Some(*ptr.ret_type.clone());
-        }
-        return Some(scope.get_function(&fn_name).ret_type.clone());
     }
 
     fn collect_symbols(&mut self, _: &mut super::Scope) {}
@@ -213,6 +345,65 @@ impl MemberCallExpr {
     pub fn new(obj: Box<AST>, r#fn: Box<AST>, deref: bool) -> Self {
         Self{ obj, r#fn, deref }
     }
+
+    fn get_object_method<'a>(trat: &'a super::Trait, d: &DynTrait, r#fn: &AST) -> (usize, &'a super::Function) {
+        if let Some(method) = trat.get_object_method(r#fn.get_value()) {
+            return method;
+        }
+        if trat.funcs.body.iter().any(|func| func.get_value() == r#fn.get_value()) {
+            error_msg_labels(
+                format!("the method `{}` cannot be called on a trait object", r#fn.get_value()).as_str(), &[
+                ErrorLabel::from(r#fn.loc(), format!("`{}` is not object safe", r#fn.get_value())),
+                ErrorLabel::from(&d.loc, "trait object declared here"),
+            ]);
+        }
+        error_msg_labels(
+            format!("no method named `{}` found for `dyn {}`", r#fn.get_value(), d.trat).as_str(), &[
+            ErrorLabel::from(r#fn.loc(), "unknown method"),
+            ErrorLabel::from(trat.id.loc(), "trait declared here"),
+        ]);
+    }
+
+    /*
+     *  NOTE(S): a trait object is a `{ *i8, *vtable }` pair, the method is loaded from the vtable
+     *  and called with the data pointer as `self`
+     */
+    fn gen_dyn_call(&mut self, d: &DynTrait, obj: llvm::ValueRef, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+        /*
+         *  NOTE(S): the trait lives in the ast, so it outlives the borrow of `scope`
+         */
+        let trat: *const super::Trait = scope.get_trait(&Tagged::new(d.loc.clone(), d.trat.clone())).get();
+        let trat = unsafe { &*trat };
+        let (idx, method) = Self::get_object_method(trat, d, &self.r#fn);
+        let AST::CallExpr(call) = &mut *self.r#fn else { unreachable!("fn should allways be of type `callexpr`"); };
+
+        if method.args.len() != call.args.len() + 1 {
+            error_msg_label(
+                format!("function `{}` expected `{}` args, but `{}` were supplied", method.get_value(), method.args.len() - 1, call.args.len()).as_str(),
+                ErrorLabel::from(call.loc(), "incorrect number of arguments supplied"),
+            );
+        }
+
+        let fat_ty = scope.resolve_type(&Type::Dyn(d.clone()), ctx);
+        let fat = if obj.get_type().matches(&fat_ty) {
+            let alloc = ctx.builder.create_alloca(&fat_ty, None);
+            ctx.builder.create_store(&obj, &alloc);
+            alloc
+        }else {
+            obj
+        };
+        let vtable_ty = trat.vtable_type(scope, ctx);
+        let fn_ty: llvm::TypeRef = super::Trait::method_type(method, scope, ctx).into();
+        let fn_ptr_ty = llvm::TypeRef::get_ptr(fn_ty.clone(), 0);
+
+        let data = ctx.builder.create_load(&llvm::TypeRef::get_ptr(llvm::TypeRef::get_int(&ctx.ctx, 8), 0), &ctx.builder.create_struct_gep(&fat_ty, &fat, 0));
+        let vtable = ctx.builder.create_load(&llvm::TypeRef::get_ptr(vtable_ty.clone(), 0), &ctx.builder.create_struct_gep(&fat_ty, &fat, 1));
+        let fn_ptr = ctx.builder.create_load(&fn_ptr_ty, &ctx.builder.create_struct_gep(&vtable_ty, &vtable, idx as u32));
+
+        let mut args = vec![data];
+        args.extend(gen_args(&mut call.args, &method.args[1..], &method.get_value(), scope, ctx));
+        return Some(ctx.builder.create_ptr_call(&fn_ty, &fn_ptr, &args));
+    }
 }
 
 impl Generate for MemberCallExpr {
@@ -227,6 +418,9 @@ impl Generate for MemberCallExpr {
     fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
         let obj_ty = self.obj.get_type(scope, ctx).unwrap();
         let mut obj = self.obj.gen_code(scope, ctx).unwrap();
+        if let Type::Dyn(d) = &obj_ty {
+            return self.gen_dyn_call(d, obj, scope, ctx);
+        }
         if self.deref {
             let obj_type = scope.resolve_type(&obj_ty, ctx);
             let ty = if !obj.get_type().matches(&obj_type) {
@@ -251,6 +445,10 @@ impl Generate for MemberCallExpr {
     }
 
     fn get_type(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<Type> {
+        if let Type::Dyn(d) = self.obj.get_type(scope, ctx).unwrap() {
+            let trat = scope.get_trait(&Tagged::new(d.loc.clone(), d.trat.clone())).get();
+            return Some(Self::get_object_method(trat, &d, &self.r#fn).1.ret_type.clone());
+        }
         let struct_name = self.obj.get_type(scope, ctx).unwrap().get_name();
         let strct = scope.get_struct(&Tagged::new(self.obj.loc().clone(), struct_name));
         let r#fn = strct.get_function(self.r#fn.loc(), self.r#fn.get_value());
@@ -263,13 +461,26 @@ impl Generate for MemberCallExpr {
 #[derive(Debug)]
 pub struct ReturnExpr {
     loc: Loc,
-    value: Option<Box<AST>>,
+    pub value: Option<Box<AST>>,
 }
 
 impl ReturnExpr {
     pub fn new(loc: Loc, value: Option<Box<AST>>) -> Self {
         Self{ loc, value }
     }
+
+    /*
+     *  NOTE(S): statements create the return block before their body when the flow analysis found
+     *  a `return` in it, a `return` inside of an expression like `x = if c { return 1; } else { 2 };`
+     *  is not seen by the analysis and creates the block itself
+     */
+    fn gen_br_ret(ctx: &crate::GenerationContext) {
+        let func = unsafe{ &mut *ctx.current_function };
+        if func.ret_block.is_none() {
+            func.ret_block = Some(llvm::BasicBlock::new(ctx.ctx, "ret", Some(&ctx.builder.get_insert_block().get_parent()), None));
+        }
+        ctx.builder.create_br(func.ret_block.as_ref().unwrap());
+    }
 }
 
 impl Generate for ReturnExpr {
@@ -288,9 +499,8 @@ impl Generate for ReturnExpr {
     fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
         if self.value.is_none() {
             if !ctx.current_function.is_null() {
-                if let Some(bb) = unsafe{&(*ctx.current_function).ret_block} {
-                    ctx.builder.create_br(bb);
-                }
+                scope.gen_exit_until(unsafe{ (*ctx.current_function).body_scope() }, ctx);
+                Self::gen_br_ret(ctx);
             }
             return None;
         }
@@ -307,9 +517,11 @@ impl Generate for ReturnExpr {
         }
 
         if !ctx.current_function.is_null() {
-            if let AST::InitializerList(list) = &mut **self.value.as_mut().unwrap() {
-                let ty = unsafe { ctx.current_function.as_ref().unwrap() }.ret_type.clone();
-                list.set_typ(Some(ty));
+            let ty = unsafe { ctx.current_function.as_ref().unwrap() }.ret_type.clone();
+            match &mut **self.value.as_mut().unwrap() {
+                AST::InitializerList(list) => list.set_typ(Some(ty)),
+                AST::TupleExpr(tuple) => tuple.set_typ(Some(ty)),
+                _ => {},
             }
         }
 
@@ -324,16 +536,25 @@ impl Generate for ReturnExpr {
                 },
             }
         }
+        scope.gen_move(self.value.as_ref().unwrap(), ctx);
 
         if !ctx.current_function.is_null() {
-            if let Some(bb) = unsafe { (*ctx.current_function).ret_block.as_ref() } {
-                let store = ctx.builder.create_store(&value, scope.get_return_alloc().unwrap());
-                ctx.builder.create_br(bb);
-                return Some(store);
+            let ret_type = unsafe { &(*ctx.current_function).ret_type };
+            if let Some(coerced) = crate::ast::coerce(&value, &value_ty, ret_type, self.value.as_ref().unwrap().loc(), scope, ctx) {
+                value = coerced;
             }
         }
 
-        return Some(ctx.builder.create_store(&value, scope.get_return_alloc().unwrap()));
+        /*
+         *  NOTE(S): the return value is stored before deferred statements run
+         */
+        let store = ctx.builder.create_store(&value, scope.get_return_alloc().unwrap());
+        if !ctx.current_function.is_null() {
+            scope.gen_exit_until(unsafe{ (*ctx.current_function).body_scope() }, ctx);
+            Self::gen_br_ret(ctx);
+        }
+
+        return Some(store);
     }
 
     fn get_type(&self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<Type> {
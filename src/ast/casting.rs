@@ -48,8 +48,11 @@ impl Generate for UnaryExpr {
                      */
                     let base_ty = scope.resolve_type(&base, ctx);
                     if !obj.get_type().matches(&base_ty) {
-                        ctx.builder.create_load(&base_ty, &obj)
+                        let ptr = ctx.builder.create_load(&base_ty, &obj);
+                        super::checks::gen_null_check(&ptr, self.loc(), ctx);
+                        ptr
                     }else {
+                        super::checks::gen_null_check(&obj, self.loc(), ctx);
                         ctx.builder.create_load(&scope.resolve_type(&base.get_base().unwrap(), ctx), &obj)
                     }
                 });
@@ -61,6 +64,7 @@ impl Generate for UnaryExpr {
                  *  temporary allocation to take its address
                  *
                  */
+                super::lints::mark_assigned(&self.obj, scope);
                 return Some(obj);
             }
             "!" => {
@@ -132,6 +136,124 @@ pub fn allow_implicit_cast(src: &llvm::TypeRef, dest: &llvm::TypeRef) -> bool {
     return false;
 }
 
+/*
+ *  returns the struct that gets turned into a trait object when a value of type `from` is used
+ *  where `to` is expected, `&Circle` -> `&dyn Shape`
+ */
+fn dyn_source(from: &Type, to: &Type) -> Option<String> {
+    let (Type::Pointer(ptr), Type::Dyn(_)) = (from, to) else { return None; };
+    let Type::Path(path) = &*ptr.typ else { return None; };
+    return Some(path.path.clone());
+}
+
+/*
+ *  returns the element type name of the buffer behind `from` when it is used where the slice `to`
+ *  is expected, `&[T; N]` and `&Vec<T>` -> `[T]`
+ */
+fn slice_source(from: &Type, to: &Type) -> Option<String> {
+    let (Type::Pointer(ptr), Type::Slice(_)) = (from, to) else { return None; };
+    return match &*ptr.typ {
+        Type::Array(arr) => Some(arr.typ.get_full_name()),
+        Type::Path(path) => match super::generic::split_generic_args(&path.path) {
+            Some((base, mut args)) if base == "Vec" && args.len() == 1 => args.pop(),
+            _ => None,
+        },
+        _ => None,
+    };
+}
+
+/*
+ *  NOTE(S): implicit conversions applied wherever a value is used with an expected type, trait
+ *  objects and slices
+ */
+pub fn can_coerce(from: &Type, to: &Type) -> bool {
+    if let (Some(elem), Type::Slice(slice)) = (slice_source(from, to), to) {
+        return elem == slice.typ.get_full_name();
+    }
+    return dyn_source(from, to).is_some();
+}
+
+pub fn coerce(value: &llvm::ValueRef, from: &Type, to: &Type, loc: &Loc, scope: &super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+    if let Some(elem) = slice_source(from, to) {
+        return Some(coerce_slice(value, from, to, &elem, loc, scope, ctx));
+    }
+    return coerce_dyn(value, from, to, loc, scope, ctx);
+}
+
+fn coerce_dyn(value: &llvm::ValueRef, from: &Type, to: &Type, loc: &Loc, scope: &super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+    let strct = Tagged::new(loc.clone(), dyn_source(from, to)?);
+    let Type::Dyn(d) = to else { unreachable!() };
+    if !scope.is_struct(&strct) || !scope.get_struct(&strct).implements_trait(&d.trat) {
+        error_msg_labels(
+            format!("the trait `{}` is not implemented for `{}`", d.trat, strct.inner()).as_str(), &[
+            ErrorLabel::from(loc, format!("value of type `{}` cannot be used as `{}`", from.get_full_name(), to.get_full_name())),
+            ErrorLabel::from(&d.loc, "trait object required here"),
+        ]);
+    }
+    /*
+     *  NOTE(S): a shared `&Circle` cannot be turned into a `&mut dyn Shape`, raw pointers carry
+     *  no such guarantee
+     */
+    let Type::Pointer(ptr) = from else { unreachable!() };
+    if d.is_mut && ptr.is_ref && !ptr.is_mut {
+        error_msg_labels("mismatched types", &[
+            ErrorLabel::from(loc, format!("`{}` is a shared reference, it cannot be used as `{}`", from.get_full_name(), to.get_full_name())),
+            ErrorLabel::from(&d.loc, "mutable trait object required here"),
+        ]);
+    }
+
+    let trat = scope.get_trait(&Tagged::new(d.loc.clone(), d.trat.clone())).get();
+    let fat_ty = scope.resolve_type(to, ctx);
+    let fat = ctx.builder.create_alloca(&fat_ty, None);
+    let data = ctx.builder.create_pointer_cast(value, &llvm::TypeRef::get_ptr(llvm::TypeRef::get_int(&ctx.ctx, 8), 0));
+    ctx.builder.create_store(&data, &ctx.builder.create_struct_gep(&fat_ty, &fat, 0));
+    ctx.builder.create_store(&trat.get_vtable(&strct, scope, ctx), &ctx.builder.create_struct_gep(&fat_ty, &fat, 1));
+    return Some(ctx.builder.create_load(&fat_ty, &fat));
+}
+
+/*
+ *  NOTE(S): the slice borrows the buffer, the array or the `Vec` has to outlive it
+ */
+fn coerce_slice(value: &llvm::ValueRef, from: &Type, to: &Type, elem: &str, loc: &Loc, scope: &super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+    let Type::Slice(slice) = to else { unreachable!() };
+    if elem != slice.typ.get_full_name() {
+        error_msg_labels("mismatched types", &[
+            ErrorLabel::from(loc, format!("value of type `{}` cannot be used as `{}`", from.get_full_name(), to.get_full_name())),
+            ErrorLabel::from(&slice.loc, format!("expected elements of type `{}`", slice.typ.get_full_name())),
+        ]);
+    }
+
+    let Type::Pointer(ptr) = from else { unreachable!() };
+    let buffer_ty = scope.resolve_type(&ptr.typ, ctx);
+    let elem_ptr_ty = llvm::TypeRef::get_ptr(scope.resolve_type(&slice.typ, ctx), 0);
+    let len_ty = llvm::TypeRef::get_int(ctx.ctx, 64);
+    let (data, len) = match &*ptr.typ {
+        Type::Array(arr) => {
+            let zero = llvm::ConstantInt::get(&len_ty, 0);
+            (
+                ctx.builder.create_gep(&buffer_ty, value, &[zero.clone(), zero], true),
+                llvm::ConstantInt::get(&len_ty, arr.elems as i32),
+            )
+        },
+        typ => {
+            let fields = &scope.get_struct(&Tagged::new(loc.clone(), typ.get_name())).fields;
+            let field = |name: &str| fields.iter().position(|field| field.id.inner() == name).unwrap() as u32;
+            let len_field = &fields[field("len") as usize].typ;
+            let len = ctx.builder.create_struct_gep(&buffer_ty, value, field("len")).try_load(&scope.resolve_type(len_field, ctx), ctx.builder);
+            (
+                ctx.builder.create_struct_gep(&buffer_ty, value, field("data")).try_load(&elem_ptr_ty, ctx.builder),
+                ctx.builder.create_cast(llvm::CastOps::ZExt, &len, &len_ty),
+            )
+        },
+    };
+
+    let fat_ty = scope.resolve_type(to, ctx);
+    let fat = ctx.builder.create_alloca(&fat_ty, None);
+    ctx.builder.create_store(&data, &ctx.builder.create_struct_gep(&fat_ty, &fat, 0));
+    ctx.builder.create_store(&len, &ctx.builder.create_struct_gep(&fat_ty, &fat, 1));
+    return ctx.builder.create_load(&fat_ty, &fat);
+}
+
 pub fn get_cast_ops(src_loc: &Loc, src: &llvm::TypeRef, dest_loc: &Loc, dest: &llvm::TypeRef) -> llvm::CastOps {
     if src.is_int_ty() && dest.is_int_ty() {
         if src.get_int_bit_width() > dest.get_int_bit_width() {
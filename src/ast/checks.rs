@@ -0,0 +1,86 @@
+use crate::lexer::{Loc, Tagged};
+use super::binary::NumberKind;
+
+/*
+ *  runtime checks, slices are always bounds checked. everything else is only emitted when
+ *  compiling with `--checks`
+ */
+
+fn declare(name: &str, ret: llvm::TypeRef, params: &[llvm::TypeRef], var_arg: bool, ctx: &crate::GenerationContext) -> llvm::Function {
+    return ctx.module.get_function(name).unwrap_or_else(|| {
+        llvm::Function::create(llvm::FunctionTypeRef::get(ret, params, var_arg), name, ctx.module)
+    });
+}
+
+/*
+ *  NOTE(S): branches to a block that prints `msg` (a printf format string) with `args` and the
+ *  location of the check to stderr and aborts when `ok` is false
+ */
+pub fn gen_check(ok: &llvm::ValueRef, msg: &str, args: &[llvm::ValueRef], loc: &Loc, ctx: &crate::GenerationContext) {
+    let func = ctx.builder.get_insert_block().get_parent();
+    let fail_block = llvm::BasicBlock::new(ctx.ctx, "check.fail", Some(&func), None);
+    let cont_block = llvm::BasicBlock::new(ctx.ctx, "check.ok", Some(&func), None);
+    ctx.builder.create_cond_br(ok, &cont_block, &fail_block);
+
+    ctx.builder.set_insert_point(&fail_block);
+    let i32_ty = llvm::TypeRef::get_int(ctx.ctx, 32);
+    let i8_ptr = llvm::TypeRef::get_ptr(llvm::TypeRef::get_int(ctx.ctx, 8), 0);
+    let dprintf = declare("dprintf", i32_ty.clone(), &[i32_ty.clone(), i8_ptr], true, ctx);
+    let abort = declare("abort", llvm::TypeRef::get_void(ctx.ctx), &[], false, ctx);
+
+    let msg = format!("{}\n  --> {}:{}:{}\n", msg, loc.file.display().to_string().replace('%', "%%"), loc.line, loc.column);
+    let mut call_args = vec![llvm::ConstantInt::get(&i32_ty, 2), ctx.builder.create_global_string_pointer(msg)];
+    call_args.extend_from_slice(args);
+    ctx.builder.create_call(&dprintf.into(), &call_args);
+    ctx.builder.create_call(&abort.into(), &[]);
+    ctx.builder.create_unreachable();
+    ctx.builder.set_insert_point(&cont_block);
+}
+
+pub fn gen_null_check(ptr: &llvm::ValueRef, loc: &Loc, ctx: &crate::GenerationContext) {
+    if !ctx.checks || !ptr.get_type().is_pointer_ty() {
+        return;
+    }
+    let addr = ctx.builder.create_cast(llvm::CastOps::PtrToInt, ptr, &llvm::TypeRef::get_int(ctx.ctx, 64));
+    let ok = ctx.builder.create_icmp_ne(&addr, &llvm::ConstantInt::get(&addr.get_type(), 0));
+    gen_check(&ok, "null pointer dereference", &[], loc, ctx);
+}
+
+/*
+ *  NOTE(S): `+`, `-` and `*` on integers go through the `llvm.*.with.overflow` intrinsics, `/`
+ *  and `%` check for a zero divisor and `MIN / -1`. returns `None` for every other operation
+ */
+pub fn gen_checked_op(op: &Tagged<String>, kind: NumberKind, lhs: &llvm::ValueRef, rhs: &llvm::ValueRef, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+    let typ = lhs.get_type();
+    if !ctx.checks || kind == NumberKind::Float || !typ.is_int_ty() || !rhs.get_type().is_int_ty() {
+        return None;
+    }
+
+    let signed = kind == NumberKind::Signed;
+    let (intrinsic, verb) = match op.inner().as_str() {
+        "+" => (if signed { "sadd" } else { "uadd" }, "add"),
+        "-" => (if signed { "ssub" } else { "usub" }, "subtract"),
+        "*" => (if signed { "smul" } else { "umul" }, "multiply"),
+        "/" | "%" => {
+            let zero = llvm::ConstantInt::get(&typ, 0);
+            gen_check(&ctx.builder.create_icmp_ne(rhs, &zero), "attempt to divide by zero", &[], op.loc(), ctx);
+            if signed {
+                let bits = typ.get_int_bit_width() as i32;
+                let min = ctx.builder.create_shl(&llvm::ConstantInt::get(&typ, 1), &llvm::ConstantInt::get(&typ, bits - 1));
+                let overflows = ctx.builder.create_and(&ctx.builder.create_icmp_eq(lhs, &min), &ctx.builder.create_icmp_eq(rhs, &llvm::ConstantInt::get(&typ, -1)));
+                gen_check(&ctx.builder.create_xor_vl(&overflows, 1), "attempt to divide with overflow", &[], op.loc(), ctx);
+            }
+            return None;
+        },
+        _ => return None,
+    };
+
+    let result_ty: llvm::TypeRef = llvm::StructTypeRef::get(ctx.ctx, &[typ.clone(), llvm::TypeRef::get_int(ctx.ctx, 1)], false).into();
+    let name = format!("llvm.{}.with.overflow.i{}", intrinsic, typ.get_int_bit_width());
+    let func = declare(&name, result_ty.clone(), &[typ.clone(), typ.clone()], false, ctx);
+
+    let result = ctx.builder.create_call(&func.into(), &[lhs.clone(), rhs.clone()]);
+    let overflow = ctx.builder.create_extract_value(&result, 1);
+    gen_check(&ctx.builder.create_xor_vl(&overflow, 1), format!("attempt to {} with overflow", verb).as_str(), &[], op.loc(), ctx);
+    return Some(ctx.builder.create_extract_value(&result, 0));
+}
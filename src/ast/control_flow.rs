@@ -1,33 +1,231 @@
-use crate::ast::{AST, Block, Generate};
-use crate::log::{error_msg, error_msg_label, ErrorLabel};
-use crate::types::containers;
-use crate::lexer::Loc;
+use crate::ast::{AST, Block, Generate, EnumVariant, symbol, symbol::{Symbol, Variable}};
+use crate::ast::binary::{gen_binary_op, gen_operator_call, overloaded_struct};
+use crate::ast::generic::split_generic_args;
+use crate::log::{error_msg, error_msg_label, error_msg_labels, error_msg_label_info, ErrorLabel};
+use crate::types::{Type, containers};
+use crate::lexer::{Loc, Tagged};
 
 /*
- *  TODO(S):
- *  type check the control flow of the program
- *  -> does function return in all valid paths?
- *  -> ignore unreachable parts of the program
- *
+ *  NOTE(S): control flow analysis of a function body, it runs before any code of the function is
+ *  generated. a statement either falls through to the next one or leaves its block through
+ *  `return`, `break` or `continue`. the statements following one that never falls through are
+ *  reported and moved to `Block::unreachable`, they are still checked but no code is kept for them
  */
+struct Flow {
+    /*
+     *  the label of every enclosing loop and if it is left by a `break`
+     */
+    loops: Vec<(Option<String>, bool)>,
+    /*
+     *  how many unreachable statements enclose the current one
+     */
+    dead: usize,
+}
+
+/*
+ *  how control leaves a statement or block
+ */
+#[derive(Clone, Copy)]
+struct Exit {
+    falls_through: bool,
+    returns: bool,
+}
+
+impl Exit {
+    const NEXT: Exit = Exit{ falls_through: true, returns: false };
+}
+
+impl Flow {
+    /*
+     *  sets `Block::returns` if a `return` can be reached from the block
+     */
+    fn block(&mut self, block: &mut Block) -> Exit {
+        let mut exit = Exit::NEXT;
+        let mut end = block.body.len();
+        for (i, stmt) in block.body.iter_mut().enumerate() {
+            if !exit.falls_through {
+                end = i;
+                break;
+            }
+            let stmt_exit = self.stmt(stmt);
+            exit = Exit{ falls_through: stmt_exit.falls_through, returns: exit.returns || stmt_exit.returns };
+        }
+        if end < block.body.len() {
+            if self.dead == 0 {
+                super::lints::unreachable_code(block.body[end - 1].loc(), &block.body[end..]);
+            }
+            block.unreachable = block.body.split_off(end);
+            block.yields = false;
+            self.dead += 1;
+            for stmt in &mut block.unreachable {
+                self.stmt(stmt);
+            }
+            self.dead -= 1;
+        }
+        block.returns = exit.returns;
+        return exit;
+    }
+
+    fn body(&mut self, label: &Option<Tagged<String>>, body: &mut Block, infinite: bool) -> Exit {
+        self.loops.push((label.as_ref().map(|label| label.inner().clone()), false));
+        let exit = self.block(body);
+        let (_, broken) = self.loops.pop().unwrap();
+        return Exit{ falls_through: !infinite || broken, returns: exit.returns };
+    }
+
+    fn stmt(&mut self, stmt: &mut AST) -> Exit {
+        return match stmt {
+            AST::ReturnExpr(ret) => {
+                if let Some(value) = &mut ret.value {
+                    self.stmt(value);
+                }
+                Exit{ falls_through: false, returns: true }
+            },
+            AST::BreakExpr(b) => {
+                let target = match &b.label {
+                    Some(label) => self.loops.iter_mut().rev().find(|(l, _)| l.as_ref() == Some(label.inner())),
+                    None => self.loops.last_mut(),
+                };
+                if let Some((_, broken)) = target.filter(|_| self.dead == 0) {
+                    *broken = true;
+                }
+                Exit{ falls_through: false, returns: false }
+            },
+            AST::ContinueExpr(_) => Exit{ falls_through: false, returns: false },
+            AST::IfStmt(stmt) => {
+                let then_exit = self.block(&mut stmt.then_body);
+                let else_exit = match &mut stmt.else_body {
+                    Some(body) => self.block(body),
+                    None => Exit::NEXT,
+                };
+                Exit{ falls_through: then_exit.falls_through || else_exit.falls_through, returns: then_exit.returns || else_exit.returns }
+            },
+            AST::WhileStmt(stmt) => self.body(&stmt.label, &mut stmt.body, stmt.cond.is_none()),
+            AST::ForStmt(stmt) => self.body(&stmt.label, &mut stmt.body, false),
+            AST::ForInStmt(stmt) => self.body(&stmt.label, &mut stmt.body, false),
+            AST::MatchStmt(stmt) => {
+                let mut exit = Exit{ falls_through: stmt.arms.is_empty(), returns: false };
+                for arm in &mut stmt.arms {
+                    let arm_exit = self.block(&mut arm.body);
+                    exit = Exit{ falls_through: exit.falls_through || arm_exit.falls_through, returns: exit.returns || arm_exit.returns };
+                }
+                exit
+            },
+            /*
+             *  NOTE(S): `let x = if c { return; } else { 1 };`
+             */
+            AST::VarDecl(decl) => match &mut decl.value {
+                Some(value) => self.stmt(value),
+                None => Exit::NEXT,
+            },
+            _ => Exit::NEXT,
+        };
+    }
+}
+
+/*
+ *  returns whether the end of the body can be reached without a `return`
+ */
+pub fn analyze_flow(body: &mut Block) -> bool {
+    return Flow{ loops: Vec::new(), dead: 0 }.block(body).falls_through;
+}
+
+/*
+ *  NOTE(S): unreachable statements are generated into a throwaway function, so they report the
+ *  same errors as reachable ones. their variables live in a temporary scope and the return block
+ *  of the function is set aside, nothing generated for them is kept
+ */
+pub fn gen_unreachable(body: &mut Block, ctx: &crate::GenerationContext) {
+    if body.unreachable.is_empty() {
+        return;
+    }
+    let void_ty = llvm::TypeRef::get_void(ctx.ctx);
+    let func = llvm::Function::create(llvm::FunctionTypeRef::get(void_ty, &[], false), "", ctx.module);
+    let block = ctx.builder.get_insert_block();
+    let ret_block = (!ctx.current_function.is_null()).then(|| unsafe{ (*ctx.current_function).ret_block.take() }).flatten();
+    ctx.builder.set_insert_point(&llvm::BasicBlock::new(ctx.ctx, "", Some(&func), None));
+
+    let mut scope = super::Scope::with_parent(&body.scope);
+    for expr in &mut body.unreachable {
+        expr.gen_stmt(&mut scope, ctx);
+    }
+
+    if !ctx.current_function.is_null() {
+        unsafe{ (*ctx.current_function).ret_block = ret_block };
+    }
+    ctx.builder.set_insert_point(&block);
+    func.erase_from_parent();
+}
 
 
 #[derive(Debug)]
 pub struct IfStmt {
+    loc: Loc,
     cond: Box<AST>,
     then_body: Block,
     else_body: Option<Block>,
 }
 
 impl IfStmt {
-    pub fn new(cond: Box<AST>, then_body: Block, else_body: Option<Block>) -> Self {
-        Self{ cond, then_body, else_body }
+    pub fn new(loc: Loc, cond: Box<AST>, then_body: Block, else_body: Option<Block>) -> Self {
+        Self{ loc, cond, then_body, else_body }
+    }
+
+    /*
+     *  an `if` is an expression if both branches end in a value or leave through `return`,
+     *  `break` or `continue`. `else if` chains are an else branch containing only the nested `if`
+     */
+    pub fn yields(&self) -> bool {
+        let Some(else_body) = &self.else_body else { return false; };
+        let produces = |body: &Block| body.yields || matches!(body.body.last(), Some(AST::ReturnExpr(_) | AST::BreakExpr(_) | AST::ContinueExpr(_)));
+        return produces(&self.then_body) && produces(else_body) && (self.then_body.yields || else_body.yields);
+    }
+
+    /*
+     *  NOTE(S): the variables of a branch are only declared while its code is generated, so they
+     *  are declared by type in a temporary scope to type the value of the branch
+     */
+    fn yield_type(body: &Block, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<Type> {
+        let mut tmp = super::Scope::with_parent(scope);
+        for expr in &body.body[..body.body.len() - 1] {
+            match expr {
+                AST::VarDecl(decl) => decl.declare_type(&mut tmp, ctx),
+                AST::TupleDecl(decl) => decl.declare_type(&mut tmp, ctx),
+                _ => {},
+            }
+        }
+        return body.body.last().unwrap().get_type(&mut tmp, ctx);
+    }
+
+    fn gen_branch(body: &mut Block, result: &Option<(llvm::ValueRef, Type)>, scope: &mut super::Scope, ctx: &crate::GenerationContext) {
+        body.scope.set_parent(scope);
+        let stmts = if result.is_some() { body.body.len() - 1 } else { body.body.len() };
+        for expr in &mut body.body[..stmts] {
+            expr.gen_stmt(&mut body.scope, ctx);
+        }
+        gen_unreachable(body, ctx);
+
+        /*
+         *  NOTE(S): a branch ending in `return` or `break` does not produce a value
+         */
+        if let Some((alloca, typ)) = result {
+            let last = body.body.last_mut().unwrap();
+            let value = last.gen_code(&mut body.scope, ctx);
+            if let Some(mut value) = value.filter(|_| last.get_type(&mut body.scope, ctx).is_some()) {
+                if last.should_load() {
+                    value = value.try_load(&body.scope.resolve_type(typ, ctx), ctx.builder);
+                }
+                body.scope.gen_move(last, ctx);
+                ctx.builder.create_store(&value, alloca);
+            }
+        }
+        body.scope.gen_exit(ctx);
     }
 }
 
 impl Generate for IfStmt {
     fn loc(&self) -> &crate::lexer::Loc {
-        error_msg("unreachable", "unreachable");
+        &self.loc
     }
 
     fn get_value(&self) -> String {
@@ -41,6 +239,12 @@ impl Generate for IfStmt {
             unsafe{(*ctx.current_function).ret_block = Some(llvm::BasicBlock::new(ctx.ctx, "ret", Some(&func), None))};
         }
 
+        let result = match self.yields().then(|| self.get_type(scope, ctx)).flatten() {
+            Some(Type::Path(path)) if path.path == "void" => None,
+            Some(typ) => Some((ctx.builder.create_alloca(&scope.resolve_type(&typ, ctx), None), typ)),
+            None => None,
+        };
+
         let true_block = llvm::BasicBlock::new(ctx.ctx, "t", Some(&func), None);
         let false_block = llvm::BasicBlock::new(ctx.ctx, "f", Some(&func), None);
         let merge_block = llvm::BasicBlock::new(ctx.ctx, "m", Some(&func), None);
@@ -58,10 +262,7 @@ impl Generate for IfStmt {
         });
 
         ctx.builder.set_insert_point(&true_block);
-        self.then_body.scope.set_parent(scope);
-        for expr in &mut self.then_body.body {
-            expr.gen_code(&mut self.then_body.scope, ctx);
-        }
+        Self::gen_branch(&mut self.then_body, &result, scope, ctx);
 
         if true_block.get_terminator().is_none() || ctx.builder.get_insert_block().get_terminator().is_none() {
             ctx.builder.create_br(&merge_block);
@@ -69,10 +270,7 @@ impl Generate for IfStmt {
 
         if let Some(body) = &mut self.else_body {
             ctx.builder.set_insert_point(&false_block);
-            body.scope.set_parent(scope);
-            for expr in &mut body.body {
-                expr.gen_code(&mut body.scope, ctx);
-            }
+            Self::gen_branch(body, &result, scope, ctx);
 
             if false_block.get_terminator().is_none() || ctx.builder.get_insert_block().get_terminator().is_none() {
                 ctx.builder.create_br(&merge_block);
@@ -83,36 +281,152 @@ impl Generate for IfStmt {
 
         if merge_block.has_n_uses(0) {
             merge_block.remove_from_parent();
-        }else {
-            ctx.builder.set_insert_point(&merge_block);
+            return None;
         }
 
-        None
+        ctx.builder.set_insert_point(&merge_block);
+        return result.map(|(alloca, typ)| alloca.try_load(&scope.resolve_type(&typ, ctx), ctx.builder));
     }
 
-    fn get_type(&self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<crate::types::Type> {
-        None
+    fn get_type(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<crate::types::Type> {
+        if !self.yields() {
+            if self.else_body.is_none() && self.then_body.yields {
+                error_msg_label(
+                    "`if` may be missing an `else` clause",
+                    ErrorLabel::from(&self.loc, "`if` expressions without `else` have no value"),
+                );
+            }
+            if self.then_body.yields || self.else_body.as_ref().is_some_and(|body| body.yields) {
+                error_msg_label(
+                    "`if` and `else` have incompatible types",
+                    ErrorLabel::from(&self.loc, "only one branch of this `if` produces a value"),
+                );
+            }
+            return None;
+        }
+
+        let else_body = self.else_body.as_ref().unwrap();
+        return match (Self::yield_type(&self.then_body, scope, ctx), Self::yield_type(else_body, scope, ctx)) {
+            (Some(then_ty), Some(else_ty)) => {
+                if !then_ty.matches(&else_ty) {
+                    error_msg_labels(
+                        "`if` and `else` have incompatible types", &[
+                            ErrorLabel::from(self.then_body.body.last().unwrap().loc(), format!("expected `{}` because of this", then_ty.get_full_name())),
+                            ErrorLabel::from(else_body.body.last().unwrap().loc(), format!("found `{}`", else_ty.get_full_name())),
+                    ]);
+                }
+                Some(then_ty)
+            },
+            (then_ty, else_ty) => then_ty.or(else_ty),
+        };
     }
 
     fn collect_symbols(&mut self, _scope: &mut super::Scope) {}
 }
 
+/*
+ *  NOTE(S): every loop pushes its exit and continue blocks together with its (optional) label
+ *  and the scope of its body
+ *  -> the loop stacks in `GenerationContext` always have the same length
+ */
+fn push_loop(label: &Option<Tagged<String>>, body: &super::Scope, break_point: &mut llvm::BasicBlock, continue_point: &mut llvm::BasicBlock, ctx: &crate::GenerationContext) {
+    unsafe {
+        let ctx = containers::to_mut(ctx);
+        ctx.current_break_point.push(break_point as *mut llvm::BasicBlock);
+        ctx.current_continue_point.push(continue_point as *mut llvm::BasicBlock);
+        ctx.current_loop_label.push(label.as_ref().map(|l| l.inner().clone()));
+        ctx.current_loop_scope.push(body as *const super::Scope);
+    }
+}
+
+fn pop_loop(ctx: &crate::GenerationContext) {
+    unsafe {
+        let ctx = containers::to_mut(ctx);
+        ctx.current_break_point.pop();
+        ctx.current_continue_point.pop();
+        ctx.current_loop_label.pop();
+        ctx.current_loop_scope.pop();
+    }
+}
+
+/*
+ *  the loop stacks of the function that is currently generated
+ */
+pub struct Loops(Vec<*mut llvm::BasicBlock>, Vec<*mut llvm::BasicBlock>, Vec<Option<String>>, Vec<*const super::Scope>);
+
+/*
+ *  NOTE(S): a closure is its own function, `break` and `continue` inside of it can not leave a
+ *  loop of the enclosing function. the stacks are emptied while the closure is generated
+ */
+pub fn take_loops(ctx: &crate::GenerationContext) -> Loops {
+    unsafe {
+        let ctx = containers::to_mut(ctx);
+        return Loops(
+            std::mem::take(&mut ctx.current_break_point),
+            std::mem::take(&mut ctx.current_continue_point),
+            std::mem::take(&mut ctx.current_loop_label),
+            std::mem::take(&mut ctx.current_loop_scope),
+        );
+    }
+}
+
+pub fn restore_loops(loops: Loops, ctx: &crate::GenerationContext) {
+    unsafe {
+        let ctx = containers::to_mut(ctx);
+        ctx.current_break_point = loops.0;
+        ctx.current_continue_point = loops.1;
+        ctx.current_loop_label = loops.2;
+        ctx.current_loop_scope = loops.3;
+    }
+}
+
+fn loop_target(label: &Option<Tagged<String>>, loc: &Loc, kw: &str, ctx: &crate::GenerationContext) -> usize {
+    if ctx.current_loop_label.is_empty() {
+        error_msg_label(
+            format!("invalid {} point", kw).as_str(),
+            ErrorLabel::from(loc, format!("`{}` outside of a loop", kw).as_str()),
+        );
+    }
+
+    return match label {
+        Some(label) => match ctx.current_loop_label.iter().rposition(|l| l.as_ref() == Some(label.inner())) {
+            Some(i) => i,
+            None => error_msg_label(
+                format!("use of undeclared label `'{}`", label.inner()).as_str(),
+                ErrorLabel::from(label.loc(), "undeclared label"),
+            ),
+        },
+        None => ctx.current_loop_label.len() - 1,
+    };
+}
+
+fn gen_loop_body(body: &mut Block, ctx: &crate::GenerationContext) {
+    for expr in &mut body.body {
+        expr.gen_stmt(&mut body.scope, ctx);
+    }
+    gen_unreachable(body, ctx);
+    body.scope.gen_exit(ctx);
+}
+
 #[derive(Debug)]
 pub struct ForStmt {
+    loc: Loc,
+    label: Option<Tagged<String>>,
     cond: Box<AST>,
     var: Box<AST>,
+    step: Option<Box<AST>>,
     body: Block,
 }
 
 impl ForStmt {
-    pub fn new(cond: Box<AST>, var: Box<AST>, body: Block) -> Self {
-        Self{ cond, var, body }
+    pub fn new(loc: Loc, label: Option<Tagged<String>>, cond: Box<AST>, var: Box<AST>, step: Option<Box<AST>>, body: Block) -> Self {
+        Self{ loc, label, cond, var, step, body }
     }
 }
 
 impl Generate for ForStmt {
     fn loc(&self) -> &crate::lexer::Loc {
-        error_msg("unreachable", "unreachable");
+        &self.loc
     }
 
     fn get_value(&self) -> String {
@@ -122,8 +436,13 @@ impl Generate for ForStmt {
     fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
         let func = ctx.builder.get_insert_block().get_parent();
 
+        if self.body.returns && unsafe{(*ctx.current_function).ret_block.is_none()} {
+            unsafe{(*ctx.current_function).ret_block = Some(llvm::BasicBlock::new(ctx.ctx, "ret", Some(&func), None))};
+        }
+
         let cond_block = llvm::BasicBlock::new(ctx.ctx, "", Some(&func), None);
         let body_block = llvm::BasicBlock::new(ctx.ctx, "", Some(&func), None);
+        let mut step_block = llvm::BasicBlock::new(ctx.ctx, "", Some(&func), None);
         let mut merge_block = llvm::BasicBlock::new(ctx.ctx, "", Some(&func), None);
 
         self.body.scope.set_parent(scope);
@@ -141,12 +460,18 @@ impl Generate for ForStmt {
         ctx.builder.create_cond_br(&cond, &body_block, &merge_block);
 
         ctx.builder.set_insert_point(&body_block);
-        unsafe{ containers::to_mut(ctx).current_break_point.push(&mut merge_block as *mut llvm::BasicBlock) };
-        for expr in &mut self.body.body {
-            expr.gen_code(&mut self.body.scope, ctx);
+        push_loop(&self.label, &self.body.scope, &mut merge_block, &mut step_block, ctx);
+        gen_loop_body(&mut self.body, ctx);
+        pop_loop(ctx);
+
+        if ctx.builder.get_insert_block().get_terminator().is_none() {
+            ctx.builder.create_br(&step_block);
         }
-        unsafe{ containers::to_mut(ctx).current_break_point.pop() };
 
+        ctx.builder.set_insert_point(&step_block);
+        if let Some(step) = &mut self.step {
+            step.gen_code(&mut self.body.scope, ctx);
+        }
         ctx.builder.create_br(&cond_block);
 
         ctx.builder.set_insert_point(&merge_block);
@@ -160,14 +485,421 @@ impl Generate for ForStmt {
     fn collect_symbols(&mut self, _scope: &mut super::Scope) {}
 }
 
+/*
+ *  NOTE(S): `loop { ... }` is parsed into a `WhileStmt` without a condition
+ */
+#[derive(Debug)]
+pub struct WhileStmt {
+    loc: Loc,
+    label: Option<Tagged<String>>,
+    cond: Option<Box<AST>>,
+    body: Block,
+}
+
+impl WhileStmt {
+    pub fn new(loc: Loc, label: Option<Tagged<String>>, cond: Option<Box<AST>>, body: Block) -> Self {
+        Self{ loc, label, cond, body }
+    }
+}
+
+impl Generate for WhileStmt {
+    fn loc(&self) -> &crate::lexer::Loc {
+        &self.loc
+    }
+
+    fn get_value(&self) -> String {
+        error_msg("unreachable", "unreachable");
+    }
+
+    fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+        let func = ctx.builder.get_insert_block().get_parent();
+
+        if self.body.returns && unsafe{(*ctx.current_function).ret_block.is_none()} {
+            unsafe{(*ctx.current_function).ret_block = Some(llvm::BasicBlock::new(ctx.ctx, "ret", Some(&func), None))};
+        }
+
+        let mut cond_block = llvm::BasicBlock::new(ctx.ctx, "", Some(&func), None);
+        let body_block = llvm::BasicBlock::new(ctx.ctx, "", Some(&func), None);
+        let mut merge_block = llvm::BasicBlock::new(ctx.ctx, "", Some(&func), None);
+
+        self.body.scope.set_parent(scope);
+        ctx.builder.create_br(&cond_block);
+
+        ctx.builder.set_insert_point(&cond_block);
+        if let Some(cond) = &mut self.cond {
+            let mut value = cond.gen_code(scope, ctx).unwrap();
+            if cond.should_load() {
+                let base = cond.get_type(scope, ctx).unwrap();
+                value = value.try_load(&scope.resolve_type(&base, ctx), ctx.builder);
+            }
+            ctx.builder.create_cond_br(&value, &body_block, &merge_block);
+        }else {
+            ctx.builder.create_br(&body_block);
+        }
+
+        ctx.builder.set_insert_point(&body_block);
+        push_loop(&self.label, &self.body.scope, &mut merge_block, &mut cond_block, ctx);
+        gen_loop_body(&mut self.body, ctx);
+        pop_loop(ctx);
+
+        if ctx.builder.get_insert_block().get_terminator().is_none() {
+            ctx.builder.create_br(&cond_block);
+        }
+
+        ctx.builder.set_insert_point(&merge_block);
+        return None;
+    }
+
+    fn get_type(&self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<crate::types::Type> {
+        None
+    }
+
+    fn collect_symbols(&mut self, _scope: &mut super::Scope) {}
+}
+
+/*
+ *  `lo..hi` and `lo..=hi`, only valid as the iterable of a `for` loop or as the index of a slicing
+ *  expression `s[lo..hi]`
+ */
+#[derive(Debug)]
+pub struct RangeExpr {
+    loc: Loc,
+    pub lo: Box<AST>,
+    pub hi: Box<AST>,
+    pub inclusive: bool,
+}
+
+impl RangeExpr {
+    pub fn new(lo: Box<AST>, hi: Box<AST>, inclusive: bool) -> Self {
+        Self{ loc: lo.loc().clone() + hi.loc().clone(), lo, hi, inclusive }
+    }
+}
+
+impl Generate for RangeExpr {
+    fn loc(&self) -> &crate::lexer::Loc {
+        &self.loc
+    }
+
+    fn get_value(&self) -> String {
+        self.lo.get_value()
+    }
+
+    fn gen_code(&mut self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+        error_msg_label(
+            "invalid range expression",
+            ErrorLabel::from(&self.loc, "ranges can only be used as the iterable of a `for` loop or to slice an array"),
+        );
+    }
+
+    fn get_type(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<crate::types::Type> {
+        self.lo.get_type(scope, ctx)
+    }
+
+    fn collect_symbols(&mut self, _scope: &mut super::Scope) {}
+}
+
+/*
+ *  `for x in iter { ... }`, `iter` is either a range, an array, a slice or a struct implementing
+ *  `Iterator`. `x` is a fresh copy of the current element in every iteration
+ */
+#[derive(Debug)]
+pub struct ForInStmt {
+    loc: Loc,
+    label: Option<Tagged<String>>,
+    var: Tagged<String>,
+    iter: Box<AST>,
+    body: Block,
+}
+
+impl ForInStmt {
+    pub fn new(loc: Loc, label: Option<Tagged<String>>, var: Tagged<String>, iter: Box<AST>, body: Block) -> Self {
+        Self{ loc, label, var, iter, body }
+    }
+
+    fn bind(&mut self, typ: Type, alloca: llvm::ValueRef) {
+        self.body.scope.add_symbol(self.var.inner(), Symbol::Variable(Variable::new(self.var.loc().clone(), typ, alloca, true)));
+    }
+
+    fn gen_body(&mut self, step_block: &mut llvm::BasicBlock, merge_block: &mut llvm::BasicBlock, ctx: &crate::GenerationContext) {
+        push_loop(&self.label, &self.body.scope, merge_block, step_block, ctx);
+        gen_loop_body(&mut self.body, ctx);
+        pop_loop(ctx);
+
+        if ctx.builder.get_insert_block().get_terminator().is_none() {
+            ctx.builder.create_br(step_block);
+        }
+    }
+
+    fn gen_operand(operand: &mut AST, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> (Type, llvm::ValueRef) {
+        let typ = operand.get_type(scope, ctx).unwrap();
+        let mut value = operand.gen_code(scope, ctx).unwrap();
+        if operand.should_load() {
+            value = value.try_load(&scope.resolve_type(&typ, ctx), ctx.builder);
+        }
+        return (typ, value);
+    }
+
+    /*
+     *  NOTE(S): the upper bound is evaluated once before the loop. inclusive ranges check for the
+     *  last element before incrementing so `0..=255` does not overflow a `u8`
+     */
+    fn gen_range(&mut self, func: &llvm::Function, scope: &mut super::Scope, ctx: &crate::GenerationContext) {
+        let range = match &mut *self.iter {
+            AST::RangeExpr(range) => range,
+            _ => unreachable!(),
+        };
+
+        let (typ, lo) = Self::gen_operand(&mut range.lo, scope, ctx);
+        let (hi_ty, hi) = Self::gen_operand(&mut range.hi, scope, ctx);
+        if !typ.matches(&hi_ty) {
+            error_msg_labels(
+                "mismatched types in range", &[
+                    ErrorLabel::from(range.lo.loc(), format!("lower bound has type `{}`", typ.get_full_name())),
+                    ErrorLabel::from(range.hi.loc(), format!("upper bound has type `{}`", hi_ty.get_full_name())),
+            ]);
+        }
+        if !matches!(scope.resolve_alias(&typ), Type::Path(path) if matches!(path.path.as_str(), "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64")) {
+            error_msg_label(
+                format!("cannot iterate over a range of `{}`", typ.get_full_name()).as_str(),
+                ErrorLabel::from(&range.loc, "range bounds have to be integers"),
+            );
+        }
+        let inclusive = range.inclusive;
+        let loc = range.loc.clone();
+
+        let llvm_ty = scope.resolve_type(&typ, ctx);
+        let counter = ctx.builder.create_alloca(&llvm_ty, None);
+        ctx.builder.create_store(&lo, &counter);
+        let var = ctx.builder.create_alloca(&llvm_ty, None);
+        self.bind(typ.clone(), var.clone());
+
+        let cond_block = llvm::BasicBlock::new(ctx.ctx, "", Some(func), None);
+        let body_block = llvm::BasicBlock::new(ctx.ctx, "", Some(func), None);
+        let mut step_block = llvm::BasicBlock::new(ctx.ctx, "", Some(func), None);
+        let mut merge_block = llvm::BasicBlock::new(ctx.ctx, "", Some(func), None);
+        ctx.builder.create_br(&cond_block);
+
+        ctx.builder.set_insert_point(&cond_block);
+        let current = counter.try_load(&llvm_ty, ctx.builder);
+        let op = Tagged::new(loc.clone(), if inclusive { "<=" } else { "<" }.to_owned());
+        let cond = gen_binary_op(&op, &typ, &current, &hi, scope, ctx);
+        ctx.builder.create_cond_br(&cond, &body_block, &merge_block);
+
+        ctx.builder.set_insert_point(&body_block);
+        ctx.builder.create_store(&current, &var);
+        self.gen_body(&mut step_block, &mut merge_block, ctx);
+
+        ctx.builder.set_insert_point(&step_block);
+        let current = counter.try_load(&llvm_ty, ctx.builder);
+        if inclusive {
+            let inc_block = llvm::BasicBlock::new(ctx.ctx, "", Some(func), None);
+            let last = gen_binary_op(&Tagged::new(loc.clone(), "==".to_owned()), &typ, &current, &hi, scope, ctx);
+            ctx.builder.create_cond_br(&last, &merge_block, &inc_block);
+            ctx.builder.set_insert_point(&inc_block);
+        }
+        let one = llvm::ConstantInt::get(&llvm_ty, 1);
+        let next = gen_binary_op(&Tagged::new(loc, "+".to_owned()), &typ, &current, &one, scope, ctx);
+        ctx.builder.create_store(&next, &counter);
+        ctx.builder.create_br(&cond_block);
+
+        ctx.builder.set_insert_point(&merge_block);
+    }
+
+    /*
+     *  NOTE(S): arrays and slices both iterate over `len` elements starting at `data`
+     */
+    fn gen_elements(&mut self, elem: &Type, func: &llvm::Function, scope: &mut super::Scope, ctx: &crate::GenerationContext) {
+        let typ = self.iter.get_type(scope, ctx).unwrap();
+        let obj = crate::ast::binary::gen_operand(&mut self.iter, scope, ctx);
+        let obj_ty = scope.resolve_type(&typ, ctx);
+        let elem_ty = scope.resolve_type(elem, ctx);
+        let idx_ty = llvm::TypeRef::get_int(ctx.ctx, 64);
+        let zero = llvm::ConstantInt::get(&idx_ty, 0);
+
+        let (data, len) = match &typ {
+            Type::Array(arr) => (
+                ctx.builder.create_gep(&obj_ty, &obj, &[zero.clone(), zero.clone()], true),
+                llvm::ConstantInt::get(&idx_ty, arr.elems as i32),
+            ),
+            _ => (
+                ctx.builder.create_struct_gep(&obj_ty, &obj, 0).try_load(&llvm::TypeRef::get_ptr(elem_ty.clone(), 0), ctx.builder),
+                ctx.builder.create_struct_gep(&obj_ty, &obj, 1).try_load(&idx_ty, ctx.builder),
+            ),
+        };
+
+        let index = ctx.builder.create_alloca(&idx_ty, None);
+        ctx.builder.create_store(&zero, &index);
+        let var = ctx.builder.create_alloca(&elem_ty, None);
+        self.bind(elem.clone(), var.clone());
+
+        let cond_block = llvm::BasicBlock::new(ctx.ctx, "", Some(func), None);
+        let body_block = llvm::BasicBlock::new(ctx.ctx, "", Some(func), None);
+        let mut step_block = llvm::BasicBlock::new(ctx.ctx, "", Some(func), None);
+        let mut merge_block = llvm::BasicBlock::new(ctx.ctx, "", Some(func), None);
+        ctx.builder.create_br(&cond_block);
+
+        ctx.builder.set_insert_point(&cond_block);
+        let current = index.try_load(&idx_ty, ctx.builder);
+        ctx.builder.create_cond_br(&ctx.builder.create_icmp_ult(&current, &len), &body_block, &merge_block);
+
+        ctx.builder.set_insert_point(&body_block);
+        let ptr = ctx.builder.create_gep(&elem_ty, &data, &[current], true);
+        ctx.builder.create_store(&ptr.try_load(&elem_ty, ctx.builder), &var);
+        self.gen_body(&mut step_block, &mut merge_block, ctx);
+
+        ctx.builder.set_insert_point(&step_block);
+        let current = index.try_load(&idx_ty, ctx.builder);
+        ctx.builder.create_store(&ctx.builder.create_add(&current, &llvm::ConstantInt::get(&idx_ty, 1)), &index);
+        ctx.builder.create_br(&cond_block);
+
+        ctx.builder.set_insert_point(&merge_block);
+    }
+
+    /*
+     *  NOTE(S): desugars into
+     *  loop {
+     *      let next = iter.next();
+     *      if !next.is_some { break; }
+     *      let x = next.value;
+     *      ...
+     *  }
+     */
+    fn gen_iterator(&mut self, strct: &Tagged<String>, func: &llvm::Function, scope: &mut super::Scope, ctx: &crate::GenerationContext) {
+        let next = scope.get_struct(strct).get_function(self.iter.loc(), "next").clone();
+        let opt = match &next.ret_type {
+            Type::Path(path) if split_generic_args(&path.path).is_some_and(|(base, _)| base == "Option") => Tagged::new(path.loc.clone(), path.path.clone()),
+            ty => error_msg_label(
+                format!("`{}::next` has to return an `Option`", strct.inner()).as_str(),
+                ErrorLabel::from(&ty.get_loc(), format!("found `{}`", ty.get_full_name())),
+            ),
+        };
+        let fields = scope.get_struct(&opt).fields.clone();
+        let field = |name: &str| fields.iter().position(|f| f.id.inner() == name).unwrap();
+        let (is_some, value) = (field("is_some"), field("value"));
+
+        let it = crate::ast::binary::gen_operand(&mut self.iter, scope, ctx);
+        let opt_ty = scope.resolve_type(&next.ret_type, ctx);
+        let current = ctx.builder.create_alloca(&opt_ty, None);
+        self.bind(fields[value].typ.clone(), ctx.builder.create_struct_gep(&opt_ty, &current, value as u32));
+
+        let mut cond_block = llvm::BasicBlock::new(ctx.ctx, "", Some(func), None);
+        let body_block = llvm::BasicBlock::new(ctx.ctx, "", Some(func), None);
+        let mut merge_block = llvm::BasicBlock::new(ctx.ctx, "", Some(func), None);
+        ctx.builder.create_br(&cond_block);
+
+        ctx.builder.set_insert_point(&cond_block);
+        ctx.builder.create_store(&gen_operator_call(&next, &[it], scope, ctx), &current);
+        let flag = ctx.builder.create_struct_gep(&opt_ty, &current, is_some as u32);
+        let flag = flag.try_load(&scope.resolve_type(&fields[is_some].typ, ctx), ctx.builder);
+        ctx.builder.create_cond_br(&flag, &body_block, &merge_block);
+
+        ctx.builder.set_insert_point(&body_block);
+        let step_block = &mut cond_block as *mut llvm::BasicBlock;
+        self.gen_body(unsafe{ &mut *step_block }, &mut merge_block, ctx);
+
+        ctx.builder.set_insert_point(&merge_block);
+    }
+}
+
+impl Generate for ForInStmt {
+    fn loc(&self) -> &crate::lexer::Loc {
+        &self.loc
+    }
+
+    fn get_value(&self) -> String {
+        error_msg("unreachable", "unreachable");
+    }
+
+    fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+        let func = ctx.builder.get_insert_block().get_parent();
+
+        if self.body.returns && unsafe{(*ctx.current_function).ret_block.is_none()} {
+            unsafe{(*ctx.current_function).ret_block = Some(llvm::BasicBlock::new(ctx.ctx, "ret", Some(&func), None))};
+        }
+
+        self.body.scope.set_parent(scope);
+        if let AST::RangeExpr(_) = &*self.iter {
+            self.gen_range(&func, scope, ctx);
+            return None;
+        }
+
+        let typ = self.iter.get_type(scope, ctx).unwrap();
+        match &typ {
+            Type::Array(arr) => {
+                self.gen_elements(&arr.typ, &func, scope, ctx);
+                return None;
+            },
+            Type::Slice(slice) => {
+                self.gen_elements(&slice.typ, &func, scope, ctx);
+                return None;
+            },
+            _ => {},
+        }
+        super::lints::mark_assigned(&self.iter, scope);
+
+        match overloaded_struct(&typ, scope) {
+            Some(strct) if scope.get_struct(&strct).implements_trait("Iterator") => self.gen_iterator(&strct, &func, scope, ctx),
+            _ => error_msg_label_info(
+                format!("`{}` is not an iterator", typ.get_full_name()).as_str(),
+                ErrorLabel::from(self.iter.loc(), "cannot be iterated over"),
+                "`for` loops iterate over ranges, arrays, slices and structs implementing `Iterator`",
+            ),
+        }
+        return None;
+    }
+
+    fn get_type(&self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<crate::types::Type> {
+        None
+    }
+
+    fn collect_symbols(&mut self, _scope: &mut super::Scope) {}
+}
+
+/*
+ *  `defer stmt;`, `stmt` runs whenever control leaves the enclosing block
+ */
+#[derive(Debug)]
+pub struct DeferStmt {
+    loc: Loc,
+    stmt: Box<AST>,
+}
+
+impl DeferStmt {
+    pub fn new(loc: Loc, stmt: Box<AST>) -> Self {
+        Self{ loc, stmt }
+    }
+}
+
+impl Generate for DeferStmt {
+    fn loc(&self) -> &crate::lexer::Loc {
+        &self.loc
+    }
+
+    fn get_value(&self) -> String {
+        error_msg("unreachable", "unreachable");
+    }
+
+    fn gen_code(&mut self, scope: &mut super::Scope, _: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+        scope.add_defer(&self.stmt);
+        return None;
+    }
+
+    fn get_type(&self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<crate::types::Type> {
+        None
+    }
+
+    fn collect_symbols(&mut self, _scope: &mut super::Scope) {}
+}
+
 #[derive(Debug)]
 pub struct BreakExpr {
     loc: Loc,
+    label: Option<Tagged<String>>,
 }
 
 impl BreakExpr {
-    pub fn new(loc: Loc) -> Self {
-        Self{ loc }
+    pub fn new(loc: Loc, label: Option<Tagged<String>>) -> Self {
+        Self{ loc, label }
     }
 }
 
@@ -180,17 +912,437 @@ impl Generate for BreakExpr {
         error_msg("unreachable", "unreachable");
     }
 
-    fn gen_code(&mut self, _: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
-        if let Some(bb) = ctx.current_break_point.last() {
-            ctx.builder.create_br(unsafe{bb.as_ref().unwrap()});
+    fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+        let target = loop_target(&self.label, &self.loc, "break", ctx);
+        scope.gen_exit_until(ctx.current_loop_scope[target], ctx);
+        ctx.builder.create_br(unsafe{ctx.current_break_point[target].as_ref().unwrap()});
+        return None;
+    }
+
+    fn get_type(&self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<crate::types::Type> {
+        None
+    }
+
+    fn collect_symbols(&mut self, _scope: &mut super::Scope) {}
+}
+
+#[derive(Debug)]
+pub struct ContinueExpr {
+    loc: Loc,
+    label: Option<Tagged<String>>,
+}
+
+impl ContinueExpr {
+    pub fn new(loc: Loc, label: Option<Tagged<String>>) -> Self {
+        Self{ loc, label }
+    }
+}
+
+impl Generate for ContinueExpr {
+    fn loc(&self) -> &crate::lexer::Loc {
+        &self.loc
+    }
+
+    fn get_value(&self) -> String {
+        error_msg("unreachable", "unreachable");
+    }
+
+    fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+        let target = loop_target(&self.label, &self.loc, "continue", ctx);
+        scope.gen_exit_until(ctx.current_loop_scope[target], ctx);
+        ctx.builder.create_br(unsafe{ctx.current_continue_point[target].as_ref().unwrap()});
+        return None;
+    }
+
+    fn get_type(&self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<crate::types::Type> {
+        None
+    }
+
+    fn collect_symbols(&mut self, _scope: &mut super::Scope) {}
+}
+
+#[derive(Debug)]
+pub struct PatternBinding {
+    pub field: Tagged<String>,
+    pub id: Tagged<String>,
+}
+
+impl PatternBinding {
+    pub fn new(field: Tagged<String>, id: Tagged<String>) -> Self {
+        Self{ field, id }
+    }
+}
+
+#[derive(Debug)]
+pub enum Pattern {
+    Wildcard(Loc),
+    Literal(Tagged<i64>),
+    Range(Loc, i64, i64),
+    Variant(Tagged<String>, Tagged<String>, Option<Vec<PatternBinding>>),
+}
+
+impl Pattern {
+    pub fn loc(&self) -> &Loc {
+        match self {
+            Pattern::Wildcard(loc) => loc,
+            Pattern::Literal(lit) => lit.loc(),
+            Pattern::Range(loc, _, _) => loc,
+            Pattern::Variant(_, variant, _) => variant.loc(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MatchArm {
+    pub patterns: Vec<Pattern>,
+    pub body: Block,
+}
+
+impl MatchArm {
+    pub fn new(patterns: Vec<Pattern>, body: Block) -> Self {
+        Self{ patterns, body }
+    }
+}
+
+#[derive(Debug)]
+pub struct MatchStmt {
+    loc: Loc,
+    value: Box<AST>,
+    arms: Vec<MatchArm>,
+}
+
+impl MatchStmt {
+    pub fn new(loc: Loc, value: Box<AST>, arms: Vec<MatchArm>) -> Self {
+        Self{ loc, value, arms }
+    }
+
+    /*
+     *  returns the name of the underlying type with all aliases resolved
+     */
+    fn base_type_name(scope: &super::Scope, typ: &Type) -> String {
+        if let Type::Path(path) = typ {
+            if let Some(Symbol::TypeAlias(alias)) = scope.try_resolve_symbol(&Tagged::new(path.loc.clone(), path.path.clone())) {
+                return Self::base_type_name(scope, &alias.dest_type.clone());
+            }
+            return path.path.clone();
+        }
+        error_msg_label(
+            format!("cannot match on value of type `{}`", typ.get_full_name()).as_str(),
+            ErrorLabel::from(&typ.get_loc(), "expected integer or enum type"),
+        );
+    }
+
+    fn int_domain(name: &str) -> Option<(i128, i128)> {
+        return match name {
+            "i8" => Some((i8::MIN as i128, i8::MAX as i128)),
+            "i16" => Some((i16::MIN as i128, i16::MAX as i128)),
+            "i32" => Some((i32::MIN as i128, i32::MAX as i128)),
+            "i64" => Some((i64::MIN as i128, i64::MAX as i128)),
+            "u8" => Some((0, u8::MAX as i128)),
+            "u16" => Some((0, u16::MAX as i128)),
+            "u32" => Some((0, u32::MAX as i128)),
+            "u64" => Some((0, u64::MAX as i128)),
+            "bool" => Some((0, 1)),
+            _ => None,
+        };
+    }
+
+    /*
+     *  maps every pattern onto an inclusive range of values of the scrutinee
+     */
+    fn pattern_range(&self, pattern: &Pattern, enum_sym: Option<&symbol::Enum>, domain: (i128, i128)) -> (i128, i128) {
+        match pattern {
+            Pattern::Wildcard(_) => domain,
+            Pattern::Literal(lit) => {
+                if enum_sym.is_some() {
+                    error_msg_label(
+                        "mismatched types in match arm",
+                        ErrorLabel::from(lit.loc(), "expected enum variant, found integer literal"),
+                    );
+                }
+                let value = *lit.inner() as i128;
+                if value < domain.0 || value > domain.1 {
+                    error_msg_label(
+                        format!("literal `{}` is out of range for the matched type", value).as_str(),
+                        ErrorLabel::from(lit.loc(), format!("valid values are `{}..={}`", domain.0, domain.1)),
+                    );
+                }
+                (value, value)
+            },
+            Pattern::Range(loc, lo, hi) => {
+                if enum_sym.is_some() {
+                    error_msg_label(
+                        "mismatched types in match arm",
+                        ErrorLabel::from(loc, "expected enum variant, found range"),
+                    );
+                }
+                let (lo, hi) = ((*lo as i128).max(domain.0), (*hi as i128).min(domain.1));
+                if lo > hi {
+                    error_msg_label(
+                        "empty range in match arm",
+                        ErrorLabel::from(loc, "this range does not match any value"),
+                    );
+                }
+                (lo, hi)
+            },
+            Pattern::Variant(en, variant, bindings) => {
+                let Some(enum_sym) = enum_sym else {
+                    error_msg_label(
+                        "mismatched types in match arm",
+                        ErrorLabel::from(variant.loc(), "expected integer pattern, found enum variant"),
+                    );
+                };
+                if en.inner() != enum_sym.name.inner() {
+                    error_msg_labels(
+                        format!("expected variant of enum `{}`, found `{}`", enum_sym.name.inner(), en.inner()).as_str(), &[
+                        ErrorLabel::from(en.loc(), "pattern of different enum"),
+                        ErrorLabel::from(self.value.loc(), format!("this is of type `{}`", enum_sym.name.inner())),
+                    ]);
+                }
+                let Some((idx, def)) = enum_sym.get_variant(variant.inner()) else {
+                    error_msg_labels(
+                        format!("unknown enum variant `{}` in enum `{}`", variant.inner(), en.inner()).as_str(), &[
+                        ErrorLabel::from(variant.loc(), "unknown enum variant"),
+                        ErrorLabel::from(enum_sym.name.loc(), "enum defined here"),
+                    ]);
+                };
+                if let Some(bindings) = bindings {
+                    Self::check_bindings(bindings, enum_sym, def);
+                }
+                (idx as i128, idx as i128)
+            },
+        }
+    }
+
+    fn check_bindings(bindings: &[PatternBinding], enum_sym: &symbol::Enum, variant: &EnumVariant) {
+        let name = format!("{}::{}", enum_sym.name.inner(), variant.id.inner());
+        if variant.is_tuple && bindings.len() != variant.fields.len() {
+            let loc = bindings.first().map(|b| b.id.loc().clone()).unwrap_or_default();
+            error_msg_labels(
+                format!("variant `{}` has `{}` fields, but the pattern binds `{}`", name, variant.fields.len(), bindings.len()).as_str(), &[
+                ErrorLabel::from(&loc, "incorrect number of fields in pattern"),
+                ErrorLabel::from(variant.id.loc(), "variant defined here"),
+            ]);
+        }
+        for binding in bindings {
+            if !variant.fields.iter().any(|f| f.id.inner() == binding.field.inner()) {
+                error_msg_labels(
+                    format!("variant `{}` does not have a field named `{}`", name, binding.field.inner()).as_str(), &[
+                    ErrorLabel::from(binding.field.loc(), "unknown field"),
+                    ErrorLabel::from(variant.id.loc(), "variant defined here"),
+                ]);
+            }
+        }
+    }
+
+    /*
+     *  makes the payload fields of the matched variant available as immutable variables in the
+     *  scope of the arm, the variables point directly into the matched value
+     */
+    fn bind_payload(arm: &mut MatchArm, enum_sym: &symbol::Enum, payload: &llvm::ValueRef, ctx: &crate::GenerationContext) {
+        if arm.patterns.len() > 1 && arm.patterns.iter().any(|p| matches!(p, Pattern::Variant(_, _, Some(_)))) {
+            error_msg_label(
+                "cannot bind variant fields in or-patterns",
+                ErrorLabel::from(arm.patterns[0].loc(), "or-pattern with bindings"),
+            );
+        }
+        let Some(Pattern::Variant(_, variant, Some(bindings))) = arm.patterns.first() else {
+            return;
+        };
+        let (idx, def) = enum_sym.get_variant(variant.inner()).unwrap();
+        let variant_ty = arm.body.scope.resolve_variant_type(enum_sym, idx, ctx);
+        let payload = ctx.builder.create_pointer_cast(payload, &llvm::TypeRef::get_ptr(variant_ty.clone(), 0));
+        for binding in bindings {
+            if binding.id.inner() == "_" {
+                continue;
+            }
+            let field_idx = def.fields.iter().position(|f| f.id.inner() == binding.field.inner()).unwrap();
+            let field = ctx.builder.create_struct_gep(&variant_ty, &payload, field_idx as u32);
+            arm.body.scope.add_symbol(binding.id.inner(), Symbol::Variable(Variable::new(binding.id.loc().clone(), def.fields[field_idx].typ.clone(), field, true)));
+        }
+    }
+
+    fn check_exhaustive(&self, ranges: &[(i128, i128)], enum_sym: Option<&symbol::Enum>, domain: (i128, i128)) {
+        let mut sorted = ranges.to_vec();
+        sorted.sort();
+        let mut missing = Vec::new();
+        let mut next = domain.0;
+        for (lo, hi) in sorted {
+            if lo > next {
+                missing.push((next, lo - 1));
+            }
+            next = next.max(hi + 1);
+        }
+        if next <= domain.1 {
+            missing.push((next, domain.1));
+        }
+
+        if missing.is_empty() {
+            return;
+        }
+
+        if let Some(enum_sym) = enum_sym {
+            let names: Vec<String> = missing.iter()
+                .flat_map(|(lo, hi)| *lo..=*hi)
+                .map(|idx| format!("`{}::{}`", enum_sym.name.inner(), enum_sym.variants[idx as usize].id.inner()))
+                .collect();
+            error_msg_labels(
+                format!("non-exhaustive patterns: {} not covered", names.join(", ")).as_str(), &[
+                ErrorLabel::from(self.value.loc(), "not all variants are covered"),
+                ErrorLabel::from(enum_sym.name.loc(), format!("enum `{}` defined here", enum_sym.name.inner())),
+            ]);
+        }
+
+        let (lo, hi) = missing[0];
+        let range = if lo == hi { format!("`{}`", lo) } else { format!("`{}..={}`", lo, hi) };
+        error_msg_labels(
+            format!("non-exhaustive patterns: {} not covered", range).as_str(), &[
+            ErrorLabel::from(self.value.loc(), "add a wildcard arm `_` or cover all values"),
+        ]);
+    }
+}
+
+impl Generate for MatchStmt {
+    fn loc(&self) -> &crate::lexer::Loc {
+        &self.loc
+    }
+
+    fn get_value(&self) -> String {
+        error_msg("unreachable", "unreachable");
+    }
+
+    fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+        let func = ctx.builder.get_insert_block().get_parent();
+
+        if self.arms.iter().any(|arm| arm.body.returns) && unsafe{(*ctx.current_function).ret_block.is_none()} {
+            unsafe{(*ctx.current_function).ret_block = Some(llvm::BasicBlock::new(ctx.ctx, "ret", Some(&func), None))};
+        }
+
+        let typ = self.value.get_type(scope, ctx).unwrap();
+        let type_name = Self::base_type_name(scope, &typ);
+        let tagged = Tagged::new(typ.get_loc(), type_name.clone());
+        let enum_sym = if scope.is_enum(&tagged) { Some(scope.get_enum(&tagged).clone()) } else { None };
+
+        let domain = if let Some(e) = &enum_sym {
+            (0, e.variants.len() as i128 - 1)
+        }else if let Some(domain) = Self::int_domain(&type_name) {
+            domain
         }else {
             error_msg_label(
-                format!("invalid break point").as_str(),
-                ErrorLabel::from(&self.loc, "invalid break point"),
+                format!("cannot match on value of type `{}`", typ.get_full_name()).as_str(),
+                ErrorLabel::from(self.value.loc(), "expected integer or enum type"),
             );
+        };
+        let is_unsigned = enum_sym.is_some() || type_name.starts_with('u') || type_name == "bool";
+
+        let arm_ranges: Vec<Vec<(i128, i128)>> = self.arms.iter().map(|arm| {
+            arm.patterns.iter().map(|p| self.pattern_range(p, enum_sym.as_ref(), domain)).collect()
+        }).collect();
+        self.check_exhaustive(&arm_ranges.concat(), enum_sym.as_ref(), domain);
+
+        let mut value = self.value.gen_code(scope, ctx).unwrap();
+        let mut payload = None;
+        let int_ty = match &enum_sym {
+            Some(e) if e.has_payload() => {
+                let enum_ty = scope.resolve_type(&typ, ctx);
+                if !self.value.should_load() {
+                    let alloca = ctx.builder.create_alloca(&enum_ty, None);
+                    ctx.builder.create_store(&value, &alloca);
+                    value = alloca;
+                }
+                let tag_ty = scope.resolve_type(&e.typ, ctx);
+                payload = Some(ctx.builder.create_struct_gep(&enum_ty, &value, 1));
+                value = ctx.builder.create_load(&tag_ty, &ctx.builder.create_struct_gep(&enum_ty, &value, 0));
+                tag_ty
+            },
+            _ => {
+                let int_ty = scope.resolve_type(&typ, ctx);
+                if self.value.should_load() {
+                    value = value.try_load(&int_ty, ctx.builder);
+                }
+                int_ty
+            },
+        };
+
+        let arm_blocks: Vec<llvm::BasicBlock> = self.arms.iter().map(|_| llvm::BasicBlock::new(ctx.ctx, "arm", Some(&func), None)).collect();
+        let default_block = llvm::BasicBlock::new(ctx.ctx, "default", Some(&func), None);
+        let merge_block = llvm::BasicBlock::new(ctx.ctx, "m", Some(&func), None);
+
+        /*
+         *  single values become switch cases, ranges are tested in order in the default block
+         *  and a wildcard ends the chain
+         */
+        let switch = ctx.builder.create_switch(&value, &default_block, arm_ranges.iter().map(|r| r.len() as u32).sum());
+        let mut covered: Vec<(i128, i128)> = Vec::new();
+        let mut range_checks: Vec<(i128, i128, usize)> = Vec::new();
+        let mut fallback = None;
+        'arms: for (i, ranges) in arm_ranges.iter().enumerate() {
+            for &(lo, hi) in ranges {
+                if (lo, hi) == domain {
+                    fallback = Some(i);
+                    break 'arms;
+                }
+                if lo == hi {
+                    if !covered.iter().any(|&(clo, chi)| clo <= lo && lo <= chi) {
+                        switch.add_case(&llvm::ConstantInt::get_u64(&int_ty, lo as u64, !is_unsigned), &arm_blocks[i]);
+                    }
+                }else {
+                    range_checks.push((lo, hi, i));
+                }
+                covered.push((lo, hi));
+            }
         }
 
-        return None;
+        ctx.builder.set_insert_point(&default_block);
+        for (lo, hi, arm) in range_checks {
+            let lo_const = llvm::ConstantInt::get_u64(&int_ty, lo as u64, !is_unsigned);
+            let hi_const = llvm::ConstantInt::get_u64(&int_ty, hi as u64, !is_unsigned);
+            let upper_block = llvm::BasicBlock::new(ctx.ctx, "range", Some(&func), None);
+            let next_block = llvm::BasicBlock::new(ctx.ctx, "range", Some(&func), None);
+            let cond = if is_unsigned { ctx.builder.create_icmp_ule(&lo_const, &value) } else { ctx.builder.create_icmp_sle(&lo_const, &value) };
+            ctx.builder.create_cond_br(&cond, &upper_block, &next_block);
+            ctx.builder.set_insert_point(&upper_block);
+            let cond = if is_unsigned { ctx.builder.create_icmp_ule(&value, &hi_const) } else { ctx.builder.create_icmp_sle(&value, &hi_const) };
+            ctx.builder.create_cond_br(&cond, &arm_blocks[arm], &next_block);
+            ctx.builder.set_insert_point(&next_block);
+        }
+        /*
+         *  NOTE(S): the match is exhaustive at this point so without a wildcard arm the end of the
+         *  chain can never be reached
+         */
+        match fallback {
+            Some(arm) => ctx.builder.create_br(&arm_blocks[arm]),
+            None => ctx.builder.create_unreachable(),
+        };
+
+        for (arm, block) in self.arms.iter_mut().zip(arm_blocks.iter()) {
+            if block.has_n_uses(0) {
+                block.remove_from_parent();
+                continue;
+            }
+            ctx.builder.set_insert_point(block);
+            arm.body.scope.set_parent(scope);
+            if let (Some(e), Some(payload)) = (&enum_sym, &payload) {
+                Self::bind_payload(arm, e, payload, ctx);
+            }
+            for expr in &mut arm.body.body {
+                expr.gen_stmt(&mut arm.body.scope, ctx);
+            }
+            gen_unreachable(&mut arm.body, ctx);
+            arm.body.scope.gen_exit(ctx);
+
+            if ctx.builder.get_insert_block().get_terminator().is_none() {
+                ctx.builder.create_br(&merge_block);
+            }
+        }
+
+        if merge_block.has_n_uses(0) {
+            merge_block.remove_from_parent();
+        }else {
+            ctx.builder.set_insert_point(&merge_block);
+        }
+
+        None
     }
 
     fn get_type(&self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<crate::types::Type> {
@@ -1,6 +1,7 @@
-use crate::types::{Type, containers, FuncPtr};
+use crate::types::{Type, containers, Closure, mangle_name};
 use crate::lexer::{Tagged, Loc};
-use crate::ast::{symbol, Impl, symbol::{Symbol, Variable}, Block, Generate};
+use crate::log::{error_msg_labels_nonfatal, ErrorLabel};
+use crate::ast::{symbol, Impl, symbol::{Symbol, Variable}, Block, Generate, AST};
 
 #[derive(Debug, Clone)]
 pub struct FunctionArg {
@@ -13,17 +14,33 @@ pub struct Function {
     id: Tagged<String>,
     name_mangeled: String,
     obj: Option<Impl>,
-    args: Vec<FunctionArg>,
+    pub args: Vec<FunctionArg>,
     body: Block,
     pub ret_type: Type,
     is_def: bool,
     is_var_arg: bool,
     pub ret_block: Option<llvm::BasicBlock>,
+    env: Option<Environment>,
+}
+
+/*
+ *  the variables captured by a closure, the closure function takes a pointer to them as its
+ *  first argument
+ */
+#[derive(Debug)]
+struct Environment {
+    typ: llvm::TypeRef,
+    captures: Vec<(Tagged<String>, Type)>,
+    by_move: bool,
 }
 
 impl Function {
     pub fn new(id: Tagged<String>, obj: Option<Impl>, args: Vec<FunctionArg>, body: Block, ret_type: Type, is_def: bool, is_var_arg: bool) -> Self {
-        Self{ id: id.clone(), name_mangeled: id.inner().to_string(), obj, args, body, ret_type, is_def, is_var_arg, ret_block: None }
+        Self{ id: id.clone(), name_mangeled: mangle_name(id.inner()), obj, args, body, ret_type, is_def, is_var_arg, ret_block: None, env: None }
+    }
+
+    pub fn body_scope(&self) -> &super::Scope {
+        return &self.body.scope;
     }
 }
 
@@ -41,11 +58,14 @@ impl Generate for Function {
         self.body.scope.set_parent(scope);
 
         if let Some(obj) = &self.obj {
-            self.name_mangeled = obj.typ.inner().to_owned() + "_" + self.id.inner();
+            self.name_mangeled = mangle_name(obj.typ.inner()) + "_" + self.id.inner();
         }
 
         let func = ctx.module.get_function(&self.name_mangeled).unwrap_or_else(|| {
             let mut params = Vec::new();
+            if self.env.is_some() {
+                params.push(llvm::TypeRef::get_ptr(llvm::TypeRef::get_int(ctx.ctx, 8), 0));
+            }
             for param in &self.args {
                 params.push(self.body.scope.resolve_type(&param.typ, ctx));
             }
@@ -58,15 +78,45 @@ impl Generate for Function {
         if !self.is_def {
             return None;
         }
+        if self.obj.is_none() && self.env.is_none() {
+            super::lints::declare_function(&self.id, &self.name_mangeled);
+        }
 
         let bb = llvm::BasicBlock::new(&ctx.ctx, "", Some(&func), None);
         ctx.builder.set_insert_point(&bb);
 
-        for i in 0..func.args().len() {
-            let arg = &func.args()[i];
+        /*
+         *  NOTE(S): captured variables are declared first so arguments can shadow them, by
+         *  reference captures store the address of the variable in the environment
+         */
+        let env_args = self.env.is_some() as usize;
+        if let Some(env) = &self.env {
+            let data = &ctx.builder.create_pointer_cast(&func.args()[0], &llvm::TypeRef::get_ptr(env.typ.clone(), 0));
+            for (i, (id, typ)) in env.captures.iter().enumerate() {
+                let field = ctx.builder.create_struct_gep(&env.typ, data, i as u32);
+                let storage = if env.by_move {
+                    field
+                }else {
+                    ctx.builder.create_load(&llvm::TypeRef::get_ptr(self.body.scope.resolve_type(typ, ctx), 0), &field)
+                };
+                self.body.scope.add_symbol(id.inner(), Symbol::Variable(Variable::new(id.loc().clone(), typ.clone(), storage, false)));
+            }
+        }
+
+        for i in 0..func.args().len() - env_args {
+            let arg = &func.args()[i + env_args];
             let alloca = ctx.builder.create_alloca(&arg.get_type(), None);
             ctx.builder.create_store(arg, &alloca);
-            self.body.scope.add_symbol(self.args[i].id.inner(), Symbol::Variable(Variable::new(self.args[i].id.loc().clone(), self.args.get(i).unwrap().typ.clone(), alloca, false)));
+            let mut var = Variable::new(self.args[i].id.loc().clone(), self.args[i].typ.clone(), alloca, false);
+            super::lints::declare_local(&self.args[i].id, false, true);
+            /*
+             *  NOTE(S): arguments passed by value are owned by the callee
+             */
+            if let Some(flag) = self.body.scope.declare_drop_flag(&self.args[i].typ, true, ctx) {
+                var = var.with_drop_flag(flag);
+                self.body.scope.add_drop(var.clone());
+            }
+            self.body.scope.add_symbol(self.args[i].id.inner(), Symbol::Variable(var));
         }
 
         /*for (i, arg) in func.args().iter().enumerate() {
@@ -81,26 +131,63 @@ impl Generate for Function {
             }else { true }
         }else { true };
 
+        /*
+         *  NOTE(S): the analysis sets aside unreachable statements, it has to run before the body
+         *  is generated
+         */
+        if super::control_flow::analyze_flow(&mut self.body) && create_alloc {
+            let labels = [
+                ErrorLabel::from(self.id.loc(), "this function can reach its end without returning a value"),
+                ErrorLabel::from(&self.ret_type.get_loc(), format!("expected `{}` because of this return type", self.ret_type.get_full_name())),
+            ];
+            if self.id.inner().is_empty() {
+                error_msg_labels_nonfatal("missing return in closure", &labels);
+            }else {
+                error_msg_labels_nonfatal(&format!("missing return in function `{}`", self.id.inner()), &labels);
+            }
+        }
+
         if create_alloc {
             self.body.scope.set_return_alloc(ctx.builder.create_alloca(&self.body.scope.resolve_type(&self.ret_type, ctx), None));
         }
 
+        /*
+         *  NOTE(S): closures are generated while the enclosing function is being generated
+         */
+        let enclosing = ctx.current_function;
         unsafe{ containers::to_mut(ctx).current_function = self as *mut Function };
         for expr in &mut self.body.body {
-            expr.gen_code(&mut self.body.scope, ctx);
+            expr.gen_stmt(&mut self.body.scope, ctx);
+        }
+        super::control_flow::gen_unreachable(&mut self.body, ctx);
+
+        if !matches!(self.body.body.last(), Some(AST::ReturnExpr(_))) {
+            self.body.scope.gen_exit(ctx);
         }
 
         if let Some(bb) = &self.ret_block {
+            if ctx.builder.get_insert_block().get_terminator().is_none() {
+                ctx.builder.create_br(bb);
+            }
             ctx.builder.set_insert_point(bb);
         }
 
-        if let Some(alloc) = self.body.scope.get_return_alloc() {
+        /*
+         *  NOTE(S): the body of a closure can see the return value of its enclosing function
+         */
+        if let Some(alloc) = self.body.scope.get_return_alloc().filter(|_| create_alloc) {
             ctx.builder.create_ret(Some(&alloc.try_load(&scope.resolve_type(&self.ret_type, ctx), ctx.builder)));
         }else {
             ctx.builder.create_ret(None);
         }
 
-        func.verify();
+        /*
+         *  NOTE(S): a poisoned statement leaves the function incomplete, it is never emitted
+         */
+        if !crate::log::has_errors() {
+            func.verify();
+        }
+        unsafe{ containers::to_mut(ctx).current_function = enclosing };
         None
     }
 
@@ -137,11 +224,106 @@ impl Into<symbol::Function> for &mut Function {
 pub struct ClosureExpr {
     loc: Loc,
     inner: Function,
+    captures: Vec<Tagged<String>>,
+    by_move: bool,
 }
 
 impl ClosureExpr {
-    pub fn new(loc: Loc, inner: Function) -> Self {
-        Self{ loc, inner }
+    pub fn new(loc: Loc, inner: Function, captures: Vec<Tagged<String>>, by_move: bool) -> Self {
+        Self{ loc, inner, captures, by_move }
+    }
+
+    /*
+     *  NOTE(S): only locals of the enclosing function are captured, everything else is
+     *  accessed directly from the closure
+     */
+    fn resolve_captures(&self, scope: &super::Scope, ctx: &crate::GenerationContext) -> Vec<(Tagged<String>, Variable)> {
+        let until = unsafe{ (*ctx.current_function).body_scope() } as *const super::Scope;
+        let mut captures: Vec<(Tagged<String>, Variable)> = Vec::new();
+        for id in &self.captures {
+            if captures.iter().any(|(captured, _)| captured.inner() == id.inner()) {
+                continue;
+            }
+            if let Some(var) = scope.resolve_local(id.inner(), until) {
+                if var.poisoned {
+                    crate::log::poison();
+                }
+                super::lints::mark_captured(&var);
+                captures.push((id.clone(), var));
+            }
+        }
+        return captures;
+    }
+
+    /*
+     *  NOTE(S): by reference environments live on the stack of the enclosing function, `move`
+     *  environments are allocated on the heap so the closure can outlive it. the closure value
+     *  owns a heap environment like a struct implementing `Drop`, it is freed when the variable
+     *  holding the closure goes out of scope and passing the closure by value moves it
+     */
+    fn gen_env(&self, captures: &[(Tagged<String>, Variable)], scope: &super::Scope, ctx: &crate::GenerationContext) -> (llvm::TypeRef, llvm::ValueRef) {
+        let fields: Vec<llvm::TypeRef> = captures.iter().map(|(_, var)| {
+            let typ = scope.resolve_type(&var.typ, ctx);
+            if self.by_move { typ } else { llvm::TypeRef::get_ptr(typ, 0) }
+        }).collect();
+        let env_ty: llvm::TypeRef = llvm::StructTypeRef::get(ctx.ctx, &fields, false).into();
+
+        let env = if self.by_move {
+            let size = ctx.module.get_data_layout().get_type_size_in_bits(&env_ty) / 8;
+            let i8_ptr = llvm::TypeRef::get_ptr(llvm::TypeRef::get_int(ctx.ctx, 8), 0);
+            let malloc = ctx.module.get_function("malloc").unwrap_or_else(|| {
+                let fn_type = llvm::FunctionTypeRef::get(i8_ptr.clone(), &[llvm::TypeRef::get_int(ctx.ctx, 64)], false);
+                llvm::Function::create(fn_type, "malloc", ctx.module)
+            });
+            let size = llvm::ConstantInt::get(&llvm::TypeRef::get_int(ctx.ctx, 64), size.max(1) as i32);
+            let data = ctx.builder.create_call(&malloc.into(), &[size]);
+            ctx.builder.create_pointer_cast(&data, &llvm::TypeRef::get_ptr(env_ty.clone(), 0))
+        }else {
+            ctx.builder.create_alloca(&env_ty, None)
+        };
+
+        for (i, (_, var)) in captures.iter().enumerate() {
+            let field = ctx.builder.create_struct_gep(&env_ty, &env, i as u32);
+            if self.by_move {
+                let value = var.alloca.try_load(&scope.resolve_type(&var.typ, ctx), ctx.builder);
+                ctx.builder.create_store(&value, &field);
+                scope.gen_move_var(var, ctx);
+            }else {
+                ctx.builder.create_store(&var.alloca, &field);
+            }
+        }
+        return (env_ty, env);
+    }
+
+    /*
+     *  NOTE(S): the destructor of a `move` environment, the captured values were moved into it
+     *  and are dropped together with it
+     */
+    fn gen_env_drop(&self, env_ty: &llvm::TypeRef, captures: &[(Tagged<String>, Variable)], scope: &super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+        let name = format!("{}.drop", self.inner.name_mangeled);
+        if let Some(func) = ctx.module.get_function(&name) {
+            return func.into();
+        }
+
+        let i8_ptr = llvm::TypeRef::get_ptr(llvm::TypeRef::get_int(ctx.ctx, 8), 0);
+        let void_ty = llvm::TypeRef::get_void(ctx.ctx);
+        let func = llvm::Function::create(llvm::FunctionTypeRef::get(void_ty.clone(), &[i8_ptr.clone()], false), &name, ctx.module);
+        let free = ctx.module.get_function("free").unwrap_or_else(|| {
+            let fn_type = llvm::FunctionTypeRef::get(void_ty, &[i8_ptr.clone()], false);
+            llvm::Function::create(fn_type, "free", ctx.module)
+        });
+
+        let block = ctx.builder.get_insert_block();
+        ctx.builder.set_insert_point(&llvm::BasicBlock::new(ctx.ctx, "", Some(&func), None));
+        let data = func.args()[0].clone();
+        let env = ctx.builder.create_pointer_cast(&data, &llvm::TypeRef::get_ptr(env_ty.clone(), 0));
+        for (i, (_, var)) in captures.iter().enumerate() {
+            scope.gen_drop_value(&var.typ, &ctx.builder.create_struct_gep(env_ty, &env, i as u32), &var.loc, ctx);
+        }
+        ctx.builder.create_call(&free.into(), &[data]);
+        ctx.builder.create_ret(None);
+        ctx.builder.set_insert_point(&block);
+        return func.into();
     }
 }
 
@@ -153,21 +335,49 @@ impl Generate for ClosureExpr {
     fn get_value(&self) -> String { "closure".to_owned() }
 
     fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
-        let refs: Vec<llvm::TypeRef> = self.inner.args.iter().map(|i| {
-            scope.resolve_type(&i.typ, ctx)
-        }).collect();
+        if ctx.current_function.is_null() {
+            crate::log::error_msg_label("closures can only be created inside of functions", crate::log::ErrorLabel::from(&self.loc, "closure defined here"));
+        }
+
+        let captures = self.resolve_captures(scope, ctx);
+        let (env_ty, env) = self.gen_env(&captures, scope, ctx);
+        self.inner.name_mangeled = format!("{}.closure.{}", unsafe{ &(*ctx.current_function).name_mangeled }, self.loc.start);
+        let i8_ptr = llvm::TypeRef::get_ptr(llvm::TypeRef::get_int(ctx.ctx, 8), 0);
+        let env_drop = if self.by_move {
+            ctx.builder.create_pointer_cast(&self.gen_env_drop(&env_ty, &captures, scope, ctx), &i8_ptr)
+        }else {
+            ctx.builder.create_cast(llvm::CastOps::IntToPtr, &llvm::ConstantInt::get(&llvm::TypeRef::get_int(ctx.ctx, 64), 0), &i8_ptr)
+        };
+        self.inner.env = Some(Environment{
+            typ: env_ty,
+            captures: captures.into_iter().map(|(id, var)| (id, var.typ)).collect(),
+            by_move: self.by_move,
+        });
+
+        let block = ctx.builder.get_insert_block();
+        let loops = super::control_flow::take_loops(ctx);
+        let generated = crate::log::recover(|| self.inner.gen_code(scope, ctx));
+        super::control_flow::restore_loops(loops, ctx);
+        ctx.builder.set_insert_point(&block);
+        if generated.is_none() {
+            crate::log::poison();
+        }
 
-        let ty = llvm::FunctionTypeRef::get(scope.resolve_type(&self.inner.ret_type, ctx), &refs, false);
-        let func = llvm::Function::create(ty, "", ctx.module);
-        self.inner.gen_code(scope, ctx);
-        return Some(func.into());
+        let func: llvm::ValueRef = ctx.module.get_function(&self.inner.name_mangeled).unwrap().into();
+        let typ = self.get_type(scope, ctx).unwrap();
+        let fat_ty = scope.resolve_type(&typ, ctx);
+        let fat = ctx.builder.create_alloca(&fat_ty, None);
+        ctx.builder.create_store(&ctx.builder.create_pointer_cast(&func, &i8_ptr), &ctx.builder.create_struct_gep(&fat_ty, &fat, 0));
+        ctx.builder.create_store(&ctx.builder.create_pointer_cast(&env, &i8_ptr), &ctx.builder.create_struct_gep(&fat_ty, &fat, 1));
+        ctx.builder.create_store(&env_drop, &ctx.builder.create_struct_gep(&fat_ty, &fat, 2));
+        return Some(ctx.builder.create_load(&fat_ty, &fat));
     }
 
     fn get_type(&self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<Type> {
         let arg_tys = self.inner.args.iter().map(|i| {
             i.typ.clone()
         }).collect();
-        return Some(Type::FuncPtr(FuncPtr::new(self.loc.clone(), arg_tys, self.inner.ret_type.clone())));
+        return Some(Type::Closure(Closure::new(self.loc.clone(), arg_tys, self.inner.ret_type.clone())));
     }
 
     fn collect_symbols(&mut self, _: &mut super::Scope) {}
@@ -0,0 +1,264 @@
+use crate::types::{Type, TokenType};
+use crate::lexer::{Tagged, Token, Lexer, Loc};
+use crate::ast::{Generate, FunctionArg};
+use crate::ast::symbol::{self, Symbol, Template};
+use crate::log::{error_msg_label, error_msg_labels, ErrorLabel};
+use crate::parse::Parser;
+
+#[derive(Debug)]
+pub enum GenericKind {
+    Struct,
+    Impl,
+    Function(Vec<FunctionArg>, Type),
+}
+
+/*
+ *  a generic struct, impl or function, nothing is generated until the item is instantiated
+ *  through `Scope::instantiate`
+ */
+#[derive(Debug)]
+pub struct Generic {
+    id: Tagged<String>,
+    kind: GenericKind,
+    template: Template,
+}
+
+impl Generic {
+    pub fn new(id: Tagged<String>, kind: GenericKind, template: Template) -> Self {
+        Self{ id, kind, template }
+    }
+}
+
+impl Generate for Generic {
+    fn loc(&self) -> &crate::lexer::Loc {
+        self.id.loc()
+    }
+
+    fn get_value(&self) -> String {
+        self.id.inner().to_string()
+    }
+
+    fn gen_code(&mut self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+        None
+    }
+
+    fn get_type(&self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<Type> { None }
+
+    fn collect_symbols(&mut self, scope: &mut super::Scope) {
+        match &self.kind {
+            GenericKind::Struct | GenericKind::Impl => {
+                if !scope.is_struct(&self.id) {
+                    scope.add_symbol(self.id.inner(), Symbol::Struct(symbol::Struct::new_dummy(self.id.clone())));
+                }
+                let strct = scope.get_struct(&self.id);
+                if let GenericKind::Impl = self.kind {
+                    strct.impls.push(self.template.clone());
+                }else {
+                    strct.name = self.id.clone();
+                    strct.template = Some(self.template.clone());
+                }
+            },
+            GenericKind::Function(args, ret_type) => {
+                let mut func = symbol::Function::new(self.id.clone(), self.id.inner().clone(), args.clone(), ret_type.clone(), true);
+                func.template = Some(self.template.clone());
+                scope.add_symbol(self.id.inner(), Symbol::Function(func));
+            },
+        }
+    }
+}
+
+/*
+ *  splits `Map<str, Vec<i32>>` into `Map` and [`str`, `Vec<i32>`]
+ */
+pub fn split_generic_args(name: &str) -> Option<(String, Vec<String>)> {
+    let start = name.find('<')?;
+    if !name.ends_with('>') {
+        return None;
+    }
+
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in name[start + 1..name.len() - 1].chars() {
+        match c {
+            '<' | '[' | '(' => depth += 1,
+            '>' | ']' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(current.trim().to_owned());
+                current.clear();
+                continue;
+            },
+            _ => {},
+        }
+        current.push(c);
+    }
+    args.push(current.trim().to_owned());
+    return Some((name[..start].to_owned(), args));
+}
+
+pub fn parse_type_str(src: &str) -> Type {
+    let mut lexer = Lexer::from(src, &"internal".into());
+    return Parser::from(lexer.get_tokens()).parse_type();
+}
+
+fn type_tokens(typ: &Type, loc: &Loc, tokens: &mut Vec<Token>) {
+    match typ {
+        Type::Path(path) => tokens.push(Token::new(&path.path, TokenType::Id, loc.clone())),
+        Type::Pointer(ptr) => {
+            if ptr.is_ref {
+                tokens.push(Token::new("&", TokenType::Ampercent, loc.clone()));
+            }else {
+                tokens.push(Token::new("*", TokenType::OpMul, loc.clone()));
+            }
+            if ptr.is_mut {
+                tokens.push(Token::new("mut", TokenType::KwMut, loc.clone()));
+            }
+            type_tokens(&ptr.typ, loc, tokens);
+        },
+        Type::Array(arr) => {
+            tokens.push(Token::new("[", TokenType::BraceLeft, loc.clone()));
+            type_tokens(&arr.typ, loc, tokens);
+            tokens.push(Token::new(";", TokenType::Semi, loc.clone()));
+            tokens.push(Token::new(&arr.elems.to_string(), TokenType::Integer, loc.clone()));
+            tokens.push(Token::new("]", TokenType::BraceRight, loc.clone()));
+        },
+        Type::Slice(slice) => {
+            tokens.push(Token::new("[", TokenType::BraceLeft, loc.clone()));
+            type_tokens(&slice.typ, loc, tokens);
+            tokens.push(Token::new("]", TokenType::BraceRight, loc.clone()));
+        },
+        Type::Dyn(d) => {
+            if d.is_ref {
+                tokens.push(Token::new("&", TokenType::Ampercent, loc.clone()));
+            }else {
+                tokens.push(Token::new("*", TokenType::OpMul, loc.clone()));
+            }
+            if d.is_mut {
+                tokens.push(Token::new("mut", TokenType::KwMut, loc.clone()));
+            }
+            tokens.push(Token::new("dyn", TokenType::KwDyn, loc.clone()));
+            tokens.push(Token::new(&d.trat, TokenType::Id, loc.clone()));
+        },
+        Type::FuncPtr(_) | Type::Closure(_) | Type::Tuple(_) => {
+            let mut lexer = Lexer::from(&typ.get_full_name(), &"internal".into());
+            tokens.extend(lexer.get_tokens().into_iter().map(|t| Token::new(&t.value, t.typ, loc.clone())));
+        },
+    }
+}
+
+/*
+ *  the tokens of a type argument list, `<u8, Vec<i32>>`
+ */
+pub fn type_args_tokens(args: &[Type], loc: &Loc) -> Vec<Token> {
+    let mut tokens = vec![Token::new("<", TokenType::AngleLeft, loc.clone())];
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            tokens.push(Token::new(",", TokenType::Comma, loc.clone()));
+        }
+        type_tokens(arg, loc, &mut tokens);
+    }
+    tokens.push(Token::new(">", TokenType::AngleRight, loc.clone()));
+    return tokens;
+}
+
+/*
+ *  replaces every generic parameter in `template` with the tokens of the matching type
+ */
+pub fn substitute(template: &Template, name: &Tagged<String>, args: &[Type]) -> Vec<Token> {
+    let mut tokens = substitute_tokens(&template.header, &template.params, args);
+    tokens.push(Token::new(name.inner(), TokenType::Id, name.loc().clone()));
+    tokens.extend(substitute_tokens(&template.tokens, &template.params, args));
+    return tokens;
+}
+
+/*
+ *  replaces every identifier in `tokens` that names one of `params` with the tokens of the
+ *  matching type in `args`
+ */
+pub fn substitute_tokens(tokens: &[Token], params: &[Tagged<String>], args: &[Type]) -> Vec<Token> {
+    let mut out = Vec::new();
+    for tok in tokens {
+        match params.iter().position(|p| tok.typ == TokenType::Id && *p.inner() == tok.value) {
+            Some(idx) => type_tokens(&args[idx], &tok.loc, &mut out),
+            None => out.push(tok.clone()),
+        }
+    }
+    return out;
+}
+
+fn unify(param: &Type, arg: &Type, params: &[Tagged<String>], bindings: &mut Vec<Option<Type>>) {
+    match (param, arg) {
+        (Type::Path(p), _) => {
+            if let Some(idx) = params.iter().position(|g| *g.inner() == p.path) {
+                if let Some(bound) = &bindings[idx] {
+                    if !bound.matches(arg) {
+                        error_msg_labels(
+                            format!("conflicting types for generic parameter `{}`", p.path).as_str(), &[
+                            ErrorLabel::from(&arg.get_loc(), format!("`{}` inferred as `{}` here", p.path, arg.get_full_name())),
+                            ErrorLabel::from(params[idx].loc(), format!("but previously inferred as `{}`", bound.get_full_name())),
+                        ]);
+                    }
+                }
+                bindings[idx] = Some(arg.clone());
+                return;
+            }
+            let (Some((p_base, p_args)), Type::Path(a)) = (split_generic_args(&p.path), arg) else { return; };
+            let Some((a_base, a_args)) = split_generic_args(&a.path) else { return; };
+            if p_base == a_base && p_args.len() == a_args.len() {
+                for (p, a) in p_args.iter().zip(a_args.iter()) {
+                    unify(&parse_type_str(p), &parse_type_str(a), params, bindings);
+                }
+            }
+        },
+        (Type::Pointer(p), Type::Pointer(a)) => unify(&p.typ, &a.typ, params, bindings),
+        (Type::Array(p), Type::Array(a)) => unify(&p.typ, &a.typ, params, bindings),
+        (Type::Slice(p), Type::Slice(a)) => unify(&p.typ, &a.typ, params, bindings),
+        _ => {},
+    }
+}
+
+/*
+ *  infers the type arguments of a generic function from the types of the supplied arguments
+ */
+pub fn infer_type_args(func: &symbol::Function, call: &Tagged<String>, arg_types: &[Type]) -> Vec<Type> {
+    let template = func.template.as_ref().unwrap();
+    let mut bindings = vec![None; template.params.len()];
+    for (param, arg) in func.args.iter().zip(arg_types.iter()) {
+        unify(&param.typ, arg, &template.params, &mut bindings);
+    }
+
+    return bindings.into_iter().enumerate().map(|(i, ty)| {
+        ty.unwrap_or_else(|| {
+            error_msg_labels(
+                format!("cannot infer type for generic parameter `{}` of function `{}`", template.params[i].inner(), func.name.inner()).as_str(), &[
+                ErrorLabel::from(call.loc(), "cannot infer type"),
+                ErrorLabel::from(template.params[i].loc(), "generic parameter declared here"),
+            ]);
+        })
+    }).collect();
+}
+
+/*
+ *  the canonical name of an instantiated generic (`Vec<i32>`), used as the name of the instance
+ */
+pub fn instance_name<S: AsRef<str>>(base: S, args: &[Type]) -> String {
+    let args: Vec<String> = args.iter().map(|a| a.get_full_name()).collect();
+    return format!("{}<{}>", base.as_ref(), args.join(", "));
+}
+
+pub fn check_arity(template: &Template, name: &Tagged<String>, args: usize) {
+    if template.params.len() != args {
+        error_msg_labels(
+            format!("`{}` expects `{}` type arguments, but `{}` were supplied", name.inner(), template.params.len(), args).as_str(), &[
+            ErrorLabel::from(name.loc(), "incorrect number of type arguments"),
+            ErrorLabel::from(template.params[0].loc(), "generic parameters declared here"),
+        ]);
+    }
+}
+
+pub fn not_generic(name: &Tagged<String>) -> ! {
+    error_msg_label(
+        format!("type `{}` does not take type arguments", name.inner()).as_str(),
+        ErrorLabel::from(name.loc(), "not a generic type"),
+    );
+}
@@ -1,18 +1,190 @@
-use crate::types::Type;
-use crate::lexer::Tagged;
-use crate::ast::{Block, Generate, AST};
+use crate::types::{Type, Pointer, mangle_name};
+use crate::lexer::{Tagged, Loc, Token};
+use crate::ast::{Block, Generate, AST, Function};
 use crate::ast::symbol;
+use crate::log::{error_msg_labels, ErrorLabel};
+use crate::types::TokenType;
+use crate::parse::Parser;
+use crate::ast::generic::{split_generic_args, parse_type_str, instance_name, substitute_tokens, type_args_tokens};
 
 #[derive(Debug)]
 pub struct Impl {
     pub typ: Tagged<String>,
     pub body: Block,
     pub trat: Option<Tagged<String>>,
+    /*
+     *  the type arguments of a generic trait, `u8` in `impl Index<u8> for Buf`
+     */
+    pub trat_args: Vec<Type>,
 }
 
 impl Impl {
-    pub fn new(typ: Tagged<String>, body: Block, trat: Option<Tagged<String>>) -> Self {
-        Self{ typ, body, trat }
+    pub fn new(typ: Tagged<String>, body: Block, trat: Option<Tagged<String>>, trat_args: Vec<Type>) -> Self {
+        Self{ typ, body, trat, trat_args }
+    }
+}
+
+impl Impl {
+    /*
+     *  replaces `Self` (and the untyped `self` parameter) with the implementing type
+     */
+    fn resolve_self(&self, typ: &Type) -> Type {
+        return match typ {
+            Type::Path(path) if path.path.is_empty() || path.path == "Self" => {
+                let mut path = path.clone();
+                path.path = self.typ.inner().clone();
+                Type::Path(path)
+            },
+            Type::Pointer(ptr) => Type::Pointer(Pointer::new(ptr.loc.clone(), Box::new(self.resolve_self(&ptr.typ)), ptr.is_ref, ptr.is_mut)),
+            _ => typ.clone(),
+        };
+    }
+
+    /*
+     *  replaces the generic parameters of the trait with the type arguments of this impl
+     */
+    fn resolve_params(&self, typ: &Type, params: &[Tagged<String>]) -> Type {
+        return match typ {
+            Type::Path(path) => {
+                if let Some(idx) = params.iter().position(|p| *p.inner() == path.path) {
+                    return self.trat_args[idx].clone();
+                }
+                let Some((base, args)) = split_generic_args(&path.path) else { return typ.clone(); };
+                let args: Vec<Type> = args.iter().map(|arg| self.resolve_params(&parse_type_str(arg), params)).collect();
+                let mut path = path.clone();
+                path.path = instance_name(base, &args);
+                Type::Path(path)
+            },
+            Type::Pointer(ptr) => Type::Pointer(Pointer::new(ptr.loc.clone(), Box::new(self.resolve_params(&ptr.typ, params)), ptr.is_ref, ptr.is_mut)),
+            _ => typ.clone(),
+        };
+    }
+
+    fn same_type(lhs: &Type, rhs: &Type) -> bool {
+        return match (lhs, rhs) {
+            (Type::Pointer(lhs), Type::Pointer(rhs)) => lhs.is_ref == rhs.is_ref && lhs.is_mut == rhs.is_mut && Self::same_type(&lhs.typ, &rhs.typ),
+            _ => lhs.get_full_name() == rhs.get_full_name(),
+        };
+    }
+
+    fn type_name(typ: &Type) -> String {
+        return match typ {
+            Type::Pointer(ptr) => {
+                let prefix = if ptr.is_ref { "&" } else { "*" };
+                let mutability = if ptr.is_mut { "mut " } else { "" };
+                format!("{}{}{}", prefix, mutability, Self::type_name(&ptr.typ))
+            },
+            _ => typ.get_full_name(),
+        };
+    }
+
+    fn type_loc(typ: &Type, func: &Function) -> Loc {
+        let loc = typ.get_loc();
+        if loc.file.as_os_str() == "internal" {
+            return func.loc().clone();
+        }
+        return loc;
+    }
+
+    fn check_type(&self, params: &[Tagged<String>], required: &Type, required_fn: &Function, implemented: &Type, implemented_fn: &Function, what: &str) {
+        let expected = self.resolve_self(&self.resolve_params(required, params));
+        let found = self.resolve_self(implemented);
+        if !Self::same_type(&expected, &found) {
+            error_msg_labels(
+                format!("{} of method `{}` does not match trait `{}`", what, implemented_fn.get_value(), self.trat.as_ref().unwrap().inner()).as_str(), &[
+                ErrorLabel::from(&Self::type_loc(implemented, implemented_fn), format!("expected `{}`, found `{}`", Self::type_name(&expected), Self::type_name(&found))),
+                ErrorLabel::from(&Self::type_loc(required, required_fn), "type declared in trait here"),
+            ]);
+        }
+    }
+
+    fn has_method<S: AsRef<str>>(&self, name: S) -> bool {
+        return self.body.body.iter().any(|func| matches!(func, AST::Function(func) if func.get_value() == name.as_ref()));
+    }
+
+    /*
+     *  parses the default body of every trait method that is not overridden as part of this impl,
+     *  so it gets generated as `Type_method`
+     */
+    fn add_provided_methods(&mut self, scope: &mut super::Scope) {
+        let trat = self.trat.clone().unwrap();
+        let decl = scope.get_trait(&trat).get();
+        for (name, tokens) in &decl.provided {
+            if self.has_method(name) {
+                continue;
+            }
+
+            let loc = self.typ.loc().clone();
+            let mut stream = vec![
+                Token::new("impl", TokenType::KwImpl, loc.clone()),
+                Token::new(trat.inner(), TokenType::Id, trat.loc().clone()),
+            ];
+            if !self.trat_args.is_empty() {
+                stream.extend(type_args_tokens(&self.trat_args, &loc));
+            }
+            stream.extend([
+                Token::new("for", TokenType::KwFor, loc.clone()),
+                Token::new(self.typ.inner(), TokenType::Id, loc.clone()),
+                Token::new("{", TokenType::CurlyLeft, loc.clone()),
+            ]);
+            stream.extend(substitute_tokens(tokens, &decl.params, &self.trat_args));
+            stream.push(Token::new("}", TokenType::CurlyRight, loc));
+
+            let AST::Mod(module) = Parser::from(stream).build_ast() else { unreachable!() };
+            for item in module.body.body {
+                if let AST::Impl(r#impl) = item {
+                    self.body.body.extend(r#impl.body.body);
+                }
+            }
+        }
+    }
+
+    fn check_trait_args(&self, scope: &mut super::Scope) {
+        let trat = self.trat.as_ref().unwrap();
+        let decl = scope.get_trait(trat).get();
+        if decl.params.len() != self.trat_args.len() {
+            error_msg_labels(
+                format!("`{}` expects `{}` type arguments, but `{}` were supplied", trat.inner(), decl.params.len(), self.trat_args.len()).as_str(), &[
+                ErrorLabel::from(trat.loc(), "incorrect number of type arguments"),
+                ErrorLabel::from(decl.id.loc(), "trait declared here"),
+            ]);
+        }
+    }
+
+    /*
+     *  checks that every method of the implemented trait is present with a matching signature
+     */
+    fn check_trait_conformance(&self, scope: &mut super::Scope) {
+        let trat = self.trat.as_ref().unwrap();
+        let decl = scope.get_trait(trat).get();
+        for item in &decl.funcs.body {
+            let AST::Function(required) = item else { continue; };
+            let implemented = self.body.body.iter().find_map(|func| match func {
+                AST::Function(func) if func.get_value() == required.get_value() => Some(func),
+                _ => None,
+            });
+
+            let Some(implemented) = implemented else {
+                error_msg_labels(
+                    format!("not all trait items implemented, missing `{}`", required.get_value()).as_str(), &[
+                    ErrorLabel::from(trat.loc(), format!("missing `{}` in implementation of `{}` for `{}`", required.get_value(), trat.inner(), self.typ.inner())),
+                    ErrorLabel::from(required.loc(), format!("`{}` declared in trait `{}` here", required.get_value(), trat.inner())),
+                ]);
+            };
+
+            if implemented.args.len() != required.args.len() {
+                error_msg_labels(
+                    format!("method `{}` has `{}` parameters, but the declaration in trait `{}` has `{}`", implemented.get_value(), implemented.args.len(), trat.inner(), required.args.len()).as_str(), &[
+                    ErrorLabel::from(implemented.loc(), format!("expected `{}` parameters, found `{}`", required.args.len(), implemented.args.len())),
+                    ErrorLabel::from(required.loc(), format!("trait requires `{}` parameters", required.args.len())),
+                ]);
+            }
+
+            for (req, imp) in required.args.iter().zip(implemented.args.iter()) {
+                self.check_type(&decl.params, &req.typ, required, &imp.typ, implemented, "parameter type");
+            }
+            self.check_type(&decl.params, &required.ret_type, required, &implemented.ret_type, implemented, "return type");
+        }
     }
 }
 
@@ -28,7 +200,7 @@ impl Generate for Impl {
     fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
         for func in &mut self.body.body {
             if let AST::Function(func) = func {
-                func.gen_code(scope, ctx);
+                crate::log::recover(|| func.gen_code(scope, ctx));
             }
         }
         None
@@ -37,6 +209,11 @@ impl Generate for Impl {
     fn get_type(&self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<Type> { None }
 
     fn collect_symbols(&mut self, scope: &mut super::Scope) {
+        if self.trat.is_some() {
+            self.check_trait_args(scope);
+            self.add_provided_methods(scope);
+        }
+
         for func in &mut self.body.body {
             if let AST::Function(func) = func {
                 let strct = if let Some(strct) = scope.try_resolve_symbol(&self.typ) {
@@ -52,13 +229,14 @@ impl Generate for Impl {
                 };
 
                 let mut func_sym: symbol::Function = func.into();
-                func_sym.name_mangeled = strct.name.inner().to_owned() + "_" + func_sym.name.inner();
+                func_sym.name_mangeled = mangle_name(strct.name.inner()) + "_" + func_sym.name.inner();
                 strct.register_function(func_sym);
             }
         }
         if let Some(t) = &self.trat {
             let strct = scope.get_struct(&self.typ);
             strct.register_trait_impl(t);
+            self.check_trait_conformance(scope);
         }
     }
 }
@@ -38,10 +38,18 @@ impl Generate for InitializerListExpr {
             let tmp = ctx.builder.create_alloca(ty, None);
             for (i, init) in self.initializers.iter_mut().enumerate() {
                 let mut value = init.1.gen_code(scope, ctx).unwrap();
+                let base = init.1.get_type(scope, ctx).unwrap();
                 if init.1.should_load() {
-                    let base = init.1.get_type(scope, ctx).unwrap();
                     value = value.try_load(&scope.resolve_type(&base, ctx), ctx.builder);
                 }
+                scope.gen_move(&init.1, ctx);
+                let field = match &init.0 {
+                    Some(name) => strct.fields.iter().find(|f| *f.id.inner() == *name.inner()),
+                    None => strct.fields.get(i),
+                };
+                if let Some(coerced) = field.and_then(|f| crate::ast::coerce(&value, &base, &f.typ, init.1.loc(), scope, ctx)) {
+                    value = coerced;
+                }
                 if let Some(name) = &init.0 {
                     let idx = if let Some(i) = strct.fields.iter().position(|f| *f.id.inner() == *name.inner()) {
                         i
@@ -174,6 +174,15 @@ pub fn macro_sizeof(call: &MacroCall, scope: &mut Scope, ctx: &GenerationContext
 
     let mut arg = call.args[0].clone();
     arg.push(crate::lexer::Token::new(";", crate::types::TokenType::Semi, arg.last().unwrap().loc.clone()));
+    /*
+     *  NOTE(S): anything longer than a name is a type (`*i8`, `Node<T>`), it would not survive
+     *  being parsed as an expression
+     */
+    if arg.len() > 2 {
+        let ty = scope.resolve_type(&Parser::from(arg).parse_type(), ctx);
+        let size = ctx.module.get_data_layout().get_type_size_in_bits(&ty);
+        return llvm::ConstantInt::get(&llvm::TypeRef::get_int(ctx.ctx, 64), size as i32 / 8);
+    }
     let mut parser = Parser::from(arg.clone());
     if let AST::Mod(m) = parser.build_ast() {
         let ident = &m.body.body[0];
@@ -211,9 +220,12 @@ pub fn macro_file(call: &MacroCall, _: &mut Scope, ctx: &GenerationContext) -> l
         );
 }
 
-pub fn macro_line(_call: &MacroCall, _scope: &mut Scope, _ctx: &GenerationContext) -> llvm::ValueRef {
-    todo!();
-    //return llvm::ConstantInt::get(&llvm::TypeRef::get_int(&ctx.ctx, 64), call.);
+pub fn macro_line(call: &MacroCall, _: &mut Scope, ctx: &GenerationContext) -> llvm::ValueRef {
+    return llvm::ConstantInt::get(&llvm::TypeRef::get_int(&ctx.ctx, 64), call.loc().line as i32);
+}
+
+pub fn macro_column(call: &MacroCall, _: &mut Scope, ctx: &GenerationContext) -> llvm::ValueRef {
+    return llvm::ConstantInt::get(&llvm::TypeRef::get_int(&ctx.ctx, 64), call.loc().column as i32);
 }
 
 pub fn impl_debug(call: &MacroCall, scope: &mut Scope, ctx: &GenerationContext) -> llvm::ValueRef {
@@ -277,6 +289,7 @@ pub fn impl_debug(call: &MacroCall, scope: &mut Scope, ctx: &GenerationContext)
     }
     func += "
                 debug.finish();
+                return FormattingError::None;
             }
         }
     ";
@@ -0,0 +1,331 @@
+use crate::ast::{AST, Generate, Scope, symbol::{Symbol, Variable}};
+use crate::lexer::{Loc, Tagged};
+use crate::log::{lint_msg_labels, ErrorLabel};
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/*
+ *  NOTE(S): lints are collected while the code is generated and reported once the whole file is
+ *  generated. generic instances and default trait methods generate the same code several times,
+ *  so everything is keyed by the location it was declared at
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+pub struct Lint {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+pub static LINTS: &[Lint] = &[
+    Lint{ name: "unused_variables", description: "`let` bindings and parameters that are never used" },
+    Lint{ name: "unused_mut", description: "`let mut` bindings that are never reassigned" },
+    Lint{ name: "unused_functions", description: "functions that are never called" },
+    Lint{ name: "unreachable_code", description: "statements after `return`, `break` or `continue`" },
+];
+
+/*
+ *  `-A warnings` or `-D warnings` applies to every lint
+ */
+const GROUP: &str = "warnings";
+
+struct Local {
+    name: String,
+    loc: Loc,
+    is_param: bool,
+    is_mut: bool,
+    used: bool,
+    assigned: bool,
+}
+
+#[derive(Default)]
+struct State {
+    /*
+     *  the flag that set the level of a lint, lints without an entry warn by default
+     */
+    levels: HashMap<&'static str, (Level, String)>,
+    locals: Vec<Local>,
+    local_index: HashMap<(PathBuf, usize), usize>,
+    functions: Vec<(Tagged<String>, String)>,
+    called: HashSet<String>,
+    function_names: HashSet<String>,
+    unreachable: Vec<(Loc, Loc)>,
+    unreachable_seen: HashSet<(PathBuf, usize)>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+fn key(loc: &Loc) -> (PathBuf, usize) {
+    return (loc.file.clone(), loc.start);
+}
+
+/*
+ *  NOTE(S): the group is applied before the single lints, so `-A warnings -W unused_mut` only
+ *  reports `unused_mut`. returns the name of the first unknown lint
+ */
+pub fn configure(warn: &[String], allow: &[String], deny: &[String]) -> Result<(), String> {
+    let flags = [(Level::Warn, "-W", warn), (Level::Allow, "-A", allow), (Level::Deny, "-D", deny)];
+    for names in flags.iter().map(|(_, _, names)| names) {
+        if let Some(name) = names.iter().find(|name| *name != GROUP && !LINTS.iter().any(|lint| lint.name == *name)) {
+            return Err(name.clone());
+        }
+    }
+
+    STATE.with(|state| {
+        let levels = &mut state.borrow_mut().levels;
+        for (level, flag, names) in &flags {
+            if names.iter().any(|name| name == GROUP) {
+                for lint in LINTS {
+                    levels.insert(lint.name, (*level, format!("{} {}", flag, GROUP)));
+                }
+            }
+        }
+        for (level, flag, names) in &flags {
+            for lint in LINTS.iter().filter(|lint| names.iter().any(|name| name == lint.name)) {
+                levels.insert(lint.name, (*level, format!("{} {}", flag, lint.name)));
+            }
+        }
+    });
+    return Ok(());
+}
+
+/*
+ *  `let` bindings and parameters, `_` and names starting with `_` are never reported
+ */
+pub fn declare_local(id: &Tagged<String>, is_mut: bool, is_param: bool) {
+    if id.inner().starts_with('_') || id.inner() == "self" || id.loc().line == 0 {
+        return;
+    }
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if state.local_index.contains_key(&key(id.loc())) {
+            return;
+        }
+        let index = state.locals.len();
+        state.local_index.insert(key(id.loc()), index);
+        state.locals.push(Local{ name: id.inner().clone(), loc: id.loc().clone(), is_param, is_mut, used: false, assigned: false });
+    });
+}
+
+fn update_local(loc: &Loc, update: impl FnOnce(&mut Local)) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if let Some(index) = state.local_index.get(&key(loc)).copied() {
+            update(&mut state.locals[index]);
+        }
+    });
+}
+
+pub fn mark_used(var: &Variable) {
+    update_local(&var.loc, |local| local.used = true);
+}
+
+/*
+ *  a closure can change the variables it captures by reference
+ */
+pub fn mark_captured(var: &Variable) {
+    update_local(&var.loc, |local| {
+        local.used = true;
+        local.assigned = true;
+    });
+}
+
+/*
+ *  NOTE(S): marks the variable an assignment, `&` or method call goes through as reassigned,
+ *  `a.b[i] = x` changes `a`
+ */
+pub fn mark_assigned(expr: &AST, scope: &Scope) {
+    let root = match expr {
+        AST::MemberExpr(member) => return mark_assigned(&member.obj, scope),
+        AST::AccessExpr(access) => return mark_assigned(&access.obj, scope),
+        AST::Id(id) => id,
+        _ => return,
+    };
+    if let Some(Symbol::Variable(var)) = scope.try_resolve_symbol(&root.id) {
+        update_local(&var.loc, |local| local.assigned = true);
+    }
+}
+
+/*
+ *  only free functions are reported, methods can be called through operators, `for` loops and
+ *  trait objects
+ */
+pub fn declare_function(id: &Tagged<String>, name_mangeled: &str) {
+    if id.inner() == "main" || id.inner().starts_with('_') || id.loc().line == 0 {
+        return;
+    }
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if state.function_names.insert(name_mangeled.to_owned()) {
+            state.functions.push((id.clone(), name_mangeled.to_owned()));
+        }
+    });
+}
+
+pub fn mark_called(name_mangeled: &str) {
+    STATE.with(|state| state.borrow_mut().called.insert(name_mangeled.to_owned()));
+}
+
+/*
+ *  reports the statements of a block that follow `exit`, a statement that never falls through
+ */
+pub fn unreachable_code(exit: &Loc, unreachable: &[AST]) {
+    let (Some(first), Some(last)) = (unreachable.first(), unreachable.last()) else { return; };
+    if first.loc().line == 0 {
+        return;
+    }
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if state.unreachable_seen.insert(key(exit)) {
+            let (first, last) = (first.loc().clone(), last.loc().clone());
+            let unreachable = if last.file == first.file && last.end > first.start { first + last } else { first };
+            state.unreachable.push((exit.clone(), unreachable));
+        }
+    });
+}
+
+fn level(lints: &HashMap<&'static str, (Level, String)>, name: &'static str) -> (Level, String) {
+    return lints.get(name).cloned().unwrap_or((Level::Warn, format!("-W {}", name)));
+}
+
+/*
+ *  NOTE(S): nothing is reported for a file with errors, the statements an error was found in
+ *  are skipped and would show up as unused. functions are only reported in `file`, there is no
+ *  `pub` so everything in an included file is treated as public
+ */
+pub fn report(file: &str) {
+    if crate::log::has_errors() {
+        return;
+    }
+
+    let state = STATE.with(|state| std::mem::take(&mut *state.borrow_mut()));
+    let mut lints: Vec<(&'static str, Loc, String, Vec<ErrorLabel>, Option<String>)> = Vec::new();
+    for local in &state.locals {
+        if !local.used {
+            let what = if local.is_param { "parameter" } else { "variable" };
+            lints.push((
+                "unused_variables",
+                local.loc.clone(),
+                format!("unused {}: `{}`", what, local.name),
+                vec![ErrorLabel::from(&local.loc, format!("this {} is never used", what))],
+                Some(format!("if this is intentional, prefix it with an underscore: `_{}`", local.name)),
+            ));
+        }else if local.is_mut && !local.assigned {
+            lints.push((
+                "unused_mut",
+                local.loc.clone(),
+                format!("variable `{}` does not need to be mutable", local.name),
+                vec![ErrorLabel::from(&local.loc, "this variable is never reassigned")],
+                Some("remove the `mut`".to_owned()),
+            ));
+        }
+    }
+
+    let input = PathBuf::from(file);
+    for (id, _) in state.functions.iter().filter(|(id, name)| id.loc().file == input && !state.called.contains(name)) {
+        lints.push((
+            "unused_functions",
+            id.loc().clone(),
+            format!("function `{}` is never called", id.inner()),
+            vec![ErrorLabel::from(id.loc(), "this function is never called")],
+            None,
+        ));
+    }
+
+    for (exit, unreachable) in &state.unreachable {
+        lints.push((
+            "unreachable_code",
+            unreachable.clone(),
+            "unreachable statement".to_owned(),
+            vec![
+                ErrorLabel::from(unreachable, "unreachable statement"),
+                ErrorLabel::from(exit, "any code following this statement is unreachable"),
+            ],
+            None,
+        ));
+    }
+
+    lints.sort_by(|a, b| (&a.1.file, a.1.start).cmp(&(&b.1.file, b.1.start)));
+    for (name, _, msg, labels, help) in lints {
+        let (level, flag) = level(&state.levels, name);
+        if level == Level::Allow {
+            continue;
+        }
+        let note = if state.levels.contains_key(name) {
+            format!("`{}` was given on the command line", flag)
+        }else {
+            format!("`{}` is on by default", flag)
+        };
+        lint_msg_labels(name, level == Level::Deny, &msg, &labels, &note, help.as_deref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(names: &[&str]) -> Vec<String> {
+        return names.iter().map(|name| name.to_string()).collect();
+    }
+
+    /*
+     *  the level of every lint after configuring a fresh state
+     */
+    fn configured(warn: &[&str], allow: &[&str], deny: &[&str]) -> Result<Vec<(&'static str, Level, String)>, String> {
+        STATE.with(|state| *state.borrow_mut() = State::default());
+        configure(&strings(warn), &strings(allow), &strings(deny))?;
+        let levels = STATE.with(|state| state.borrow().levels.clone());
+        return Ok(LINTS.iter().map(|lint| {
+            let (level, flag) = level(&levels, lint.name);
+            (lint.name, level, flag)
+        }).collect());
+    }
+
+    fn level_of(levels: &[(&'static str, Level, String)], name: &str) -> (Level, String) {
+        let (_, level, flag) = levels.iter().find(|(lint, _, _)| *lint == name).unwrap();
+        return (*level, flag.clone());
+    }
+
+    #[test]
+    fn every_lint_warns_by_default() {
+        for (name, level, flag) in configured(&[], &[], &[]).unwrap() {
+            assert_eq!((level, flag), (Level::Warn, format!("-W {}", name)));
+        }
+    }
+
+    #[test]
+    fn unknown_lints_are_rejected() {
+        assert_eq!(configured(&[], &["unused_variables", "unused_vars"], &[]), Err("unused_vars".to_owned()));
+        assert_eq!(configured(&["warning"], &[], &[]), Err("warning".to_owned()));
+    }
+
+    #[test]
+    fn groups() {
+        for (_, level, flag) in configured(&[], &[], &["warnings"]).unwrap() {
+            assert_eq!((level, flag.as_str()), (Level::Deny, "-D warnings"));
+        }
+        for (_, level, flag) in configured(&[], &["warnings"], &[]).unwrap() {
+            assert_eq!((level, flag.as_str()), (Level::Allow, "-A warnings"));
+        }
+    }
+
+    #[test]
+    fn single_lints_override_groups() {
+        let levels = configured(&["unused_mut"], &["warnings"], &[]).unwrap();
+        assert_eq!(level_of(&levels, "unused_mut"), (Level::Warn, "-W unused_mut".to_owned()));
+        assert_eq!(level_of(&levels, "unused_variables"), (Level::Allow, "-A warnings".to_owned()));
+
+        let levels = configured(&[], &["unreachable_code"], &["warnings"]).unwrap();
+        assert_eq!(level_of(&levels, "unreachable_code"), (Level::Allow, "-A unreachable_code".to_owned()));
+        assert_eq!(level_of(&levels, "unused_functions"), (Level::Deny, "-D warnings".to_owned()));
+    }
+}
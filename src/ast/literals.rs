@@ -5,7 +5,7 @@ use crate::ast::{symbol, symbol::Symbol};
 
 /*
  *  TODO(S):
- *  find out a better way to determine if a referece should be dereferenced
+ *  find out a better way to determine if a reference should be dereferenced
  *
  */
 
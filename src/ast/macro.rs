@@ -14,6 +14,7 @@ lazy_static! {
         map.insert("sizeof", crate::ast::intrinsics::macro_sizeof);
         map.insert("file", crate::ast::intrinsics::macro_file);
         map.insert("line", crate::ast::intrinsics::macro_line);
+        map.insert("column", crate::ast::intrinsics::macro_column);
         map.insert("impl_debug", crate::ast::intrinsics::impl_debug);
         map.insert("test_macro", crate::ast::intrinsics::test_macro);
 
@@ -80,6 +81,8 @@ impl Generate for MacroCall {
         match self.id.inner().as_str() {
             "format_args" => Some(Type::Path(Path::new(self.id.loc().clone(), "Arguments".into()))),
             "sizeof" => Some(Type::Path(Path::new(self.id.loc().clone(), "size_t".into()))),
+            "file" => Some(Type::Path(Path::new(self.id.loc().clone(), "str".into()))),
+            "line" | "column" => Some(Type::Path(Path::new(self.id.loc().clone(), "u64".into()))),
             "test_macro" => Some(Type::FuncPtr(crate::types::FuncPtr::new(self.id.loc().clone(), vec![
                 Type::Pointer(crate::types::Pointer::new(self.id.loc().clone(), Box::new(Type::Path(Path::new(self.id.loc().clone(), "i8".to_string()))), true, false)),
                 Type::Pointer(crate::types::Pointer::new(self.id.loc().clone(), Box::new(Type::Path(Path::new(self.id.loc().clone(), "Formatter".to_string()))), true, false)),
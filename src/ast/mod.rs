@@ -7,13 +7,15 @@ pub use module::Mod;
 mod scope;
 pub use scope::Scope;
 mod r#struct;
-pub use r#struct::{StructDef, StructField, EnumDef};
+pub use r#struct::{StructDef, StructField, EnumDef, EnumVariant};
 mod function;
 pub use function::{Function, FunctionArg, ClosureExpr};
 mod r#impl;
 pub use r#impl::Impl;
 mod r#trait;
 pub use r#trait::Trait;
+mod generic;
+pub use generic::{Generic, GenericKind, instance_name};
 mod vardecl;
 pub use vardecl::VarDecl;
 mod call;
@@ -21,18 +23,22 @@ pub use call::{ReturnExpr, CallExpr, MemberCallExpr};
 mod binary;
 pub use binary::BinaryExpr;
 mod casting;
-pub use casting::{UnaryExpr, CastExpr, get_cast_ops};
+pub use casting::{UnaryExpr, CastExpr, get_cast_ops, can_coerce, coerce};
 mod r#macro;
 pub use r#macro::{Macro, MacroCall, MacroArgType, MacroArg, MacroArm};
 mod assignment;
 pub use assignment::AssignmentExpr;
 mod control_flow;
-pub use control_flow::{IfStmt, ForStmt, BreakExpr};
+pub use control_flow::{IfStmt, ForStmt, ForInStmt, WhileStmt, RangeExpr, MatchStmt, MatchArm, Pattern, PatternBinding, BreakExpr, ContinueExpr, DeferStmt};
 mod literals;
 pub use literals::{TypeAlias, Identifier, NumberLiteral, StaticLiteral, StringLiteral, NumberType};
+mod tuple;
+pub use tuple::{TupleExpr, TupleDecl};
 mod initializers;
 pub use initializers::{Initializer, InitializerListExpr};
 pub mod symbol;
 mod preprocessor;
 pub use preprocessor::{Preprocessor, IncludeFile};
 mod intrinsics;
+mod checks;
+pub mod lints;
@@ -1,4 +1,4 @@
-use crate::ast::{Block, Generate};
+use crate::ast::{Block, Generate, AST};
 use crate::lexer::{Loc, Tagged};
 use crate::types::{Type, Path, Pointer};
 use crate::ast::symbol::{Symbol, Struct};
@@ -25,7 +25,18 @@ impl Generate for Mod {
 
     fn gen_code(&mut self, _: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
         for expr in &mut self.body.body {
-            expr.gen_code(&mut self.body.scope, ctx);
+            expr.gen_stmt(&mut self.body.scope, ctx);
+        }
+
+        /*
+         *  NOTE(S): generating an instance may instantiate further generics, so the list can
+         *  grow while it is being walked
+         */
+        let mut i = 0;
+        while i < self.body.scope.instances.len() {
+            let instance: *mut AST = &mut *self.body.scope.instances[i];
+            unsafe{ &mut *instance }.gen_stmt(&mut self.body.scope, ctx);
+            i += 1;
         }
         None
     }
@@ -43,7 +54,7 @@ impl Generate for Mod {
         self.body.scope.add_symbol("str", Symbol::Struct(strct));
 
         for expr in &mut self.body.body {
-            expr.collect_symbols(&mut self.body.scope);
+            crate::log::recover(|| expr.collect_symbols(&mut self.body.scope));
         }
 
         self.body.scope.check_structs();
@@ -87,7 +87,7 @@ impl Preprocessor {
         }
 
         if !self.macros.contains_key(call.id.inner()) {
-            error_msg_label("could not resolve macro", ErrorLabel::from(call.id.loc(), "unknwon macro"));
+            error_msg_label("could not resolve macro", ErrorLabel::from(call.id.loc(), "unknown macro"));
         }
 
         let m = self.macros.get(call.id.inner()).unwrap();
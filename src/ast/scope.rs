@@ -1,25 +1,38 @@
-use crate::types::{Type, containers::Pointer};
-use crate::ast::{symbol, symbol::Symbol};
-use crate::lexer::Tagged;
+use crate::types::{Type, containers::Pointer, mangle_name};
+use crate::ast::{symbol, symbol::Symbol, AST, Generate};
+use crate::ast::generic::{split_generic_args, parse_type_str, substitute, check_arity, not_generic};
+use crate::lexer::{Loc, Tagged};
 use crate::log::{error_msg_label, error_msg_labels, ErrorLabel};
+use crate::parse::Parser;
 
 use std::collections::HashMap;
 
+/*
+ *  NOTE(S): everything that has to run when a scope is left, in the order it was registered
+ */
+#[derive(Debug, Clone)]
+enum ScopeExit {
+    Defer(Pointer<AST>),
+    Drop(symbol::Variable),
+}
+
 #[derive(Debug)]
 pub struct Scope {
     pub parent: Pointer<Scope>,
     symbol_table: HashMap<String, symbol::Symbol>,
     //known_symbols: HashSet<String>,
     return_value: Option<llvm::ValueRef>,
+    pub instances: Vec<Box<AST>>,
+    exits: Vec<ScopeExit>,
 }
 
 impl Scope {
     pub fn new() -> Self {
-        Self { parent: Pointer::new(), symbol_table: HashMap::new(), /*known_symbols: HashSet::new(),*/ return_value: None }
+        Self { parent: Pointer::new(), symbol_table: HashMap::new(), /*known_symbols: HashSet::new(),*/ return_value: None, instances: Vec::new(), exits: Vec::new() }
     }
 
     pub fn with_parent(parent: &Scope) -> Self {
-        Self{ parent: Pointer::from(parent), symbol_table: HashMap::new(), /*known_symbols: HashSet::new(),*/ return_value: None }
+        Self{ parent: Pointer::from(parent), symbol_table: HashMap::new(), /*known_symbols: HashSet::new(),*/ return_value: None, instances: Vec::new(), exits: Vec::new() }
     }
 
     pub fn set_parent(&mut self, parent: &Scope) {
@@ -42,6 +55,176 @@ impl Scope {
         self.return_value = Some(alloc);
     }
 
+    pub fn add_defer(&mut self, stmt: &AST) {
+        self.exits.push(ScopeExit::Defer(Pointer::from(stmt)));
+    }
+
+    pub fn add_drop(&mut self, var: symbol::Variable) {
+        self.exits.push(ScopeExit::Drop(var));
+    }
+
+    /*
+     *  returns the struct whose `drop` has to be called for a local of type `typ`
+     */
+    pub fn drop_struct(&self, typ: &Type) -> Option<Tagged<String>> {
+        return crate::ast::binary::overloaded_struct(typ, self)
+            .filter(|strct| self.get_struct(strct).implements_trait("Drop"));
+    }
+
+    /*
+     *  structs implementing `Drop` and closures, a `move` closure owns its environment
+     */
+    pub fn needs_drop(&self, typ: &Type) -> bool {
+        return matches!(typ, Type::Closure(_)) || self.drop_struct(typ).is_some();
+    }
+
+    /*
+     *  NOTE(S): the drop flag is set by `let` and cleared when the value is moved, so a value is
+     *  only dropped when its variable still owns it
+     */
+    pub fn declare_drop_flag(&self, typ: &Type, owned: bool, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+        if !self.needs_drop(typ) {
+            return None;
+        }
+        let flag_ty = llvm::TypeRef::get_int(ctx.ctx, 1);
+        let flag = ctx.builder.create_alloca(&flag_ty, None);
+        ctx.builder.create_store(&llvm::ConstantInt::get(&flag_ty, owned as i32), &flag);
+        return Some(flag);
+    }
+
+    /*
+     *  marks the value of a local as moved when it is returned, passed or assigned by value
+     */
+    pub fn gen_move(&self, expr: &AST, ctx: &crate::GenerationContext) {
+        let AST::Id(_) = expr else { return; };
+        let name = Tagged::new(expr.loc().clone(), expr.get_value());
+        if let Some(Symbol::Variable(var)) = self.try_resolve_symbol(&name) {
+            self.gen_move_var(var, ctx);
+        }
+    }
+
+    pub fn gen_move_var(&self, var: &symbol::Variable, ctx: &crate::GenerationContext) {
+        if let Some(flag) = &var.drop_flag {
+            let flag_ty = llvm::TypeRef::get_int(ctx.ctx, 1);
+            ctx.builder.create_store(&llvm::ConstantInt::get(&flag_ty, 0), flag);
+        }
+    }
+
+    /*
+     *  NOTE(S): a value read out of a place (`x.field`, `v[i]`, `*p` or through a reference) is a
+     *  copy, the place still owns it and drops it. a local holding such a copy does not own it
+     */
+    pub fn owns_value(&self, expr: &AST) -> bool {
+        return match expr {
+            AST::MemberExpr(member) => matches!(member.member.as_deref(), Some(AST::CallExpr(_))),
+            AST::AccessExpr(_) => false,
+            AST::UnaryExpr(unary) => unary.op.inner() != "*",
+            AST::Id(id) => !matches!(
+                self.try_resolve_symbol(&id.id),
+                Some(Symbol::Variable(var)) if id.deref && matches!(&var.typ, Type::Pointer(ptr) if ptr.is_ref)
+            ),
+            _ => true,
+        };
+    }
+
+    /*
+     *  NOTE(S): assigning to a local drops the value it still owns, the variable owns the new
+     *  value afterwards unless it is a copy out of a place
+     */
+    pub fn gen_reinit(&self, expr: &AST, owned: bool, ctx: &crate::GenerationContext) {
+        let AST::Id(_) = expr else { return; };
+        let name = Tagged::new(expr.loc().clone(), expr.get_value());
+        if let Some(Symbol::Variable(var)) = self.try_resolve_symbol(&name).cloned() {
+            if let Some(flag) = &var.drop_flag {
+                self.gen_drop(&var, ctx);
+                let flag_ty = llvm::TypeRef::get_int(ctx.ctx, 1);
+                ctx.builder.create_store(&llvm::ConstantInt::get(&flag_ty, owned as i32), flag);
+            }
+        }
+    }
+
+    /*
+     *  calls `drop` on the value of `var` if it has not been moved
+     */
+    pub fn gen_drop(&self, var: &symbol::Variable, ctx: &crate::GenerationContext) {
+        let Some(flag) = var.drop_flag.as_ref().filter(|_| self.needs_drop(&var.typ)) else { return; };
+        let flag_ty = llvm::TypeRef::get_int(ctx.ctx, 1);
+
+        let parent = ctx.builder.get_insert_block().get_parent();
+        let drop_block = llvm::BasicBlock::new(ctx.ctx, "drop", Some(&parent), None);
+        let merge_block = llvm::BasicBlock::new(ctx.ctx, "", Some(&parent), None);
+        ctx.builder.create_cond_br(&flag.try_load(&flag_ty, ctx.builder), &drop_block, &merge_block);
+
+        ctx.builder.set_insert_point(&drop_block);
+        self.gen_drop_value(&var.typ, &var.alloca, &var.loc, ctx);
+        ctx.builder.create_store(&llvm::ConstantInt::get(&flag_ty, 0), flag);
+        ctx.builder.create_br(&merge_block);
+        ctx.builder.set_insert_point(&merge_block);
+    }
+
+    /*
+     *  NOTE(S): drops the value `ptr` points to. a closure stores the destructor of its
+     *  environment next to it, it is null for closures that capture by reference
+     */
+    pub fn gen_drop_value(&self, typ: &Type, ptr: &llvm::ValueRef, loc: &Loc, ctx: &crate::GenerationContext) {
+        if let Type::Closure(_) = typ {
+            let fat_ty = self.resolve_type(typ, ctx);
+            let i8_ptr = llvm::TypeRef::get_ptr(llvm::TypeRef::get_int(ctx.ctx, 8), 0);
+            let env = ctx.builder.create_load(&i8_ptr, &ctx.builder.create_struct_gep(&fat_ty, ptr, 1));
+            let drop = ctx.builder.create_load(&i8_ptr, &ctx.builder.create_struct_gep(&fat_ty, ptr, 2));
+
+            let parent = ctx.builder.get_insert_block().get_parent();
+            let drop_block = llvm::BasicBlock::new(ctx.ctx, "drop.env", Some(&parent), None);
+            let merge_block = llvm::BasicBlock::new(ctx.ctx, "", Some(&parent), None);
+            let int_ty = llvm::TypeRef::get_int(ctx.ctx, 64);
+            let addr = ctx.builder.create_cast(llvm::CastOps::PtrToInt, &drop, &int_ty);
+            ctx.builder.create_cond_br(&ctx.builder.create_icmp_ne(&addr, &llvm::ConstantInt::get(&int_ty, 0)), &drop_block, &merge_block);
+
+            ctx.builder.set_insert_point(&drop_block);
+            let fn_ty: llvm::TypeRef = llvm::FunctionTypeRef::get(llvm::TypeRef::get_void(ctx.ctx), &[i8_ptr], false).into();
+            ctx.builder.create_ptr_call(&fn_ty, &ctx.builder.create_pointer_cast(&drop, &llvm::TypeRef::get_ptr(fn_ty.clone(), 0)), &[env]);
+            ctx.builder.create_br(&merge_block);
+            ctx.builder.set_insert_point(&merge_block);
+            return;
+        }
+
+        let Some(strct) = self.drop_struct(typ) else { return; };
+        let func = self.get_struct(&strct).get_function(loc, "drop").clone();
+        crate::ast::binary::gen_operator_call(&func, &[ptr.clone()], self, ctx);
+    }
+
+    /*
+     *  generates the deferred statements and drops of this scope in reverse order, nothing is
+     *  generated once the current block is terminated
+     */
+    pub fn gen_exit(&mut self, ctx: &crate::GenerationContext) {
+        for exit in self.exits.clone().iter_mut().rev() {
+            if ctx.builder.get_insert_block().get_terminator().is_some() {
+                return;
+            }
+            match exit {
+                ScopeExit::Defer(stmt) => { stmt.as_mut().gen_code(self, ctx); },
+                ScopeExit::Drop(var) => self.gen_drop(var, ctx),
+            }
+        }
+    }
+
+    /*
+     *  NOTE(S): used by `return`, `break` and `continue` to leave every scope up to `until`,
+     *  starting with the innermost one
+     */
+    pub fn gen_exit_until(&mut self, until: *const Scope, ctx: &crate::GenerationContext) {
+        let mut scope = self as *mut Scope;
+        loop {
+            let current = unsafe{ &mut *scope };
+            current.gen_exit(ctx);
+            if std::ptr::eq(current, until) || current.parent.is_null() {
+                return;
+            }
+            scope = current.parent.as_mut() as *mut Scope;
+        }
+    }
+
     pub fn add_symbol<S: AsRef<str>>(&mut self, id: S, sym: Symbol) {
         let scope = self.resolve(id.as_ref());
         if let Some(symbol) = scope.symbol_table.get_mut(id.as_ref()) {
@@ -65,6 +248,7 @@ impl Scope {
     }
 
     pub fn try_resolve_symbol<S: AsRef<str>>(&self, name: &Tagged<S>) -> Option<&mut Symbol> {
+        self.instantiate(name.inner(), name.loc());
         if self.has_symbol(name.inner()) {
             return Some(self.resolve_symbol(name));
         }
@@ -72,10 +256,11 @@ impl Scope {
     }
 
     pub fn get_struct<S: AsRef<str>>(&self, name: &Tagged<S>) -> &mut symbol::Struct {
+        self.instantiate(name.inner(), name.loc());
         if !self.has_symbol(name.inner()) {
             error_msg_label(
                 format!("could not resolve struct type `{}`", name.inner().as_ref()).as_str(),
-                ErrorLabel::from(&name.loc(), "unknwon type")
+                ErrorLabel::from(&name.loc(), "unknown type")
             );
         }
         /*if let Symbol::Struct(strct) = self.resolve_symbol(name) {
@@ -83,7 +268,7 @@ impl Scope {
         }else {
             error_msg_label(
                 format!("could not resolve struct type `{}`", name.inner().as_ref()).as_str(),
-                ErrorLabel::from(&name.loc(), "unknwon type")
+                ErrorLabel::from(&name.loc(), "unknown type")
             );
         }*/
         match self.resolve_symbol(name) {
@@ -91,7 +276,7 @@ impl Scope {
             Symbol::TypeAlias(alias) => return self.get_struct(&Tagged::new(alias.dest_type.get_loc(), alias.dest_type.get_full_name())),
             _ => error_msg_label(
                 format!("could not resolve struct type `{}`", name.inner().as_ref()).as_str(),
-                ErrorLabel::from(&name.loc(), "unknwon type")
+                ErrorLabel::from(&name.loc(), "unknown type")
             ),
         }
     }
@@ -100,7 +285,7 @@ impl Scope {
         if !self.has_symbol(name.inner()) {
             error_msg_label(
                 format!("could not resolve function `{}`", name.inner().as_ref()).as_str(),
-                ErrorLabel::from(&name.loc(), "unknwon function")
+                ErrorLabel::from(&name.loc(), "unknown function")
             );
         }
         if let Symbol::Function(func) = self.resolve_symbol(name) {
@@ -108,7 +293,7 @@ impl Scope {
         }else {
             error_msg_label(
                 format!("could not resolve function `{}`", name.inner().as_ref()).as_str(),
-                ErrorLabel::from(&name.loc(), "unknwon function")
+                ErrorLabel::from(&name.loc(), "unknown function")
             );
         }
     }
@@ -117,15 +302,19 @@ impl Scope {
         if !self.has_symbol(name.inner()) {
             error_msg_label(
                 format!("could not resolve variable `{}`", name.inner().as_ref()).as_str(),
-                ErrorLabel::from(&name.loc(), "unknwon variable")
+                ErrorLabel::from(&name.loc(), "unknown variable")
             );
         }
         if let Symbol::Variable(var) = self.resolve_symbol(name) {
+            if var.poisoned {
+                crate::log::poison();
+            }
+            crate::ast::lints::mark_used(var);
             return var;
         }else {
             error_msg_label(
                 format!("could not resolve variable `{}`", name.inner().as_ref()).as_str(),
-                ErrorLabel::from(&name.loc(), "unknwon variable")
+                ErrorLabel::from(&name.loc(), "unknown variable")
             );
         }
     }
@@ -134,7 +323,7 @@ impl Scope {
         if !self.has_symbol(name.inner()) {
             error_msg_label(
                 format!("could not resolve enum `{}`", name.inner().as_ref()).as_str(),
-                ErrorLabel::from(&name.loc(), "unknwon enum")
+                ErrorLabel::from(&name.loc(), "unknown enum")
             );
         }
         if let Symbol::Enum(e) = self.resolve_symbol(name) {
@@ -142,7 +331,24 @@ impl Scope {
         }else {
             error_msg_label(
                 format!("could not resolve enum `{}`", name.inner().as_ref()).as_str(),
-                ErrorLabel::from(&name.loc(), "unknwon enum")
+                ErrorLabel::from(&name.loc(), "unknown enum")
+            );
+        }
+    }
+
+    pub fn get_trait<S: AsRef<str>>(&self, name: &Tagged<S>) -> &mut symbol::Trait {
+        if !self.has_symbol(name.inner()) {
+            error_msg_label(
+                format!("could not resolve trait `{}`", name.inner().as_ref()).as_str(),
+                ErrorLabel::from(&name.loc(), "unknown trait")
+            );
+        }
+        if let Symbol::Trait(t) = self.resolve_symbol(name) {
+            return t;
+        }else {
+            error_msg_label(
+                format!("could not resolve trait `{}`", name.inner().as_ref()).as_str(),
+                ErrorLabel::from(&name.loc(), "unknown trait")
             );
         }
     }
@@ -158,6 +364,7 @@ impl Scope {
     }
 
     pub fn is_struct<S: AsRef<str>>(&self, name: &Tagged<S>) -> bool {
+        self.instantiate(name.inner(), name.loc());
         if !self.has_symbol(name.inner()) {
             return false;
         }
@@ -167,6 +374,18 @@ impl Scope {
         return false;
     }
 
+    /*
+     *  follows type aliases until a type that is not an alias is reached, `size_t` -> `u64`
+     */
+    pub fn resolve_alias(&self, typ: &Type) -> Type {
+        if let Type::Path(path) = typ {
+            if let Some(Symbol::TypeAlias(alias)) = self.try_resolve_symbol(&Tagged::new(path.loc.clone(), path.path.clone())) {
+                return self.resolve_alias(&alias.dest_type.clone());
+            }
+        }
+        return typ.clone();
+    }
+
     pub fn resolve_type(&self, typ: &Type, ctx: &crate::GenerationContext) -> llvm::TypeRef {
         return match typ {
             Type::Path(path) => {
@@ -174,6 +393,7 @@ impl Scope {
                     return ty;
                 }
 
+                self.instantiate(&path.path, &path.loc);
                 let scope = self.resolve(&path.path);
                 if let Some(entry) = scope.symbol_table.get_mut(&path.path) {
                     match entry {
@@ -191,7 +411,29 @@ impl Scope {
                             }
                         }
                         Symbol::Enum(e) => {
-                            return self.resolve_type(&e.typ, ctx);
+                            if !e.has_payload() {
+                                return self.resolve_type(&e.typ, ctx);
+                            }
+                            if let Some(ty) = &e.generated {
+                                return ty.clone();
+                            }
+                            /*
+                             *  NOTE(S): enums with payloads are laid out as `{ tag, [n x i64] }` where
+                             *  the array is large enough to hold the biggest variant
+                             */
+                            let layout = ctx.module.get_data_layout();
+                            let size = (0..e.variants.len())
+                                .map(|i| layout.get_type_size_in_bits(&self.resolve_variant_type(e, i, ctx)))
+                                .max()
+                                .unwrap_or(0);
+                            let mut ty = llvm::StructTypeRef::create_opaque(ctx.ctx, e.name.inner());
+                            ty.set_body(&[
+                                self.resolve_type(&e.typ, ctx),
+                                llvm::ArrayTypeRef::get(&llvm::TypeRef::get_int(ctx.ctx, 64), (size + 63) / 64).into(),
+                            ], false);
+                            let ty = Into::<llvm::TypeRef>::into(ty);
+                            e.generated = Some(ty.clone());
+                            return ty;
                         }
                         Symbol::TypeAlias(a) => {
                             return self.resolve_type(&a.dest_type, ctx);
@@ -210,12 +452,22 @@ impl Scope {
             Type::Slice(slice) => {
                 return llvm::StructTypeRef::get(&ctx.ctx,
                         &[
-                            self.resolve_type(&slice.typ, ctx),
+                            llvm::TypeRef::get_ptr(self.resolve_type(&slice.typ, ctx), 0),
                             llvm::TypeRef::get_int(&ctx.ctx, 64)
                         ],
                 false)
                 .into();
             }
+            Type::Dyn(d) => {
+                let trat = self.get_trait(&Tagged::new(d.loc.clone(), d.trat.clone())).get();
+                return llvm::StructTypeRef::get(ctx.ctx,
+                        &[
+                            llvm::TypeRef::get_ptr(llvm::TypeRef::get_int(&ctx.ctx, 8), 0),
+                            llvm::TypeRef::get_ptr(trat.vtable_type(self, ctx), 0),
+                        ],
+                false)
+                .into();
+            }
             Type::FuncPtr(func) => {
                 let params: Vec<llvm::TypeRef> = func.params.iter().map(|param| self.resolve_type(param, ctx)).collect();
                 let func_ty = llvm::FunctionTypeRef::get(
@@ -225,14 +477,101 @@ impl Scope {
                 );
                 return llvm::TypeRef::get_ptr(func_ty.into(), 0);
             }
+            Type::Tuple(t) => {
+                let elems: Vec<llvm::TypeRef> = t.elems.iter().map(|elem| self.resolve_type(elem, ctx)).collect();
+                return llvm::StructTypeRef::get(ctx.ctx, &elems, false).into();
+            }
+            /*
+             *  NOTE(S): the function, its environment and the destructor of the environment
+             */
+            Type::Closure(_) => {
+                let ptr = llvm::TypeRef::get_ptr(llvm::TypeRef::get_int(&ctx.ctx, 8), 0);
+                return llvm::StructTypeRef::get(ctx.ctx, &[ptr.clone(), ptr.clone(), ptr], false).into();
+            }
         }
     }
 
+    pub fn resolve_variant_type(&self, e: &symbol::Enum, variant: usize, ctx: &crate::GenerationContext) -> llvm::TypeRef {
+        let fields: Vec<llvm::TypeRef> = e.variants[variant].fields.iter().map(|f| self.resolve_type(&f.typ, ctx)).collect();
+        return llvm::StructTypeRef::get(ctx.ctx, &fields, false).into();
+    }
+
     pub fn has_symbol<S: AsRef<str>>(&self, name: S) -> bool {
         let scope = self.resolve(name.as_ref());
         return scope.symbol_table.contains_key(name.as_ref());
     }
 
+    fn root(&self) -> &mut Self {
+        if self.parent.is_null() {
+            return unsafe{crate::types::containers::to_mut(self)};
+        }
+        return self.parent.as_ref().root();
+    }
+
+    /*
+     *  instantiates the generic struct (with all of its impls) or function `name` refers to, the
+     *  instance is registered in the root scope and generated after the rest of the module
+     */
+    pub fn instantiate<S: AsRef<str>>(&self, name: S, loc: &Loc) {
+        let Some((base, args)) = split_generic_args(name.as_ref()) else { return; };
+        let root = self.root();
+        if root.symbol_table.contains_key(name.as_ref()) || root.symbol_table.contains_key(&mangle_name(name.as_ref())) {
+            return;
+        }
+
+        let name = Tagged::new(loc.clone(), name.as_ref().to_owned());
+        let args: Vec<Type> = args.iter().map(|arg| parse_type_str(arg)).collect();
+        let mut items = Vec::new();
+        match root.symbol_table.get(&base) {
+            Some(Symbol::Struct(strct)) => {
+                let Some(template) = &strct.template else { not_generic(&name); };
+                check_arity(template, &name, args.len());
+                items.push(substitute(template, &name, &args));
+                for r#impl in &strct.impls {
+                    check_arity(r#impl, &name, args.len());
+                    items.push(substitute(r#impl, &name, &args));
+                }
+                /*
+                 *  NOTE(S): register the instance before parsing it, so self referencing
+                 *  instances (`*Node<T>`) do not get instantiated again
+                 */
+                root.symbol_table.insert(name.inner().clone(), Symbol::Struct(symbol::Struct::new_dummy(name.clone())));
+            },
+            Some(Symbol::Function(func)) if func.template.is_some() => {
+                let template = func.template.as_ref().unwrap();
+                check_arity(template, &name, args.len());
+                items.push(substitute(template, &name, &args));
+            },
+            Some(_) => not_generic(&name),
+            None => error_msg_label(
+                format!("could not resolve generic `{}`", base).as_str(),
+                ErrorLabel::from(name.loc(), "unknown generic")
+            ),
+        }
+
+        for tokens in items {
+            let AST::Mod(module) = Parser::from(tokens).build_ast() else { unreachable!() };
+            for mut item in module.body.body {
+                item.collect_symbols(root);
+                root.instances.push(Box::new(item));
+            }
+        }
+    }
+
+    /*
+     *  looks up a variable declared between this scope and `until`, used to find the locals a
+     *  closure captures
+     */
+    pub fn resolve_local(&self, name: &str, until: *const Scope) -> Option<symbol::Variable> {
+        if let Some(Symbol::Variable(var)) = self.symbol_table.get(name) {
+            return Some(var.clone());
+        }
+        if self.symbol_table.contains_key(name) || std::ptr::eq(self, until) || self.parent.is_null() {
+            return None;
+        }
+        return self.parent.as_ref().resolve_local(name, until);
+    }
+
     fn resolve(&self, id: &str) -> &mut Self {
         if self.symbol_table.contains_key(id) {
             return unsafe{crate::types::containers::to_mut(self)};
@@ -38,7 +38,7 @@ impl StructDef {
                 _ => format!(""),
             }.as_str();
         }
-        formatting_function += "debug.finish();}}";
+        formatting_function += "debug.finish(); return FormattingError::None; }}";
 
         let mut lexer = Lexer::from(&formatting_function, &"internal".into());
         return lexer.get_tokens();
@@ -74,8 +74,8 @@ impl Generate for StructDef {
     fn collect_symbols(&mut self, scope: &mut super::Scope) {
         if let Some(field) = self.is_self_referential() {
             error_msg_label_info(
-                "cannot self referece",
-                ErrorLabel::from(&field.typ.get_loc(), "referece to `Self`"),
+                "cannot self reference",
+                ErrorLabel::from(&field.typ.get_loc(), "reference to `Self`"),
                 "try adding a `*` or `&`"
             );
         }
@@ -91,15 +91,28 @@ impl Generate for StructDef {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct EnumVariant {
+    pub id: Tagged<String>,
+    pub fields: Vec<StructField>,
+    pub is_tuple: bool,
+}
+
+impl EnumVariant {
+    pub fn new(id: Tagged<String>, fields: Vec<StructField>, is_tuple: bool) -> Self {
+        Self{ id, fields, is_tuple }
+    }
+}
+
 #[derive(Debug)]
 pub struct EnumDef {
     id: Tagged<String>,
-    variants: Vec<String>,
+    variants: Vec<EnumVariant>,
     typ: Type,
 }
 
 impl EnumDef {
-    pub fn new(id: Tagged<String>, variants: Vec<String>, typ: Type) -> Self {
+    pub fn new(id: Tagged<String>, variants: Vec<EnumVariant>, typ: Type) -> Self {
         Self{ id, variants, typ }
     }
 }
@@ -1,7 +1,7 @@
 use crate::types::Type;
 use crate::types::containers::Pointer;
 use crate::log::{error_msg_label,  ErrorLabel};
-use crate::lexer::{Loc, Tagged};
+use crate::lexer::{Loc, Tagged, Token};
 
 use llvm::ValueRef;
 
@@ -38,11 +38,43 @@ pub struct Variable {
     pub typ: Type,
     pub alloca: ValueRef,
     pub is_const: bool,
+    /*
+     *  NOTE(S): only set for locals that get dropped at the end of their scope, the flag is
+     *  cleared once the value is moved out of the variable
+     */
+    pub drop_flag: Option<ValueRef>,
+    /*
+     *  NOTE(S): set for the variables of a `let` whose initializer had an error, a statement
+     *  using one of them is skipped without reporting anything
+     */
+    pub poisoned: bool,
 }
 
 impl Variable {
     pub fn new(loc: Loc, typ: Type, alloca: ValueRef, is_const: bool) -> Self {
-        Self{ loc, typ, alloca, is_const }
+        Self{ loc, typ, alloca, is_const, drop_flag: None, poisoned: false }
+    }
+
+    pub fn with_drop_flag(mut self, flag: ValueRef) -> Self {
+        self.drop_flag = Some(flag);
+        self
+    }
+}
+
+/*
+ *  the tokens of a generic item, instantiated by replacing every generic parameter with a concrete
+ *  type and parsing the result as `header name tokens`
+ */
+#[derive(Debug, Clone)]
+pub struct Template {
+    pub params: Vec<Tagged<String>>,
+    pub header: Vec<Token>,
+    pub tokens: Vec<Token>,
+}
+
+impl Template {
+    pub fn new(params: Vec<Tagged<String>>, header: Vec<Token>, tokens: Vec<Token>) -> Self {
+        Self{ params, header, tokens }
     }
 }
 
@@ -53,11 +85,12 @@ pub struct Function {
     pub args: Vec<crate::ast::FunctionArg>,
     pub ret_type: Type,
     pub is_definition: bool,
+    pub template: Option<Template>,
 }
 
 impl Function {
     pub fn new(name: Tagged<String>, name_mangeled: String, args: Vec<crate::ast::FunctionArg>, ret_type: Type, is_definition: bool) -> Self {
-        Self{ name, name_mangeled, args, ret_type, is_definition }
+        Self{ name, name_mangeled, args, ret_type, is_definition, template: None }
     }
 }
 
@@ -69,15 +102,17 @@ pub struct Struct {
     traits: HashSet<String>,
     pub generated: Option<llvm::TypeRef>,
     pub resolved: bool,
+    pub template: Option<Template>,
+    pub impls: Vec<Template>,
 }
 
 impl Struct {
     pub fn new(name: Tagged<String>, fields: Vec<crate::ast::StructField>) -> Self {
-        Self{ name, fields, functions: HashMap::new(), traits: HashSet::new(), generated: None, resolved: true }
+        Self{ name, fields, functions: HashMap::new(), traits: HashSet::new(), generated: None, resolved: true, template: None, impls: Vec::new() }
     }
 
     pub fn new_dummy(name: Tagged<String>) -> Self {
-        Self{ name, fields: Vec::new(), functions: HashMap::new(), traits: HashSet::new(), generated: None, resolved: true }
+        Self{ name, fields: Vec::new(), functions: HashMap::new(), traits: HashSet::new(), generated: None, resolved: true, template: None, impls: Vec::new() }
     }
 
     pub fn register_function(&mut self, func: Function) {
@@ -116,14 +151,23 @@ impl Struct {
 
 #[derive(Debug, Clone)]
 pub struct Enum {
-    name: Tagged<String>,
-    pub variants: Vec<String>,
+    pub name: Tagged<String>,
+    pub variants: Vec<crate::ast::EnumVariant>,
     pub typ: Type,
+    pub generated: Option<llvm::TypeRef>,
 }
 
 impl Enum {
-    pub fn new(name: Tagged<String>, variants: Vec<String>, typ: Type) -> Self {
-        Self{ name, variants, typ }
+    pub fn new(name: Tagged<String>, variants: Vec<crate::ast::EnumVariant>, typ: Type) -> Self {
+        Self{ name, variants, typ, generated: None }
+    }
+
+    pub fn has_payload(&self) -> bool {
+        return self.variants.iter().any(|v| !v.fields.is_empty());
+    }
+
+    pub fn get_variant<S: AsRef<str>>(&self, name: S) -> Option<(usize, &crate::ast::EnumVariant)> {
+        return self.variants.iter().enumerate().find(|(_, v)| v.id.inner() == name.as_ref());
     }
 }
 
@@ -159,4 +203,8 @@ impl Trait {
     pub fn new(inner: Pointer<crate::ast::Trait>) -> Self {
         Self{ inner }
     }
+
+    pub fn get(&self) -> &crate::ast::Trait {
+        return &self.inner;
+    }
 }
@@ -1,18 +1,113 @@
-use crate::types::Type;
-use crate::lexer::Tagged;
-use crate::ast::{Block, Generate};
+use crate::types::{Type, mangle_name};
+use crate::lexer::{Tagged, Token};
+use crate::ast::{Block, Generate, Function, AST};
 use crate::ast::{symbol, symbol::Symbol};
 use crate::types::containers::Pointer;
+use crate::ast::generic::{split_generic_args, parse_type_str};
 
 #[derive(Debug)]
 pub struct Trait {
     pub id: Tagged<String>,
+    /*
+     *  the generic parameters of the trait (`T` in `Index<T>`), every impl supplies a type for
+     *  each of them
+     */
+    pub params: Vec<Tagged<String>>,
     pub funcs: Block,
+    /*
+     *  the tokens of every method with a default body, these get parsed again inside of each
+     *  impl that does not override them
+     */
+    pub provided: Vec<(String, Vec<Token>)>,
 }
 
 impl Trait {
-    pub fn new(id: Tagged<String>, funcs: Block) -> Self {
-        Self{ id, funcs }
+    pub fn new(id: Tagged<String>, params: Vec<Tagged<String>>, funcs: Block) -> Self {
+        Self{ id, params, funcs, provided: Vec::new() }
+    }
+
+    /*
+     *  NOTE(S): the generic parameters of the trait are only known per impl, so they are treated
+     *  like `Self`
+     */
+    fn mentions_self(&self, typ: &Type) -> bool {
+        return match typ {
+            Type::Path(path) => {
+                let base = split_generic_args(&path.path);
+                path.path.is_empty() || path.path == "Self"
+                    || self.params.iter().any(|p| *p.inner() == path.path)
+                    || base.is_some_and(|(_, args)| args.iter().any(|arg| self.mentions_self(&parse_type_str(arg))))
+            },
+            Type::Pointer(ptr) => self.mentions_self(&ptr.typ),
+            Type::Array(arr) => self.mentions_self(&arr.typ),
+            Type::Slice(slice) => self.mentions_self(&slice.typ),
+            Type::FuncPtr(func) => func.params.iter().any(|p| self.mentions_self(p)) || self.mentions_self(&func.ret_type),
+            Type::Closure(c) => c.params.iter().any(|p| self.mentions_self(p)) || self.mentions_self(&c.ret_type),
+            Type::Tuple(t) => t.elems.iter().any(|e| self.mentions_self(e)),
+            Type::Dyn(_) => false,
+        };
+    }
+
+    /*
+     *  the methods that can be called through a `&dyn Trait`, they have to take `self` by
+     *  reference and must not mention `Self` or a generic parameter anywhere else in their
+     *  signature
+     */
+    pub fn object_methods(&self) -> Vec<&Function> {
+        return self.funcs.body.iter().filter_map(|func| match func {
+            AST::Function(func) => Some(func),
+            _ => None,
+        }).filter(|func| match func.args.first() {
+            Some(arg) => *arg.id.inner() == "self"
+                && arg.typ.is_pointer_like()
+                && !func.args[1..].iter().any(|arg| self.mentions_self(&arg.typ))
+                && !self.mentions_self(&func.ret_type),
+            None => false,
+        }).collect();
+    }
+
+    pub fn get_object_method<S: AsRef<str>>(&self, name: S) -> Option<(usize, &Function)> {
+        return self.object_methods().into_iter().enumerate().find(|(_, func)| func.get_value() == name.as_ref());
+    }
+
+    /*
+     *  the type of a vtable entry, `self` is passed as an opaque `*i8`
+     */
+    pub fn method_type(func: &Function, scope: &super::Scope, ctx: &crate::GenerationContext) -> llvm::FunctionTypeRef {
+        let mut params = vec![llvm::TypeRef::get_ptr(llvm::TypeRef::get_int(&ctx.ctx, 8), 0)];
+        params.extend(func.args[1..].iter().map(|arg| scope.resolve_type(&arg.typ, ctx)));
+        return llvm::FunctionTypeRef::get(scope.resolve_type(&func.ret_type, ctx), &params, false);
+    }
+
+    pub fn vtable_type(&self, scope: &super::Scope, ctx: &crate::GenerationContext) -> llvm::TypeRef {
+        let entries: Vec<llvm::TypeRef> = self.object_methods().iter().map(|func| {
+            llvm::TypeRef::get_ptr(Self::method_type(func, scope, ctx).into(), 0)
+        }).collect();
+        return llvm::StructTypeRef::get(ctx.ctx, &entries, false).into();
+    }
+
+    /*
+     *  returns the vtable of `strct` for this trait, it gets emitted on first use
+     */
+    pub fn get_vtable(&self, strct: &Tagged<String>, scope: &super::Scope, ctx: &crate::GenerationContext) -> llvm::ValueRef {
+        let name = format!("vtable.{}.{}", self.id.inner(), mangle_name(strct.inner()));
+        if let Some(vtable) = ctx.module.get_global(&name) {
+            return vtable.into();
+        }
+
+        let mut sym = scope.get_struct(strct).clone();
+        let entries: Vec<llvm::ValueRef> = self.object_methods().iter().map(|method| {
+            let func = sym.get_function(method.loc(), method.get_value()).clone();
+            let value = ctx.module.get_function(&func.name_mangeled).unwrap_or_else(|| {
+                let params: Vec<llvm::TypeRef> = func.args.iter().map(|arg| scope.resolve_type(&arg.typ, ctx)).collect();
+                let fn_type = llvm::FunctionTypeRef::get(scope.resolve_type(&func.ret_type, ctx), &params, false);
+                llvm::Function::create(fn_type, &func.name_mangeled, ctx.module)
+            });
+            llvm::ConstantExpr::get_pointer_cast(&value.into(), &llvm::TypeRef::get_ptr(Self::method_type(method, scope, ctx).into(), 0))
+        }).collect();
+
+        let ty = self.vtable_type(scope, ctx);
+        return llvm::GlobalVariable::with_name(ctx.module, &ty, &llvm::ConstantStruct::get(ty.clone(), &entries), true, &name).into();
     }
 }
 
@@ -0,0 +1,199 @@
+use crate::types::{Type, Tuple, Path};
+use crate::lexer::{Tagged, Loc};
+use crate::ast::{AST, Generate};
+use crate::ast::symbol::{Symbol, Variable};
+use crate::log::{error_msg_label, error_msg_labels, ErrorLabel};
+
+#[derive(Debug)]
+pub struct TupleExpr {
+    loc: Loc,
+    typ: Option<Type>,
+    elems: Vec<AST>,
+}
+
+impl TupleExpr {
+    pub fn new(loc: Loc, elems: Vec<AST>) -> Self {
+        Self{ loc, typ: None, elems }
+    }
+
+    /*
+     *  NOTE(S): the expected type converts the elements, e.g. integer literals in a tuple that
+     *  gets returned from a function
+     */
+    pub fn set_typ(&mut self, ty: Option<Type>) {
+        self.typ = ty.filter(|ty| matches!(ty, Type::Tuple(t) if t.elems.len() == self.elems.len()));
+    }
+}
+
+impl Generate for TupleExpr {
+    fn loc(&self) -> &Loc {
+        &self.loc
+    }
+
+    fn get_value(&self) -> String {
+        "tuple".to_owned()
+    }
+
+    fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+        let typ = self.get_type(scope, ctx).unwrap();
+        let Type::Tuple(tuple) = &typ else { unreachable!() };
+        let tuple_ty = scope.resolve_type(&typ, ctx);
+        let tmp = ctx.builder.create_alloca(&tuple_ty, None);
+        for (i, elem) in self.elems.iter_mut().enumerate() {
+            let elem_ty = elem.get_type(scope, ctx).unwrap();
+            let mut value = elem.gen_code(scope, ctx).unwrap();
+            if elem.should_load() {
+                value = value.try_load(&scope.resolve_type(&elem_ty, ctx), ctx.builder);
+            }
+            scope.gen_move(elem, ctx);
+
+            let expected = &tuple.elems[i];
+            if let Some(coerced) = crate::ast::coerce(&value, &elem_ty, expected, elem.loc(), scope, ctx) {
+                value = coerced;
+            }else {
+                let ty = scope.resolve_type(expected, ctx);
+                value = ctx.builder.create_cast(crate::ast::get_cast_ops(elem.loc(), &value.get_type(), &expected.get_loc(), &ty), &value, &ty);
+            }
+            ctx.builder.create_store(&value, &ctx.builder.create_struct_gep(&tuple_ty, &tmp, i as u32));
+        }
+        return Some(ctx.builder.create_load(&tuple_ty, &tmp));
+    }
+
+    fn get_type(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<Type> {
+        if self.typ.is_some() {
+            return self.typ.clone();
+        }
+        let elems = self.elems.iter().map(|elem| elem.get_type(scope, ctx).unwrap()).collect();
+        return Some(Type::Tuple(Tuple::new(self.loc.clone(), elems)));
+    }
+
+    fn collect_symbols(&mut self, _: &mut super::Scope) {}
+}
+
+/*
+ *  `let (a, b) = value;`, every element is copied into its own variable, `_` skips an element
+ */
+#[derive(Debug)]
+pub struct TupleDecl {
+    loc: Loc,
+    ids: Vec<Tagged<String>>,
+    cnst: bool,
+    typ: Option<Type>,
+    value: Box<AST>,
+}
+
+impl TupleDecl {
+    pub fn new(loc: Loc, ids: Vec<Tagged<String>>, cnst: bool, typ: Option<Type>, value: Box<AST>) -> Self {
+        Self{ loc, ids, cnst, typ, value }
+    }
+
+    fn tuple_type(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Tuple {
+        let value_ty = self.value.get_type(scope, ctx).unwrap();
+        let typ = match &self.typ {
+            Some(ty) => {
+                if !matches!(*self.value, AST::TupleExpr(_)) && !ty.matches(&value_ty) {
+                    error_msg_labels("mismatched types", &[
+                        ErrorLabel::from(&ty.get_loc(), format!("expected type `{}`", ty.get_full_name())),
+                        ErrorLabel::from(self.value.loc(), format!("but value has type `{}`", value_ty.get_full_name())),
+                    ]);
+                }
+                ty.clone()
+            },
+            None => value_ty,
+        };
+
+        return match typ {
+            Type::Tuple(tuple) if tuple.elems.len() == self.ids.len() => tuple,
+            typ => error_msg_label(
+                format!("cannot destructure a value of type `{}` into `{}` variables", typ.get_full_name(), self.ids.len()).as_str(),
+                ErrorLabel::from(self.value.loc(), format!("expected a tuple with `{}` elements", self.ids.len())),
+            ),
+        };
+    }
+
+    /*
+     *  NOTE(S): see `VarDecl::declare_poisoned`
+     */
+    pub fn declare_poisoned(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) {
+        let placeholder = llvm::ConstantInt::get(&llvm::TypeRef::get_int(ctx.ctx, 8), 0);
+        for (i, id) in self.ids.iter().enumerate() {
+            let ty = match &self.typ {
+                Some(Type::Tuple(tuple)) if tuple.elems.len() == self.ids.len() => tuple.elems[i].clone(),
+                _ => Type::Path(Path::new(id.loc().clone(), "void".into())),
+            };
+            let mut var = Variable::new(id.loc().clone(), ty, placeholder.clone(), self.cnst);
+            var.poisoned = true;
+            scope.add_symbol(id.inner(), Symbol::Variable(var));
+        }
+    }
+
+    /*
+     *  NOTE(S): see `VarDecl::declare_type`
+     */
+    pub fn declare_type(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) {
+        let tuple = self.tuple_type(scope, ctx);
+        let placeholder = llvm::ConstantInt::get(&llvm::TypeRef::get_int(ctx.ctx, 8), 0);
+        for (id, typ) in self.ids.iter().zip(tuple.elems.into_iter()) {
+            scope.add_symbol(id.inner(), Symbol::Variable(Variable::new(id.loc().clone(), typ, placeholder.clone(), self.cnst)));
+        }
+    }
+}
+
+impl Generate for TupleDecl {
+    fn loc(&self) -> &Loc {
+        &self.loc
+    }
+
+    fn get_value(&self) -> String {
+        self.ids.iter().map(|id| id.inner().clone()).collect::<Vec<String>>().join(", ")
+    }
+
+    fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
+        if let AST::TupleExpr(tuple) = &mut *self.value {
+            tuple.set_typ(self.typ.clone());
+        }
+
+        let tuple = self.tuple_type(scope, ctx);
+        let tuple_ty = scope.resolve_type(&Type::Tuple(tuple.clone()), ctx);
+        let mut value = self.value.gen_code(scope, ctx).unwrap();
+        if self.value.should_load() {
+            value = value.try_load(&tuple_ty, ctx.builder);
+        }
+        scope.gen_move(&self.value, ctx);
+        let owned = scope.owns_value(&self.value);
+
+        let tmp = ctx.builder.create_alloca(&tuple_ty, None);
+        ctx.builder.create_store(&value, &tmp);
+        for (i, (id, typ)) in self.ids.iter().zip(tuple.elems.into_iter()).enumerate() {
+            let ptr = ctx.builder.create_struct_gep(&tuple_ty, &tmp, i as u32);
+            /*
+             *  NOTE(S): nothing binds a skipped element, an owned value is dropped right away
+             */
+            if id.inner() == "_" {
+                if owned {
+                    scope.gen_drop_value(&typ, &ptr, id.loc(), ctx);
+                }
+                continue;
+            }
+            let elem_ty = scope.resolve_type(&typ, ctx);
+            let elem = ctx.builder.create_load(&elem_ty, &ptr);
+            let alloca = ctx.builder.create_alloca(&elem_ty, None);
+            ctx.builder.create_store(&elem, &alloca);
+
+            let mut var = Variable::new(id.loc().clone(), typ.clone(), alloca, self.cnst);
+            super::lints::declare_local(id, !self.cnst, false);
+            if let Some(flag) = scope.declare_drop_flag(&typ, owned, ctx) {
+                var = var.with_drop_flag(flag);
+                scope.add_drop(var.clone());
+            }
+            scope.add_symbol(id.inner(), Symbol::Variable(var));
+        }
+        return None;
+    }
+
+    fn get_type(&self, _: &mut super::Scope, _: &crate::GenerationContext) -> Option<Type> {
+        None
+    }
+
+    fn collect_symbols(&mut self, _: &mut super::Scope) {}
+}
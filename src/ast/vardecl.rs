@@ -1,4 +1,4 @@
-use crate::types::Type;
+use crate::types::{Type, Path};
 use crate::lexer::Tagged;
 use crate::ast::{AST, Generate};
 use crate::ast::symbol::{Symbol, Variable};
@@ -8,13 +8,55 @@ pub struct VarDecl {
     id: Tagged<String>,
     cnst: bool,
     typ: Option<Type>,
-    value: Option<Box<AST>>,
+    pub value: Option<Box<AST>>,
 }
 
 impl VarDecl {
     pub fn new(id: Tagged<String>, cnst: bool, typ: Option<Type>, value: Option<Box<AST>>) -> Self {
         Self{ id, cnst, typ, value }
     }
+
+    /*
+     *  NOTE(S): registers the variable with its type only so expressions after it can be typed
+     *  before any code is generated, the placeholder is never loaded from
+     */
+    pub fn declare_type(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) {
+        let ty = match (&self.typ, &self.value) {
+            (Some(ty), _) => ty.clone(),
+            (None, Some(value)) => value.get_type(scope, ctx).unwrap(),
+            (None, None) => return,
+        };
+        let placeholder = llvm::ConstantInt::get(&llvm::TypeRef::get_int(ctx.ctx, 8), 0);
+        scope.add_symbol(self.id.inner(), Symbol::Variable(Variable::new(self.id.loc().clone(), ty, placeholder, self.cnst)));
+    }
+
+    /*
+     *  NOTE(S): declares the variable of a `let` whose initializer had an error, its uses are
+     *  skipped instead of reported as unknown variables
+     */
+    pub fn declare_poisoned(&self, scope: &mut super::Scope, ctx: &crate::GenerationContext) {
+        let ty = self.typ.clone().unwrap_or_else(|| Type::Path(Path::new(self.id.loc().clone(), "void".into())));
+        let placeholder = llvm::ConstantInt::get(&llvm::TypeRef::get_int(ctx.ctx, 8), 0);
+        let mut var = Variable::new(self.id.loc().clone(), ty, placeholder, self.cnst);
+        var.poisoned = true;
+        scope.add_symbol(self.id.inner(), Symbol::Variable(var));
+    }
+
+    /*
+     *  NOTE(S): locals owning a value that implements `Drop` are dropped when their scope is left
+     */
+    fn declare(&self, var: Variable, drop_flag: Option<llvm::ValueRef>, scope: &mut super::Scope) {
+        let var = match drop_flag {
+            Some(flag) => {
+                let var = var.with_drop_flag(flag);
+                scope.add_drop(var.clone());
+                var
+            },
+            None => var,
+        };
+        super::lints::declare_local(&self.id, !self.cnst, false);
+        scope.add_symbol(self.id.inner(), Symbol::Variable(var));
+    }
 }
 
 impl Generate for VarDecl {
@@ -28,8 +70,10 @@ impl Generate for VarDecl {
 
     fn gen_code(&mut self, scope: &mut super::Scope, ctx: &crate::GenerationContext) -> Option<llvm::ValueRef> {
         if let Some(value) = &mut self.value {
-            if let AST::InitializerList(list) = &mut **value {
-                list.set_typ(self.typ.clone());
+            match &mut **value {
+                AST::InitializerList(list) => list.set_typ(self.typ.clone()),
+                AST::TupleExpr(tuple) => tuple.set_typ(self.typ.clone()),
+                _ => {},
             }
 
             let value_ty = value.get_type(scope, ctx).unwrap();
@@ -38,7 +82,9 @@ impl Generate for VarDecl {
                 val = val.try_load(&scope.resolve_type(&value_ty, ctx), ctx.builder);
             }
 
-            if let Some(ty) = &self.typ {
+            if let Some(coerced) = self.typ.as_ref().and_then(|ty| crate::ast::coerce(&val, &value_ty, ty, value.loc(), scope, ctx)) {
+                val = coerced;
+            }else if let Some(ty) = &self.typ {
                 let typ = scope.resolve_type(ty, ctx);
                 val = ctx.builder.create_cast(
                     crate::ast::get_cast_ops(value.loc(), &val.get_type(), &ty.get_loc(), &typ), &val, &typ);
@@ -48,11 +94,15 @@ impl Generate for VarDecl {
 
             let alloca = ctx.builder.create_alloca(&val.get_type(), None);
             ctx.builder.create_store(&val, &alloca);
-            scope.add_symbol(self.id.inner(), Symbol::Variable(Variable::new(self.id.loc().clone(), ty, alloca.clone(), self.cnst)));
+            scope.gen_move(value, ctx);
+            let drop_flag = scope.declare_drop_flag(&ty, scope.owns_value(value), ctx);
+            self.declare(Variable::new(self.id.loc().clone(), ty, alloca.clone(), self.cnst), drop_flag, scope);
             return Some(alloca);
         }else {
-            let alloca = ctx.builder.create_alloca(&scope.resolve_type(self.typ.as_ref().unwrap(), ctx), None);
-            scope.add_symbol(self.id.inner(), Symbol::Variable(Variable::new(self.id.loc().clone(), self.typ.as_ref().unwrap().clone(), alloca.clone(), self.cnst)));
+            let ty = self.typ.as_ref().unwrap().clone();
+            let alloca = ctx.builder.create_alloca(&scope.resolve_type(&ty, ctx), None);
+            let drop_flag = scope.declare_drop_flag(&ty, false, ctx);
+            self.declare(Variable::new(self.id.loc().clone(), ty, alloca.clone(), self.cnst), drop_flag, scope);
             return Some(alloca);
         }
     }
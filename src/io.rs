@@ -17,6 +17,26 @@ struct CliArgs {
     emit: Option<EmitType>,
     #[arg(short, default_value_t = String::from("out.o"))]
     output_file: String,
+    /// emit runtime checks for array bounds, null dereferences, integer overflow and division by zero
+    #[arg(long)]
+    checks: bool,
+    /// stop after this many errors, `0` reports every error
+    #[arg(long, default_value_t = 20)]
+    error_limit: usize,
+    #[arg(long, value_enum, default_value_t = ErrorFormat::human)]
+    error_format: ErrorFormat,
+    /// print a detailed explanation of an error code, e.g. `--explain E0012`
+    #[arg(long, value_name = "CODE")]
+    explain: Option<String>,
+    /// report a lint as a warning, `warnings` names every lint
+    #[arg(short = 'W', value_name = "LINT")]
+    warn: Vec<String>,
+    /// do not report a lint
+    #[arg(short = 'A', value_name = "LINT")]
+    allow: Vec<String>,
+    /// report a lint as an error
+    #[arg(short = 'D', value_name = "LINT")]
+    deny: Vec<String>,
 }
 
 #[allow(non_camel_case_types)]
@@ -26,12 +46,27 @@ pub enum EmitType {
     ast,
 }
 
+#[allow(non_camel_case_types)]
+#[derive(ValueEnum, Clone, Debug, Default, PartialEq)]
+pub enum ErrorFormat {
+    #[default]
+    human,
+    json,
+}
+
 #[derive(Debug)]
 pub struct CompilationOptions {
     pub input_file: Option<String>,
     pub output_file: String,
     pub emit_exe: bool,
     pub emit_extra: Option<EmitType>,
+    pub checks: bool,
+    pub error_limit: usize,
+    pub error_format: ErrorFormat,
+    pub explain: Option<String>,
+    pub warn: Vec<String>,
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
 }
 
 pub fn get_cli_options() -> CompilationOptions {
@@ -54,6 +89,13 @@ pub fn get_cli_options() -> CompilationOptions {
         output_file: args.output_file,
         emit_exe,
         emit_extra: args.emit,
+        checks: args.checks,
+        error_limit: args.error_limit,
+        error_format: args.error_format,
+        explain: args.explain,
+        warn: args.warn,
+        allow: args.allow,
+        deny: args.deny,
     };
 }
 
@@ -100,7 +142,7 @@ pub fn emit_exe(
     if unsafe { libc::system(cmd.as_ptr()) } != 0 {
         return Err(std::io::Error::new(
             std::io::ErrorKind::Other,
-            "failed to execture linker command",
+            "failed to execute linker command",
         ));
     };
     return Ok(());
@@ -2,11 +2,20 @@ pub mod lexer {
     use super::super::super::types::{KeyWords, TokenType};
     use std::path::PathBuf;
 
+    /*
+     *  NOTE(S): `start` and `end` index the chars of the source, the byte offsets differ from them
+     *  for non ascii source. `line` and `column` are 1-based and point at `start`, they are `0` for
+     *  locations that do not come from the lexer
+     */
     #[derive(Debug, Clone)]
     pub struct Loc {
         pub file: PathBuf,
         pub start: usize,
         pub end: usize,
+        pub byte_start: usize,
+        pub byte_end: usize,
+        pub line: usize,
+        pub column: usize,
     }
 
     impl Loc {
@@ -15,20 +24,32 @@ pub mod lexer {
                 file,
                 start,
                 end,
+                byte_start: start,
+                byte_end: end,
+                line: 0,
+                column: 0,
             };
         }
+
+        pub fn with_position(mut self, byte_start: usize, byte_end: usize, line: usize, column: usize) -> Self {
+            self.byte_start = byte_start;
+            self.byte_end = byte_end;
+            self.line = line;
+            self.column = column;
+            return self;
+        }
     }
 
     impl Default for Loc {
         fn default() -> Self {
-            Self{ file: PathBuf::from("internal"), start: 0, end: 1 }
+            Self::new(PathBuf::from("internal"), 0, 1)
         }
     }
 
     impl std::ops::Add for Loc {
         type Output = Loc;
         fn add(self, rhs: Self) -> Self::Output {
-            Self::new(self.file, self.start, rhs.end)
+            Self{ end: rhs.end, byte_end: rhs.byte_end, ..self }
         }
     }
 
@@ -79,12 +100,25 @@ pub mod lexer {
         }
     }
 
+    /*
+     *  the position of the lexer in the source, see `Loc`
+     */
+    #[derive(Debug, Clone, Copy)]
+    struct Position {
+        index: usize,
+        byte: usize,
+        line: usize,
+        column: usize,
+    }
+
     pub struct Lexer {
         pub file: PathBuf,
         src: Vec<char>,
         curr: char,
         index: usize,
-        line_index: usize,
+        byte_index: usize,
+        line: usize,
+        column: usize,
     }
 
     impl Lexer {
@@ -94,7 +128,9 @@ pub mod lexer {
                 src: logic.chars().collect(),
                 curr: ' ',
                 index: 0,
-                line_index: 0,
+                byte_index: 0,
+                line: 1,
+                column: 1,
             };
             parser.src.push('\0');
             parser.curr = parser.src[0];
@@ -115,19 +151,44 @@ pub mod lexer {
         }
 
         fn advance(&mut self) {
+            if self.curr == '\n' {
+                self.line += 1;
+                self.column = 1;
+            }else {
+                self.column += 1;
+            }
+            self.byte_index += self.curr.len_utf8();
             self.index += 1;
             self.curr = self.src[self.index];
-            self.line_index += 1;
+        }
+
+        fn position(&self) -> Position {
+            return Position{ index: self.index, byte: self.byte_index, line: self.line, column: self.column };
+        }
+
+        /*
+         *  returns the location from `start` up to the current char
+         */
+        fn loc_from(&self, start: Position) -> Loc {
+            return Loc::new(self.file.clone(), start.index, self.index).with_position(start.byte, self.byte_index, start.line, start.column);
+        }
+
+        /*
+         *  returns the location of the current char
+         */
+        fn loc_here(&self) -> Loc {
+            let start = self.position();
+            return Loc::new(self.file.clone(), start.index, start.index + 1).with_position(start.byte, start.byte + self.curr.len_utf8(), start.line, start.column);
         }
 
         fn number(&mut self) -> Token {
             let mut value: String = String::new();
-            let start = self.line_index;
-            while self.curr.is_digit(10) || self.curr == '.' {
+            let start = self.position();
+            while self.curr.is_digit(10) || (self.curr == '.' && self.src[self.index + 1] != '.') {
                 value.push(self.curr);
                 self.advance();
             }
-            let loc = Loc::new(self.file.clone(), start, self.line_index);
+            let loc = self.loc_from(start);
             if value.contains(".") {
                 return Token::new(value.as_str(), TokenType::Float, loc);
             }
@@ -136,36 +197,50 @@ pub mod lexer {
 
         fn string(&mut self) -> Token {
             let mut value: String = String::new();
-            let start = self.line_index;
+            let start = self.position();
             while self.curr != '\"' {
                 value.push(self.curr);
                 self.advance();
             }
-            let loc = Loc::new(self.file.clone(), start, self.line_index);
+            let loc = self.loc_from(start);
             self.advance();
             return Token::new(value.as_str(), TokenType::String, loc);
         }
 
         fn char(&mut self) -> Token {
             let mut value: String = String::new();
-            let start = self.line_index;
+            let start = self.position();
             while self.curr != '\'' {
                 value.push(self.curr);
                 self.advance();
             }
-            let loc = Loc::new(self.file.clone(), start, self.line_index);
+            let loc = self.loc_from(start);
             self.advance();
             return Token::new(value.as_str(), TokenType::Char, loc);
         }
 
+        /*
+         *  NOTE(S): a label is a `'` followed by an identifier that is not closed by a second `'`
+         *  -> 'a' is a char literal, 'outer is a label
+         */
+        fn label(&mut self, start: Position) -> Token {
+            let mut value: String = String::new();
+            while self.curr.is_alphanumeric() || self.curr == '_' {
+                value.push(self.curr);
+                self.advance();
+            }
+            let loc = self.loc_from(start);
+            return Token::new(value.as_str(), TokenType::Label, loc);
+        }
+
         fn id(&mut self) -> Token {
             let mut value: String = String::new();
-            let start = self.line_index;
+            let start = self.position();
             while self.curr.is_alphanumeric() || self.curr == '_' {
                 value.push(self.curr);
                 self.advance();
             }
-            let loc = Loc::new(self.file.clone(), start, self.line_index);
+            let loc = self.loc_from(start);
             if let Some(typ) = KeyWords.get(&value.as_str()) {
                 return Token::new(value.as_str(), typ.clone(), loc);
             }
@@ -188,9 +263,19 @@ pub mod lexer {
                     self.advance();
                 }
 
-                let loc = Loc::new(self.file.clone(), self.line_index, self.line_index + 1);
+                let loc = self.loc_here();
                 match self.curr {
-                    '.' => return self.advance_token(Token::new(".", TokenType::Dot, loc)),
+                    '.' => match self.src[self.index + 1] {
+                        '.' => {
+                            self.advance();
+                            if self.src[self.index + 1] == '=' {
+                                self.advance();
+                                return self.advance_token(Token::new("..=", TokenType::OpRangeInc, loc));
+                            }
+                            return self.advance_token(Token::new("..", TokenType::OpRange, loc));
+                        }
+                        _ => return self.advance_token(Token::new(".", TokenType::Dot, loc)),
+                    },
                     ',' => return self.advance_token(Token::new(",", TokenType::Comma, loc)),
                     ';' => return self.advance_token(Token::new(";", TokenType::Semi, loc)),
                     ':' => match self.src[self.index + 1] {
@@ -200,15 +285,31 @@ pub mod lexer {
                         }
                         _ => return self.advance_token(Token::new(":", TokenType::Colon, loc)),
                     },
-                    '+' => return self.advance_token(Token::new("+", TokenType::OpPlus, loc)),
+                    '+' => match self.src[self.index + 1] {
+                        '=' => {
+                            self.advance();
+                            return self.advance_token(Token::new("+=", TokenType::OpPlusEq, loc));
+                        }
+                        _ => return self.advance_token(Token::new("+", TokenType::OpPlus, loc)),
+                    },
                     '-' => match self.src[self.index + 1] {
                         '>' => {
                             self.advance();
                             return self.advance_token(Token::new("->", TokenType::Arrow, loc));
                         }
+                        '=' => {
+                            self.advance();
+                            return self.advance_token(Token::new("-=", TokenType::OpMinusEq, loc));
+                        }
                         _ => return self.advance_token(Token::new("-", TokenType::OpMinus, loc)),
                     },
-                    '*' => return self.advance_token(Token::new("*", TokenType::OpMul, loc)),
+                    '*' => match self.src[self.index + 1] {
+                        '=' => {
+                            self.advance();
+                            return self.advance_token(Token::new("*=", TokenType::OpMulEq, loc));
+                        }
+                        _ => return self.advance_token(Token::new("*", TokenType::OpMul, loc)),
+                    },
                     '/' => match self.src[self.index + 1] {
                         '/' => {
                             while self.curr != '\n' {
@@ -239,9 +340,26 @@ pub mod lexer {
                             }
                             continue;
                         },
+                        '=' => {
+                            self.advance();
+                            return self.advance_token(Token::new("/=", TokenType::OpDivEq, loc));
+                        }
                         _ => return self.advance_token(Token::new("/", TokenType::OpDiv, loc)),
                     },
-                    '%' => return self.advance_token(Token::new("%", TokenType::OpMod, loc)),
+                    '%' => match self.src[self.index + 1] {
+                        '=' => {
+                            self.advance();
+                            return self.advance_token(Token::new("%=", TokenType::OpModEq, loc));
+                        }
+                        _ => return self.advance_token(Token::new("%", TokenType::OpMod, loc)),
+                    },
+                    '^' => match self.src[self.index + 1] {
+                        '=' => {
+                            self.advance();
+                            return self.advance_token(Token::new("^=", TokenType::OpXorEq, loc));
+                        }
+                        _ => return self.advance_token(Token::new("^", TokenType::OpXor, loc)),
+                    },
                     '[' => return self.advance_token(Token::new("[", TokenType::BraceLeft, loc)),
                     ']' => return self.advance_token(Token::new("]", TokenType::BraceRight, loc)),
                     '{' => return self.advance_token(Token::new("{", TokenType::CurlyLeft, loc)),
@@ -255,6 +373,14 @@ pub mod lexer {
                             self.advance();
                             return self.advance_token(Token::new("<=", TokenType::OpLeEq, loc));
                         }
+                        '<' => {
+                            self.advance();
+                            if self.src[self.index + 1] == '=' {
+                                self.advance();
+                                return self.advance_token(Token::new("<<=", TokenType::OpShlEq, loc));
+                            }
+                            return self.advance_token(Token::new("<<", TokenType::OpShl, loc));
+                        }
                         _ => return self.advance_token(Token::new("<", TokenType::AngleLeft, loc)),
                     },
                     '>' => match self.src[self.index + 1] {
@@ -262,6 +388,14 @@ pub mod lexer {
                             self.advance();
                             return self.advance_token(Token::new(">=", TokenType::OpGrEq, loc));
                         }
+                        '>' => {
+                            self.advance();
+                            if self.src[self.index + 1] == '=' {
+                                self.advance();
+                                return self.advance_token(Token::new(">>=", TokenType::OpShrEq, loc));
+                            }
+                            return self.advance_token(Token::new(">>", TokenType::OpShr, loc));
+                        }
                         _ => {
                             return self.advance_token(Token::new(">", TokenType::AngleRight, loc))
                         }
@@ -271,6 +405,10 @@ pub mod lexer {
                             self.advance();
                             return self.advance_token(Token::new("&&", TokenType::OpAnd, loc));
                         }
+                        '=' => {
+                            self.advance();
+                            return self.advance_token(Token::new("&=", TokenType::OpAmpercentEq, loc));
+                        }
                         _ => return self.advance_token(Token::new("&", TokenType::Ampercent, loc)),
                     },
                     '|' => match self.src[self.index + 1] {
@@ -278,6 +416,10 @@ pub mod lexer {
                             self.advance();
                             return self.advance_token(Token::new("||", TokenType::OpOr, loc));
                         }
+                        '=' => {
+                            self.advance();
+                            return self.advance_token(Token::new("|=", TokenType::OpPipeEq, loc));
+                        }
                         _ => return self.advance_token(Token::new("|", TokenType::Pipe, loc)),
                     },
                     '!' => match self.src[self.index + 1] {
@@ -292,6 +434,10 @@ pub mod lexer {
                             self.advance();
                             return self.advance_token(Token::new("==", TokenType::OpEqEq, loc));
                         }
+                        '>' => {
+                            self.advance();
+                            return self.advance_token(Token::new("=>", TokenType::FatArrow, loc));
+                        }
                         _ => return self.advance_token(Token::new("=", TokenType::Equals, loc)),
                     },
 
@@ -300,7 +446,11 @@ pub mod lexer {
                         return self.string();
                     }
                     '\'' => {
+                        let start = self.position();
                         self.advance();
+                        if (self.curr.is_alphabetic() || self.curr == '_') && self.src[self.index + 1] != '\'' {
+                            return self.label(start);
+                        }
                         return self.char();
                     }
                     '\0' => return Token::new("", TokenType::Eof, loc),
@@ -309,7 +459,7 @@ pub mod lexer {
                             return self.number();
                         }
 
-                        if self.curr.is_alphabetic() {
+                        if self.curr.is_alphabetic() || self.curr == '_' {
                             return self.id();
                         }
 
@@ -317,7 +467,70 @@ pub mod lexer {
                     }
                 }
             }
-            return Token::new("", TokenType::Eof, Loc::new(PathBuf::new(), self.line_index, self.line_index + 1));
+            return Token::new("", TokenType::Eof, self.loc_here());
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn tokens(src: &str) -> Vec<Token> {
+            return Lexer::from(src, &PathBuf::from("test.ly")).get_tokens();
+        }
+
+        /*
+         *  the value, line, column, char range and byte range of a token
+         */
+        fn position(token: &Token) -> (&str, usize, usize, (usize, usize), (usize, usize)) {
+            let loc = &token.loc;
+            return (token.value.as_str(), loc.line, loc.column, (loc.start, loc.end), (loc.byte_start, loc.byte_end));
+        }
+
+        #[test]
+        fn ascii_positions() {
+            let tokens = tokens("let x = 10;\n  foo(x);");
+            assert_eq!(position(&tokens[0]), ("let", 1, 1, (0, 3), (0, 3)));
+            assert_eq!(position(&tokens[1]), ("x", 1, 5, (4, 5), (4, 5)));
+            assert_eq!(position(&tokens[3]), ("10", 1, 9, (8, 10), (8, 10)));
+            assert_eq!(position(&tokens[4]), (";", 1, 11, (10, 11), (10, 11)));
+            assert_eq!(position(&tokens[5]), ("foo", 2, 3, (14, 17), (14, 17)));
+            assert_eq!(position(&tokens[6]), ("(", 2, 6, (17, 18), (17, 18)));
+        }
+
+        #[test]
+        fn non_ascii_positions() {
+            let src = "let s = \"héllo\";\nlet ü = 'ß';";
+            let tokens = tokens(src);
+            assert_eq!(position(&tokens[3]), ("héllo", 1, 10, (9, 14), (9, 15)));
+            assert_eq!(position(&tokens[4]), (";", 1, 16, (15, 16), (16, 17)));
+            assert_eq!(position(&tokens[6]), ("ü", 2, 5, (21, 22), (22, 24)));
+            assert_eq!(position(&tokens[8]), ("ß", 2, 10, (26, 27), (28, 30)));
+            for token in &tokens {
+                assert_eq!(&src[token.loc.byte_start..token.loc.byte_end], token.value);
+            }
+        }
+
+        #[test]
+        fn comments_keep_positions() {
+            let src = "// ünïcödé\n/* ä\n ö */ x";
+            let tokens = tokens(src);
+            assert_eq!(position(&tokens[0]), ("x", 3, 7, (22, 23), (28, 29)));
+        }
+
+        #[test]
+        fn labels_include_the_quote() {
+            let tokens = tokens("'outer: loop {}");
+            assert_eq!(position(&tokens[0]), ("outer", 1, 1, (0, 6), (0, 6)));
+        }
+
+        #[test]
+        fn joined_locations() {
+            let tokens = tokens("a +\n  bé");
+            let loc = tokens[0].loc.clone() + tokens[2].loc.clone();
+            assert_eq!((loc.line, loc.column), (1, 1));
+            assert_eq!((loc.start, loc.end), (0, 8));
+            assert_eq!((loc.byte_start, loc.byte_end), (0, 9));
         }
     }
 }
@@ -0,0 +1,1051 @@
+/*
+ *  NOTE(S): every diagnostic kind gets a stable code so tools and users can refer to it. a code
+ *  is attached to a diagnostic by matching its message against the patterns below, `{}` matches
+ *  any text. codes are never reused, new kinds get a new code
+ */
+pub struct ErrorCode {
+    pub code: &'static str,
+    pub title: &'static str,
+    messages: &'static [&'static str],
+    pub explanation: &'static str,
+}
+
+pub static CODES: &[ErrorCode] = &[
+    ErrorCode{
+        code: "E0001",
+        title: "unexpected token",
+        messages: &["unexpected token found", "unexpected token found: `{}`"],
+        explanation: r#"The parser found a token that is not allowed at this position.
+
+Erroneous code example:
+
+    fn add(a: i32 b: i32) -> i32 {
+        return a + b;
+    }
+
+The label of the error tells what was expected instead, here the parameters
+have to be comma separated:
+
+    fn add(a: i32, b: i32) -> i32 {
+        return a + b;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0002",
+        title: "missing type annotation",
+        messages: &["missing type specifier", "type annotation missing"],
+        explanation: r#"A declaration that requires a type was written without one.
+
+Erroneous code example:
+
+    struct Point { x; y: i32; };
+
+Struct fields, function parameters and statics always need an explicit type:
+
+    struct Point { x: i32; y: i32; };
+"#,
+    },
+    ErrorCode{
+        code: "E0003",
+        title: "invalid constant",
+        messages: &["invalid integer literal", "non constant in static context"],
+        explanation: r#"An integer literal could not be parsed, or the length of an array type is not
+a constant.
+
+Erroneous code example:
+
+    fn main() -> i32 {
+        let n = 4;
+        let arr: [i32; n];
+        return 0;
+    }
+
+The length of an array has to be an integer literal:
+
+    fn main() -> i32 {
+        let arr: [i32; 4];
+        return 0;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0004",
+        title: "duplicate symbol",
+        messages: &["duplicate symbol found", "cannot redefine symbol `{}`"],
+        explanation: r#"The same name was defined twice in one scope.
+
+Erroneous code example:
+
+    fn value() -> i32 { return 1; }
+    fn value() -> i32 { return 2; }
+
+Rename or remove one of the definitions:
+
+    fn value() -> i32 { return 1; }
+    fn other_value() -> i32 { return 2; }
+"#,
+    },
+    ErrorCode{
+        code: "E0005",
+        title: "invalid generic declaration",
+        messages: &[
+            "empty generic parameter list",
+            "generic functions require a body",
+            "generic member functions are not supported",
+            "unexpected type in generic impl",
+        ],
+        explanation: r#"A generic item was declared in a way the compiler does not support.
+
+Erroneous code example:
+
+    fn first<>(a: i32) -> i32 { return a; }
+
+A generic parameter list needs at least one parameter, generic functions need a
+body and the type arguments of an `impl` have to be the plain parameters of the
+struct:
+
+    fn first<T>(a: T) -> T { return a; }
+"#,
+    },
+    ErrorCode{
+        code: "E0006",
+        title: "invalid enum type",
+        messages: &["invalid enum type"],
+        explanation: r#"The underlying type of an enum has to be a primitive type.
+
+Erroneous code example:
+
+    enum Kind: *u8 { A, B }
+
+Use an integer type instead:
+
+    enum Kind: u8 { A, B }
+"#,
+    },
+    ErrorCode{
+        code: "E0007",
+        title: "invalid range pattern",
+        messages: &[
+            "invalid range pattern",
+            "empty range in match arm",
+            "literal `{}` is out of range for the matched type",
+        ],
+        explanation: r#"A range or literal in a match arm can never match the matched value.
+
+Erroneous code example:
+
+    fn main() -> i32 {
+        let x: u8 = 3;
+        match x {
+            10..=0 => {}
+            300 => {}
+            _ => {}
+        }
+        return 0;
+    }
+
+The start of a range has to be less or equal to its end and every literal has
+to fit into the type of the matched value:
+
+    match x {
+        0..=10 => {}
+        255 => {}
+        _ => {}
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0010",
+        title: "unresolved variable",
+        messages: &["could not resolve variable `{}`"],
+        explanation: r#"A variable was used that is not declared in the current scope.
+
+Erroneous code example:
+
+    fn main() -> i32 {
+        return count;
+    }
+
+Declare the variable before using it, a variable declared in a block is not
+visible outside of it:
+
+    fn main() -> i32 {
+        let count = 0;
+        return count;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0011",
+        title: "unresolved function",
+        messages: &["could not resolve function `{}`", "unknown function"],
+        explanation: r#"A function or method was called that is not declared.
+
+Erroneous code example:
+
+    struct Point { x: i32; y: i32; };
+
+    fn main() -> i32 {
+        let p = Point::origin();
+        return 0;
+    }
+
+Define the function in an `impl` block of the type, or declare it if it is
+provided by another object file:
+
+    impl Point {
+        fn origin() -> Point {
+            let p: Point = {0, 0};
+            return p;
+        }
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0012",
+        title: "unknown field",
+        messages: &[
+            "unknown field `{}` in type `{}`",
+            "no element `{}` in tuple `{}`",
+            "tuple `{}` has no named fields",
+            "out of bounds struct index",
+            "invalid struct member",
+        ],
+        explanation: r#"A field was accessed that does not exist on the type of the value.
+
+Erroneous code example:
+
+    struct Point { x: i32; y: i32; };
+
+    fn main() -> i32 {
+        let p: Point = {1, 2};
+        return p.z;
+    }
+
+The note of the error lists the available fields. Tuple elements are accessed
+by their index, slices only have the fields `data` and `len`:
+
+    fn main() -> i32 {
+        let p: Point = {1, 2};
+        return p.y;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0013",
+        title: "unresolved type",
+        messages: &[
+            "could not resolve struct type `{}`",
+            "cannot resolve type `{}` in this scope",
+            "unknown type `{}`",
+            "could not resolve generic `{}`",
+        ],
+        explanation: r#"A type was named that is not declared.
+
+Erroneous code example:
+
+    fn area(s: Square) -> i64;
+
+Declare the type, or include the file that declares it:
+
+    struct Square { s: i64; };
+
+    fn area(s: Square) -> i64;
+"#,
+    },
+    ErrorCode{
+        code: "E0014",
+        title: "invalid enum variant",
+        messages: &[
+            "could not resolve enum `{}`",
+            "unknown enum variant `{}` in enum `{}`",
+            "invalid field type for variant `{}::{}`",
+            "variant `{}::{}` has `{}` fields, but `{}` were supplied",
+        ],
+        explanation: r#"An enum or one of its variants does not exist, or a variant was constructed
+with the wrong fields.
+
+Erroneous code example:
+
+    enum Opt { None, Some(i32) }
+
+    fn main() -> i32 {
+        let o = Opt::Some(1, 2);
+        return 0;
+    }
+
+Pass exactly one value for every field of the variant:
+
+    fn main() -> i32 {
+        let o = Opt::Some(1);
+        return 0;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0015",
+        title: "unresolved trait",
+        messages: &["could not resolve trait `{}`"],
+        explanation: r#"A trait was named that is not declared.
+
+Erroneous code example:
+
+    struct Square { s: i64; };
+
+    impl Shape for Square {
+        fn area(&self) -> i64 { return self.s * self.s; }
+    }
+
+Declare the trait before implementing it:
+
+    trait Shape {
+        fn area(&self) -> i64;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0016",
+        title: "wrong number of type arguments",
+        messages: &[
+            "`{}` expects `{}` type arguments, but `{}` were supplied",
+            "type `{}` does not take type arguments",
+        ],
+        explanation: r#"A generic item was given more or fewer type arguments than it declares, or a
+non generic item was given type arguments.
+
+Erroneous code example:
+
+    fn make<T>(a: T) -> T { return a; }
+
+    fn main() -> i32 {
+        let a = make::<i32, i64>(1);
+        return 0;
+    }
+
+Supply one type argument per generic parameter:
+
+    fn main() -> i32 {
+        let a = make::<i32>(1);
+        return 0;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0017",
+        title: "invalid macro call",
+        messages: &[
+            "could not resolve macro",
+            "insufficient args supplied to macro `{}`",
+            "macro `format_args` expected `{}` args, but `{}` were provided",
+            "macro `sizeof` expected exactly 1 argument",
+        ],
+        explanation: r#"A macro does not exist or was called with the wrong arguments.
+
+Erroneous code example:
+
+    fn main() -> i32 {
+        let size = sizeof$(i32, i64);
+        return 0;
+    }
+
+Check the name of the macro and pass the arguments its arms expect:
+
+    fn main() -> i32 {
+        let size = sizeof$(i32);
+        return 0;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0018",
+        title: "invalid loop control",
+        messages: &["invalid {} point", "use of undeclared label `'{}`"],
+        explanation: r#"A `break` or `continue` was used outside of a loop, or it names a label that
+no enclosing loop has.
+
+Erroneous code example:
+
+    fn main() -> i32 {
+        'outer: while 1 < 2 {
+            break 'inner;
+        }
+        return 0;
+    }
+
+Use the label of an enclosing loop:
+
+    fn main() -> i32 {
+        'outer: while 1 < 2 {
+            break 'outer;
+        }
+        return 0;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0020",
+        title: "mismatched types",
+        messages: &[
+            "mismatched types",
+            "`if` and `else` have incompatible types",
+            "`if` may be missing an `else` clause",
+            "invalid return type `{}` for function with return type `void`",
+        ],
+        explanation: r#"A value has a different type than the place it is used in expects.
+
+Erroneous code example:
+
+    fn main() -> i32 {
+        let a: i32 = 1;
+        let mut p: *i32 = &a;
+        p = a;
+        return 0;
+    }
+
+Convert the value explicitly with a cast, or change the expected type:
+
+    fn main() -> i32 {
+        let a: i32 = 1;
+        let mut p: *i32 = &a;
+        *p = a;
+        return 0;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0021",
+        title: "invalid call arguments",
+        messages: &[
+            "function `{}` expected `{}` args, but `{}` were supplied",
+            "closure expected `{}` args, but `{}` were supplied",
+            "invalid argument type for function `{}`",
+            "incorrect number of arguments supplied",
+        ],
+        explanation: r#"A function or closure was called with the wrong number of arguments, or an
+argument has the wrong type.
+
+Erroneous code example:
+
+    fn add(a: i32, b: i32) -> i32 { return a + b; }
+
+    fn main() -> i32 {
+        return add(1);
+    }
+
+Pass one argument of the declared type for every parameter:
+
+    fn main() -> i32 {
+        return add(1, 2);
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0022",
+        title: "invalid conversion",
+        messages: &["unable to convert types"],
+        explanation: r#"A value cannot be converted to the requested type.
+
+Erroneous code example:
+
+    struct Point { x: i32; y: i32; };
+
+    fn main() -> i32 {
+        let p: Point = {1, 2};
+        return (:i32)p;
+    }
+
+Only numbers, pointers and references can be cast, access a field instead:
+
+    fn main() -> i32 {
+        let p: Point = {1, 2};
+        return p.x;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0023",
+        title: "invalid operator",
+        messages: &[
+            "invalid operand to binary operator `{}`",
+            "binary operator `{}` cannot be applied to type `{}`",
+            "cannot apply operator `{}` to type `{}`",
+            "unknown binary operator `{}`",
+            "unknown unary operator `{}`",
+        ],
+        explanation: r#"An operator was applied to values it is not defined for.
+
+Erroneous code example:
+
+    fn main() -> i32 {
+        let a: i32 = 1;
+        let b: i64 = 2;
+        let c = a + b;
+        return 0;
+    }
+
+Both sides of a binary operator have to have the same type, bitwise operators
+only work on integers and logical operators only on `bool`:
+
+    fn main() -> i32 {
+        let a: i32 = 1;
+        let b: i64 = 2;
+        let c = (:i64)a + b;
+        return 0;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0024",
+        title: "invalid dereference",
+        messages: &["cannot deref value type", "invalid level of indirection"],
+        explanation: r#"A value that is not a pointer or reference was dereferenced.
+
+Erroneous code example:
+
+    fn main() -> i32 {
+        let mut a: i32 = 1;
+        *a = 2;
+        return 0;
+    }
+
+Only pointers and references can be dereferenced:
+
+    fn main() -> i32 {
+        let mut a: i32 = 1;
+        let p: *i32 = &a;
+        *p = 2;
+        return 0;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0025",
+        title: "invalid operator overload",
+        messages: &[
+            "binary operator `{}` cannot be overloaded",
+            "`{}::index` has to return a pointer to the element",
+        ],
+        explanation: r#"An operator overload has a signature the compiler cannot use.
+
+Erroneous code example:
+
+    struct Buf { data: *i64; };
+
+    impl Buf {
+        fn index(&self, i: u64) -> i64 { return 0; }
+    }
+
+`index` has to return a pointer to the element so it can be assigned to:
+
+    impl Buf {
+        fn index(&self, i: u64) -> *i64 { return self.data; }
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0026",
+        title: "invalid iteration",
+        messages: &[
+            "invalid range expression",
+            "mismatched types in range",
+            "cannot iterate over a range of `{}`",
+            "`{}` is not an iterator",
+            "`{}::next` has to return an `Option`",
+        ],
+        explanation: r#"A `for` loop was given a value it cannot iterate over.
+
+Erroneous code example:
+
+    fn main() -> i32 {
+        let lo: i32 = 0;
+        let hi: i64 = 10;
+        for i in lo..hi {}
+        return 0;
+    }
+
+Both ends of a range have to be integers of the same type, other values need a
+`next` method returning an `Option`:
+
+    fn main() -> i32 {
+        let lo: i64 = 0;
+        let hi: i64 = 10;
+        for i in lo..hi {}
+        return 0;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0027",
+        title: "missing return",
+        messages: &[
+            "missing return in function `{}`",
+            "missing return in closure",
+        ],
+        explanation: r#"A function with a return type can reach the end of its body without
+returning a value.
+
+Erroneous code example:
+
+    fn sign(x: i32) -> i32 {
+        if x < 0 {
+            return -1;
+        }else if x > 0 {
+            return 1;
+        }
+    }
+
+Every path through the body has to end in `return`, a loop without a condition
+that is never left with `break` counts as well:
+
+    fn sign(x: i32) -> i32 {
+        if x < 0 {
+            return -1;
+        }else if x > 0 {
+            return 1;
+        }
+        return 0;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0030",
+        title: "trait not implemented",
+        messages: &[
+            "the trait `{}` is not implemented for `{}`",
+            "not all trait items implemented, missing `{}`",
+        ],
+        explanation: r#"A type was used as a trait object of a trait it does not implement, or an
+implementation is missing some of the methods of the trait.
+
+Erroneous code example:
+
+    trait Shape {
+        fn area(&self) -> i64;
+        fn scale(&mut self, f: i64);
+    }
+
+    struct Square { s: i64; };
+
+    impl Shape for Square {
+        fn area(&self) -> i64 { return self.s * self.s; }
+    }
+
+Implement every method of the trait that has no default body:
+
+    impl Shape for Square {
+        fn area(&self) -> i64 { return self.s * self.s; }
+        fn scale(&mut self, f: i64) { self.s = self.s * f; }
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0031",
+        title: "conflicting trait implementation",
+        messages: &["trait `{}` is already implemented for type `{}`"],
+        explanation: r#"A trait was implemented twice for the same type.
+
+Erroneous code example:
+
+    impl Shape for Square {
+        fn area(&self) -> i64 { return self.s * self.s; }
+    }
+
+    impl Shape for Square {
+        fn area(&self) -> i64 { return 0; }
+    }
+
+Remove one of the implementations.
+"#,
+    },
+    ErrorCode{
+        code: "E0032",
+        title: "method does not match trait",
+        messages: &[
+            "method `{}` has `{}` parameters, but the declaration in trait `{}` has `{}`",
+            "{} of method `{}` does not match trait `{}`",
+        ],
+        explanation: r#"A method in a trait implementation has a different signature than the
+declaration in the trait.
+
+Erroneous code example:
+
+    trait Shape {
+        fn area(&self) -> i64;
+    }
+
+    impl Shape for Square {
+        fn area(&self, scale: i64) -> i64 { return self.s * scale; }
+    }
+
+The parameters and the return type have to match the trait:
+
+    impl Shape for Square {
+        fn area(&self) -> i64 { return self.s * self.s; }
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0033",
+        title: "invalid trait object method",
+        messages: &[
+            "no method named `{}` found for `dyn {}`",
+            "the method `{}` cannot be called on a trait object",
+        ],
+        explanation: r#"A method was called on a trait object that the trait does not declare, or that
+cannot be dispatched dynamically.
+
+Erroneous code example:
+
+    fn total(s: &dyn Shape) -> i64 {
+        return s.perimeter();
+    }
+
+Only methods declared in the trait that take `self` by reference can be called
+on a `dyn` value:
+
+    fn total(s: &dyn Shape) -> i64 {
+        return s.area();
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0034",
+        title: "recursive type",
+        messages: &["cannot self reference"],
+        explanation: r#"A struct contains itself by value, which would give it an infinite size.
+
+Erroneous code example:
+
+    struct Node { value: i64; next: Node; };
+
+Store a pointer to the struct instead:
+
+    struct Node { value: i64; next: *Node; };
+"#,
+    },
+    ErrorCode{
+        code: "E0040",
+        title: "invalid match",
+        messages: &[
+            "cannot match on value of type `{}`",
+            "mismatched types in match arm",
+            "expected variant of enum `{}`, found `{}`",
+            "non-exhaustive patterns: {} not covered",
+            "variant `{}` has `{}` fields, but the pattern binds `{}`",
+            "variant `{}` does not have a field named `{}`",
+            "cannot bind variant fields in or-patterns",
+        ],
+        explanation: r#"A `match` has a pattern that does not fit the matched value, or it does not
+cover every possible value.
+
+Erroneous code example:
+
+    enum Kind { A, B, C }
+
+    fn main() -> i32 {
+        let k = Kind::A;
+        match k {
+            Kind::B => {}
+        }
+        return 0;
+    }
+
+Add arms for the missing variants, or a wildcard arm:
+
+    match k {
+        Kind::B => {}
+        _ => {}
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0041",
+        title: "invalid destructuring",
+        messages: &["cannot destructure a value of type `{}` into `{}` variables"],
+        explanation: r#"A `let` with a tuple pattern was given a value that is not a tuple with the
+same number of elements.
+
+Erroneous code example:
+
+    fn main() -> i32 {
+        let (a, b) = (1, 2, 3);
+        return 0;
+    }
+
+Bind every element, `_` skips one:
+
+    fn main() -> i32 {
+        let (a, b, _) = (1, 2, 3);
+        return 0;
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0042",
+        title: "closure outside of a function",
+        messages: &["closures can only be created inside of functions"],
+        explanation: r#"A closure was written outside of a function body.
+
+Erroneous code example:
+
+    static F: Fn(i32) -> i32 = |a: i32| { return a; };
+
+Create the closure inside of a function:
+
+    fn main() -> i32 {
+        let f = |a: i32| { return a; };
+        return f(0);
+    }
+"#,
+    },
+    ErrorCode{
+        code: "E0043",
+        title: "generic inference failed",
+        messages: &[
+            "cannot infer type for generic parameter `{}` of function `{}`",
+            "conflicting types for generic parameter `{}`",
+        ],
+        explanation: r#"The type arguments of a generic function call could not be inferred from its
+arguments.
+
+Erroneous code example:
+
+    fn pick<T>(a: T, b: T) -> T { return a; }
+
+    fn main() -> i32 {
+        let a: i32 = 1;
+        let b: i64 = 2;
+        pick(a, b);
+        return 0;
+    }
+
+Pass arguments of one type, or supply the type arguments explicitly:
+
+    pick::<i64>((:i64)a, b);
+"#,
+    },
+    ErrorCode{
+        code: "E0044",
+        title: "unreadable include",
+        messages: &["could not read file `{}`"],
+        explanation: r#"A file named by `#include` does not exist or could not be read.
+
+Erroneous code example:
+
+    #include "std/io/prnt.ly"
+
+Relative paths are resolved from the directory the compiler is run in:
+
+    #include "std/io/print.ly"
+"#,
+    },
+    ErrorCode{
+        code: "E0045",
+        title: "value cannot be formatted",
+        messages: &[
+            "cannot format `{}`",
+            "cannot format type `{}` with the default formatter",
+            "failed to get formatting function for `{}`",
+            "type `{}` does not implement trait `Debug`",
+            "type `{}` does not implement trait `Display`",
+        ],
+        explanation: r#"A value was passed to a formatting macro but its type has no formatting
+function.
+
+Erroneous code example:
+
+    struct Point { x: i32; y: i32; };
+
+    fn main() -> i32 {
+        let p: Point = {1, 2};
+        println$("{}", p);
+        return 0;
+    }
+
+Implement `Display` for the type, or derive `Debug` and format it with `{:?}`.
+"#,
+    },
+];
+
+/*
+ *  `{}` in a pattern matches any text, the rest has to match exactly
+ */
+fn matches(pattern: &str, msg: &str) -> bool {
+    let mut parts = pattern.split("{}");
+    let first = parts.next().unwrap();
+    let Some(mut rest) = msg.strip_prefix(first) else { return false; };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    return rest.is_empty();
+}
+
+pub fn lookup(msg: &str) -> Option<&'static str> {
+    return CODES.iter().find(|code| code.messages.iter().any(|pattern| matches(pattern, msg))).map(|code| code.code);
+}
+
+pub fn find(code: &str) -> Option<&'static ErrorCode> {
+    return CODES.iter().find(|c| c.code.eq_ignore_ascii_case(code));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /*
+     *  NOTE(S): the functions that report an error and the index of their message argument
+     */
+    const REPORTERS: &[(&str, usize)] = &[
+        ("error_msg_labels_nonfatal", 0),
+        ("error_msg_label_info", 0),
+        ("error_msg_labels", 0),
+        ("error_msg_label", 0),
+        ("token_expected_help", 1),
+        ("token_expected", 1),
+        ("error_msg", 1),
+    ];
+
+    /*
+     *  NOTE(S): internal errors that are not meant to be seen by users
+     */
+    const UNCODED: &[&str] = &["unreachable", "resolution expr err", "Arguments::new is undefined"];
+
+    fn is_ident(c: u8) -> bool {
+        return c.is_ascii_alphanumeric() || c == b'_';
+    }
+
+    /*
+     *  skips `count` arguments of a call, `pos` is just after the opening paren
+     */
+    fn skip_args(src: &[u8], mut pos: usize, mut count: usize) -> usize {
+        let mut depth = 0;
+        while count > 0 && pos < src.len() {
+            match src[pos] {
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => depth -= 1,
+                b'"' => {
+                    pos += 1;
+                    while src[pos] != b'"' {
+                        if src[pos] == b'\\' {
+                            pos += 1;
+                        }
+                        pos += 1;
+                    }
+                },
+                b',' if depth == 0 => count -= 1,
+                _ => {},
+            }
+            pos += 1;
+        }
+        return pos;
+    }
+
+    /*
+     *  the message literal of a call, either `"..."`, `&"..."` or `&format!("...", ..)`
+     */
+    fn message_literal(rest: &str) -> Option<String> {
+        let rest = rest.trim_start();
+        let rest = rest.strip_prefix('&').unwrap_or(rest).trim_start();
+        let rest = rest.strip_prefix("format!(").unwrap_or(rest).trim_start();
+        let rest = rest.strip_prefix('"')?;
+        return Some(rest[..rest.find('"')?].to_owned());
+    }
+
+    fn collect_messages(dir: &Path, messages: &mut Vec<(String, String)>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                collect_messages(&path, messages);
+                continue;
+            }
+            if path.extension().map_or(true, |ext| ext != "rs") {
+                continue;
+            }
+            let src = std::fs::read_to_string(&path).unwrap();
+            let bytes = src.as_bytes();
+            for (name, arg) in REPORTERS {
+                let call = format!("{}(", name);
+                for (start, _) in src.match_indices(&call) {
+                    if (start > 0 && is_ident(bytes[start - 1])) || src[..start].ends_with("fn ") {
+                        continue;
+                    }
+                    let pos = skip_args(bytes, start + call.len(), *arg);
+                    let line = src[..start].lines().count() + 1;
+                    match message_literal(&src[pos..]) {
+                        Some(msg) => messages.push((format!("{}:{}", path.display(), line), msg)),
+                        None => panic!("{}:{}: message of `{}` is not a literal", path.display(), line, name),
+                    }
+                }
+            }
+        }
+    }
+
+    fn source_messages() -> Vec<(String, String)> {
+        let mut messages = Vec::new();
+        collect_messages(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/src")), &mut messages);
+        return messages;
+    }
+
+    #[test]
+    fn matches_exact() {
+        assert!(matches("missing return in closure", "missing return in closure"));
+        assert!(!matches("missing return in closure", "missing return in closure body"));
+        assert!(!matches("missing return in closure", "a missing return in closure"));
+    }
+
+    #[test]
+    fn matches_wildcards() {
+        assert!(matches("unknown field `{}`", "unknown field `z`"));
+        assert!(matches("unknown field `{}`", "unknown field ``"));
+        assert!(!matches("unknown field `{}`", "unknown field z"));
+        assert!(matches("unexpected token found: `{}`", "unexpected token found: `;`"));
+        assert!(matches("type `{}` does not implement trait `{}`", "type `Foo` does not implement trait `Drop`"));
+        assert!(!matches("type `{}` does not implement trait `{}`", "type `Foo` implements trait `Drop`"));
+        assert!(matches("{}", "anything"));
+    }
+
+    #[test]
+    fn lookup_and_find() {
+        assert_eq!(lookup("unexpected token found"), Some("E0001"));
+        assert_eq!(lookup("unexpected token found: `}`"), Some("E0001"));
+        assert_eq!(lookup("not an error message"), None);
+        assert_eq!(find("e0001").map(|code| code.code), Some("E0001"));
+        assert!(find("E9999").is_none());
+    }
+
+    #[test]
+    fn codes_are_unique() {
+        for (i, code) in CODES.iter().enumerate() {
+            assert!(CODES[..i].iter().all(|other| other.code != code.code), "{} is registered twice", code.code);
+        }
+    }
+
+    #[test]
+    fn every_message_has_a_code() {
+        for (site, msg) in source_messages() {
+            if UNCODED.contains(&msg.as_str()) {
+                continue;
+            }
+            assert!(lookup(&msg).is_some(), "{}: `{}` has no error code", site, msg);
+        }
+    }
+
+    #[test]
+    fn every_pattern_matches_a_message() {
+        let messages = source_messages();
+        for code in CODES {
+            for pattern in code.messages {
+                assert!(messages.iter().any(|(_, msg)| matches(pattern, msg)), "{}: `{}` matches no error message", code.code, pattern);
+            }
+        }
+    }
+}
@@ -1,15 +1,20 @@
 use crate::lexer::Loc;
+use crate::io::ErrorFormat;
 
 use std::path::PathBuf;
 use std::cell::RefCell;
 use std::collections::HashMap;
 
-use ariadne::{sources, ReportKind, Report, Color, Source, Label, Config, CharSet};
+use serde::Serialize;
+use ariadne::{sources, ReportKind, Report, Color, Label, Config, CharSet};
+
+mod codes;
 
 thread_local! {
     static SRC_FILES: RefCell<HashMap<PathBuf, String>> = {
         RefCell::new(HashMap::with_capacity(1))
-    }
+    };
+    static DIAGNOSTICS: RefCell<Sink> = RefCell::new(Sink::default());
 }
 
 #[inline]
@@ -33,47 +38,17 @@ fn lazily_load_src_file<'a>(path: &'a std::path::PathBuf) -> String {
 }
 
 pub fn token_expected<S: AsRef<str>>(loc: &Loc, msg: S, info: S) -> ! {
-    let path_str = loc.file.to_str().unwrap();
-    Report::build(ReportKind::Error, "", loc.start)
-        .with_message(msg.as_ref())
-        .with_label(
-            Label::new((path_str, loc.start..loc.end))
-            .with_message(info.as_ref())
-            .with_color(Color::Red)
-        )
-        .with_config(get_config())
-        .finish()
-        .eprint((path_str, Source::from(lazily_load_src_file(&loc.file.clone().into()))))
-        .unwrap();
-    std::process::exit(1);
+    report(Diagnostic::error(msg.as_ref()).with_label(ErrorLabel::from(loc, info.as_ref())));
 }
 
 pub fn token_expected_help<S: AsRef<str>>(loc: &Loc, msg: S, info: S, help: S) -> ! {
-    let path_str = loc.file.to_str().unwrap();
-    Report::build(ReportKind::Error, "", loc.start)
-        .with_message(msg.as_ref())
-        .with_label(
-            Label::new((path_str, loc.start..loc.end))
-            .with_message(info.as_ref())
-            .with_color(Color::Red)
-        )
-        .with_help(help.as_ref())
-        .with_config(get_config())
-        .finish()
-        .eprint((path_str, Source::from(lazily_load_src_file(&loc.file.clone().into()))))
-        .unwrap();
-    std::process::exit(1);
+    report(Diagnostic::error(msg.as_ref()).with_label(ErrorLabel::from(loc, info.as_ref())).with_help(help.as_ref()));
 }
 
 pub fn error_msg(path: &str, msg: &str) -> ! {
-    Report::<(&str, std::ops::Range<usize>)>::build(ReportKind::Error, "", 0)
-        .with_message(msg)
-        .with_config(get_config())
-        .finish()
-        .eprint((path, Source::from(lazily_load_src_file(&PathBuf::from(path)))))
-        .unwrap();
-
-    std::process::exit(1);
+    let mut diag = Diagnostic::error(msg);
+    diag.path = Some(path.to_owned());
+    report(diag);
 }
 
 use std::ops::Range;
@@ -82,63 +57,315 @@ pub struct ErrorLabel {
     path: String,
     msg: String,
     range: Range<usize>,
+    bytes: Range<usize>,
+    line: usize,
+    column: usize,
 }
 
 impl ErrorLabel {
-    pub fn new<S: ToString>(path: S, range: Range<usize>, msg: S) -> Self {
-        Self{ path: path.to_string(), msg: msg.to_string(), range }
-    }
-
     pub fn from<S: ToString>(loc: &Loc, msg: S) -> Self {
-        Self{ path: loc.file.to_str().unwrap().into(), msg: msg.to_string(), range: loc.start..loc.end }
+        Self{
+            path: loc.file.to_str().unwrap().into(),
+            msg: msg.to_string(),
+            range: loc.start..loc.end,
+            bytes: loc.byte_start..loc.byte_end,
+            line: loc.line,
+            column: loc.column,
+        }
     }
 }
 
-impl<'a> From<&'a ErrorLabel> for Label<(String, Range<usize>)> {
-    fn from(value: &'a ErrorLabel) -> Self {
-        Label::new((value.path.clone(), value.range.clone())).with_message(value.msg.clone()).with_color(Color::Red)
+pub fn error_msg_label(msg: &str, label: ErrorLabel) -> ! {
+    report(Diagnostic::error(msg).with_label(label));
+}
+
+pub fn error_msg_label_info(msg: &str, label: ErrorLabel, info: &str) -> ! {
+    report(Diagnostic::error(msg).with_label(label).with_note(info));
+}
+
+pub fn error_msg_labels(msg: &str, labels: &[ErrorLabel]) -> ! {
+    let mut diag = Diagnostic::error(msg);
+    diag.labels.extend_from_slice(labels);
+    report(diag);
+}
+
+/*
+ *  NOTE(S): for errors that leave the ast intact, the compilation fails but the statement is
+ *  still generated
+ */
+pub fn error_msg_labels_nonfatal(msg: &str, labels: &[ErrorLabel]) {
+    let mut diag = Diagnostic::error(msg);
+    diag.labels.extend_from_slice(labels);
+    emit(diag);
+}
+
+/*
+ *  NOTE(S): a lint is a warning unless it is denied with `-D`, a denied lint is counted as an
+ *  error but does not stop the compilation. the name of the lint is used as its code
+ */
+pub fn lint_msg_labels(lint: &'static str, deny: bool, msg: &str, labels: &[ErrorLabel], note: &str, help: Option<&str>) {
+    let mut diag = if deny { Diagnostic::error(msg) } else { Diagnostic::warning(msg) };
+    diag.code = Some(lint);
+    diag.labels.extend_from_slice(labels);
+    diag.note = Some(note.to_owned());
+    diag.help = help.map(|help| help.to_owned());
+    emit(diag);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/*
+ *  a single error or warning, it is rendered as soon as it is emitted
+ */
+#[derive(Clone)]
+pub struct Diagnostic {
+    severity: Severity,
+    code: Option<&'static str>,
+    msg: String,
+    labels: Vec<ErrorLabel>,
+    note: Option<String>,
+    help: Option<String>,
+    /*
+     *  the file a diagnostic without labels refers to
+     */
+    path: Option<String>,
+}
+
+impl Diagnostic {
+    fn error(msg: &str) -> Self {
+        Self{ severity: Severity::Error, code: codes::lookup(msg), msg: msg.to_owned(), labels: Vec::new(), note: None, help: None, path: None }
+    }
+
+    fn warning(msg: &str) -> Self {
+        Self{ severity: Severity::Warning, ..Self::error(msg) }
+    }
+
+    fn with_label(mut self, label: ErrorLabel) -> Self {
+        self.labels.push(label);
+        return self;
+    }
+
+    fn with_note(mut self, note: &str) -> Self {
+        self.note = Some(note.to_owned());
+        return self;
+    }
+
+    fn with_help(mut self, help: &str) -> Self {
+        self.help = Some(help.to_owned());
+        return self;
+    }
+
+    /*
+     *  NOTE(S): one object per line for `--error-format=json`, the first label is the primary span
+     */
+    fn render_json(&self) {
+        let spans: Vec<JsonSpan> = self.labels.iter().enumerate().map(|(i, label)| JsonSpan{
+            file: &label.path,
+            line: label.line,
+            column: label.column,
+            start: label.range.start,
+            end: label.range.end,
+            byte_start: label.bytes.start,
+            byte_end: label.bytes.end,
+            label: &label.msg,
+            primary: i == 0,
+        }).collect();
+        let json = JsonDiagnostic{
+            severity: self.severity,
+            code: self.code,
+            message: &self.msg,
+            file: self.labels.first().map(|label| label.path.as_str()).or(self.path.as_deref()),
+            spans,
+            note: self.note.as_deref(),
+            help: self.help.as_deref(),
+        };
+        eprintln!("{}", serde_json::to_string(&json).unwrap());
+    }
+
+    fn render(&self) {
+        let (kind, color) = match self.severity {
+            Severity::Error => (ReportKind::Error, Color::Red),
+            Severity::Warning => (ReportKind::Warning, Color::Yellow),
+        };
+        let (path, offset) = match self.labels.first() {
+            Some(label) => (label.path.clone(), label.range.start),
+            None => (self.path.clone().unwrap_or_default(), 0),
+        };
+
+        let mut builder = Report::<(String, Range<usize>)>::build(kind, path.clone(), offset)
+            .with_message(&self.msg)
+            .with_config(get_config());
+        if let Some(code) = self.code {
+            builder = builder.with_code(code);
+        }
+        for label in &self.labels {
+            builder.add_label(Label::new((label.path.clone(), label.range.clone())).with_message(label.msg.clone()).with_color(color));
+        }
+        if let Some(note) = &self.note {
+            builder = builder.with_note(note);
+        }
+        if let Some(help) = &self.help {
+            builder = builder.with_help(help);
+        }
+
+        let mut files: Vec<String> = self.labels.iter().map(|label| label.path.clone()).collect();
+        files.push(path);
+        files.sort();
+        files.dedup();
+        let sources_vec: Vec<(String, String)> = files.into_iter().map(|path| {
+            let content = lazily_load_src_file(&PathBuf::from(&path));
+            return (path, content);
+        }).collect();
+        builder.finish().eprint(sources(sources_vec)).unwrap();
     }
 }
 
-pub fn error_msg_label(msg: &str, label: ErrorLabel) -> ! {
-    Report::<(String, std::ops::Range<usize>)>::build(ReportKind::Error, "", 0)
-        .with_message(msg)
-        .with_label(Label::from(&label))
-        .with_config(get_config())
-        .finish()
-        .eprint((label.path.clone(), Source::from(lazily_load_src_file(&PathBuf::from(&label.path)))))
-        .unwrap();
+#[derive(Serialize)]
+struct JsonSpan<'a> {
+    file: &'a str,
+    line: usize,
+    column: usize,
+    start: usize,
+    end: usize,
+    byte_start: usize,
+    byte_end: usize,
+    label: &'a str,
+    primary: bool,
+}
 
-    std::process::exit(1);
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    severity: Severity,
+    code: Option<&'static str>,
+    message: &'a str,
+    file: Option<&'a str>,
+    spans: Vec<JsonSpan<'a>>,
+    note: Option<&'a str>,
+    help: Option<&'a str>,
 }
 
-pub fn error_msg_label_info(msg: &str, label: ErrorLabel, info: &str) -> ! {
-    Report::<(String, std::ops::Range<usize>)>::build(ReportKind::Error, "", 0)
-        .with_message(msg)
-        .with_label(Label::from(&label))
-        .with_note(info)
-        .with_config(get_config())
-        .finish()
-        .eprint((label.path.clone(), Source::from(lazily_load_src_file(&PathBuf::from(&label.path)))))
-        .unwrap();
+/*
+ *  NOTE(S): collects the diagnostics of a compilation. an error unwinds to the closest `recover`
+ *  which skips the statement or item the error was found in, so one run reports every error
+ *  instead of just the first one
+ */
+#[derive(Default)]
+struct Sink {
+    errors: usize,
+    warnings: usize,
+    /*
+     *  `0` does not limit the number of errors
+     */
+    error_limit: usize,
+    format: ErrorFormat,
+}
 
-    std::process::exit(1);
+/*
+ *  the payload of the unwind started by an error, every other panic is a bug in the compiler
+ */
+struct Poisoned;
+
+pub fn set_error_limit(limit: usize) {
+    DIAGNOSTICS.with(|sink| sink.borrow_mut().error_limit = limit);
 }
 
-pub fn error_msg_labels(msg: &str, labels: &[ErrorLabel]) -> ! {
-    let sources_vec: Vec<(String, String)> = labels.iter().map(|label| {
-        return (label.path.to_owned(), lazily_load_src_file(&PathBuf::from(&label.path)));
-    }).collect();
+pub fn set_error_format(format: ErrorFormat) {
+    DIAGNOSTICS.with(|sink| sink.borrow_mut().format = format);
+}
+
+pub fn has_errors() -> bool {
+    return DIAGNOSTICS.with(|sink| sink.borrow().errors > 0);
+}
+
+fn error_format() -> ErrorFormat {
+    return DIAGNOSTICS.with(|sink| sink.borrow().format.clone());
+}
+
+/*
+ *  prints the summary of a compilation, it is not counted as a diagnostic. json output only has
+ *  diagnostics, the exit code tells if the compilation failed
+ */
+fn summary(msg: &str) {
+    if error_format() == ErrorFormat::human {
+        eprintln!("error: {}", msg);
+    }
+}
 
-    let mut builder = Report::<(String, std::ops::Range<usize>)>::build(ReportKind::Error, "", 0);
-    for label in labels {
-        builder.add_label(Label::from(label));
+/*
+ *  prints the long-form explanation of an error code for `--explain`
+ */
+pub fn explain(code: &str) -> bool {
+    let Some(code) = codes::find(code) else { return false; };
+    println!("{}: {}\n", code.code, code.title);
+    print!("{}", code.explanation);
+    return true;
+}
+
+fn emit(diag: Diagnostic) {
+    match error_format() {
+        ErrorFormat::human => diag.render(),
+        ErrorFormat::json => diag.render_json(),
     }
-    builder.with_message(msg)
-        .with_config(get_config())
-        .finish()
-        .eprint(sources(sources_vec.clone()))
-        .unwrap();
+    let (errors, limit) = DIAGNOSTICS.with(|sink| {
+        let mut sink = sink.borrow_mut();
+        match diag.severity {
+            Severity::Error => sink.errors += 1,
+            Severity::Warning => sink.warnings += 1,
+        }
+        return (sink.errors, sink.error_limit);
+    });
+    if limit != 0 && errors >= limit {
+        summary(format!("aborting after {} errors, the error limit was reached", errors).as_str());
+        std::process::exit(1);
+    }
+}
 
-    std::process::exit(1);
+fn report(diag: Diagnostic) -> ! {
+    emit(diag);
+    poison();
+}
+
+/*
+ *  unwinds to the closest `recover` without reporting anything, the error was reported already
+ */
+pub fn poison() -> ! {
+    std::panic::resume_unwind(Box::new(Poisoned));
+}
+
+/*
+ *  runs `f`, returns `None` if an error was reported while running it
+ */
+pub fn recover<T>(f: impl FnOnce() -> T) -> Option<T> {
+    return match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        Ok(value) => Some(value),
+        Err(payload) if payload.is::<Poisoned>() => None,
+        Err(payload) => std::panic::resume_unwind(payload),
+    };
+}
+
+/*
+ *  NOTE(S): prints the summary and exits if any error was reported, nothing is emitted then
+ */
+pub fn finish(file: &str) {
+    let (errors, warnings) = DIAGNOSTICS.with(|sink| {
+        let sink = sink.borrow();
+        return (sink.errors, sink.warnings);
+    });
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
+    if errors > 0 {
+        if warnings > 0 {
+            summary(format!("could not compile `{}` due to {} previous error{}; {} warning{} emitted", file, errors, plural(errors), warnings, plural(warnings)).as_str());
+        }else {
+            summary(format!("could not compile `{}` due to {} previous error{}", file, errors, plural(errors)).as_str());
+        }
+        std::process::exit(1);
+    }
+    if warnings > 0 && error_format() == ErrorFormat::human {
+        eprintln!("warning: `{}` generated {} warning{}", file, warnings, plural(warnings));
+    }
 }
@@ -26,17 +26,29 @@ pub struct GenerationContext<'a> {
     pub builder: &'a llvm::IRBuilder<'a>,
     pub current_function: *mut crate::ast::Function,
     pub current_break_point: Vec<*mut llvm::BasicBlock>,
+    pub current_continue_point: Vec<*mut llvm::BasicBlock>,
+    pub current_loop_label: Vec<Option<String>>,
+    pub current_loop_scope: Vec<*const crate::ast::Scope>,
+    pub checks: bool,
 }
 
 impl<'a> GenerationContext<'a> {
-    pub fn new(ctx: &'a llvm::Context, module: &'a llvm::Module<'a>, builder: &'a llvm::IRBuilder<'a>) -> GenerationContext<'a> {
-        return Self{ ctx, module, builder, current_function: std::ptr::null_mut(), current_break_point: Vec::new() }
+    pub fn new(ctx: &'a llvm::Context, module: &'a llvm::Module<'a>, builder: &'a llvm::IRBuilder<'a>, checks: bool) -> GenerationContext<'a> {
+        return Self{ ctx, module, builder, current_function: std::ptr::null_mut(), current_break_point: Vec::new(), current_continue_point: Vec::new(), current_loop_label: Vec::new(), current_loop_scope: Vec::new(), checks }
     }
 }
 
 fn main() {
     let opt = io::get_cli_options();
 
+    if let Some(code) = &opt.explain {
+        if !log::explain(code) {
+            eprintln!("[error]: `{}` is not a valid error code", code);
+            std::process::exit(1);
+        }
+        std::process::exit(0);
+    }
+
     let file = if let Some(file) = &opt.input_file {
         file
     }else {
@@ -64,13 +76,28 @@ fn main() {
     m.set_target_triple(&tt);
     let builder = llvm::IRBuilder::new(&ctx);
 
-    let mut lexer = Lexer::from(&content, &PathBuf::from(file));
-    let mut parser = Parser::new(&mut lexer);
-    let mut ast = parser.build_ast();
-    let mut scope = Scope::new();
-    ast.collect_symbols(&mut scope);
-    let ctx = GenerationContext::new(&ctx, &m, &builder);
-    ast.gen_code(&mut Scope::new(), &ctx);
+    log::set_error_limit(opt.error_limit);
+    log::set_error_format(opt.error_format.clone());
+    if let Err(lint) = ast::lints::configure(&opt.warn, &opt.allow, &opt.deny) {
+        eprintln!("[error]: unknown lint `{}`, the available lints are:", lint);
+        for lint in ast::lints::LINTS {
+            eprintln!("    {:<18} {}", lint.name, lint.description);
+        }
+        std::process::exit(1);
+    }
+    let ctx = GenerationContext::new(&ctx, &m, &builder, opt.checks);
+    let ast = log::recover(|| {
+        let mut lexer = Lexer::from(&content, &PathBuf::from(file));
+        let mut parser = Parser::new(&mut lexer);
+        let mut ast = parser.build_ast();
+        let mut scope = Scope::new();
+        ast.collect_symbols(&mut scope);
+        ast.gen_code(&mut Scope::new(), &ctx);
+        return ast;
+    });
+    ast::lints::report(file);
+    log::finish(file);
+    let ast = ast.unwrap();
 
     let out_file = PathBuf::from(&opt.output_file);
 
@@ -3,7 +3,7 @@ pub mod parser {
 
     use crate::ast::*;
     use crate::lexer::{Lexer, Loc, Token, Tagged};
-    use crate::types::{TokenType, Type, Path, Pointer, Array, ArraySlice, FuncPtr, containers};
+    use crate::types::{TokenType, Type, Path, Pointer, Array, ArraySlice, FuncPtr, Closure, Tuple, DynTrait, containers};
     use crate::log::{token_expected, token_expected_help};
 
 
@@ -27,7 +27,11 @@ pub mod parser {
         preprocessor: Preprocessor,
         current_impl: containers::Pointer<Impl>,
         current_trait: containers::Pointer<Trait>,
-        current_block: containers::Pointer<Block>,
+        /*
+         *  NOTE(S): the identifiers used inside of every closure currently being parsed, they
+         *  are the candidates for capturing
+         */
+        closure_idents: Vec<Vec<Tagged<String>>>,
     }
 
     impl Parser {
@@ -38,7 +42,7 @@ pub mod parser {
                 preprocessor: Preprocessor::new(),
                 current_impl: containers::Pointer::new(),
                 current_trait: containers::Pointer::new(),
-                current_block: containers::Pointer::new(),
+                closure_idents: Vec::new(),
             };
             parser.tokens.push(Token::new(
                 "",
@@ -55,7 +59,7 @@ pub mod parser {
                 preprocessor: Preprocessor::new(),
                 current_impl: containers::Pointer::new(),
                 current_trait: containers::Pointer::new(),
-                current_block: containers::Pointer::new(),
+                closure_idents: Vec::new(),
             };
             parser.tokens.push(Token::new(
                 "",
@@ -68,7 +72,13 @@ pub mod parser {
         pub fn build_ast(&mut self) -> AST {
             let mut module = Mod::new();
             loop {
-                module.body.body.push(self.parse_globals());
+                match self.recover(Self::parse_globals) {
+                    Some(item) => module.body.body.push(item),
+                    None if self.at().typ == TokenType::CurlyRight => {
+                        self.eat();
+                    },
+                    None => {},
+                }
                 if self.at().typ == TokenType::Eof {
                     break;
                 }
@@ -76,6 +86,48 @@ pub mod parser {
             return AST::Mod(module);
         }
 
+        /*
+         *  NOTE(S): a syntax error skips the rest of the statement or item it was found in so the
+         *  remaining ones are still parsed, the error itself was reported already
+         */
+        fn recover<T>(&mut self, parse: fn(&mut Self) -> T) -> Option<T> {
+            let closures = self.closure_idents.len();
+            if let Some(node) = crate::log::recover(|| parse(self)) {
+                return Some(node);
+            }
+            self.closure_idents.truncate(closures);
+            self.synchronize();
+            return None;
+        }
+
+        /*
+         *  skips to the token after the next `;` or the `}` closing a block opened after the
+         *  error, the `}` of the enclosing block is not consumed
+         */
+        fn synchronize(&mut self) {
+            let mut depth = 0;
+            loop {
+                match self.at().typ {
+                    TokenType::Eof => crate::log::poison(),
+                    TokenType::Semi if depth == 0 => {
+                        self.eat();
+                        return;
+                    },
+                    TokenType::CurlyLeft => depth += 1,
+                    TokenType::CurlyRight if depth == 0 => return,
+                    TokenType::CurlyRight => {
+                        depth -= 1;
+                        if depth == 0 {
+                            self.eat();
+                            return;
+                        }
+                    },
+                    _ => {},
+                }
+                self.eat();
+            }
+        }
+
         #[inline]
         fn at(&self) -> &Token {
             return &self.tokens[self.index];
@@ -130,7 +182,7 @@ pub mod parser {
         }
 
         fn parse_struct_decl(&mut self) -> AST {
-            self.eat();
+            let kw = self.eat().clone();
 
             let id = self.eat().clone();
 
@@ -138,6 +190,9 @@ pub mod parser {
                 token_expected(&id.loc, "unexpected token found", "expected identifier after keyword `struct`");
             }
 
+            let params = self.parse_generic_params();
+            let start = self.index;
+
             if self.eat().typ != TokenType::CurlyLeft {
                 token_expected_help(&self.peek(-1).loc, "unexpected token found", "expected `{` after struct identifier", "try adding `{`");
             }
@@ -152,6 +207,11 @@ pub mod parser {
                 token_expected(&self.peek(-1).loc, "unexpected token found", "expected, `;` after struct declaration");
             }
 
+            if !params.is_empty() {
+                let template = symbol::Template::new(params, vec![kw], self.capture_tokens(start));
+                return AST::Generic(Generic::new(id.into(), GenericKind::Struct, template));
+            }
+
             return AST::StructDef(StructDef::new(Tagged::new(id.loc, id.value), fields));
         }
 
@@ -200,7 +260,7 @@ pub mod parser {
         }
 
         fn parse_func(&mut self) -> AST {
-            self.eat();
+            let kw = self.eat().clone();
 
             let id = self.eat().clone();
 
@@ -208,6 +268,12 @@ pub mod parser {
                 token_expected(&id.loc, "unexpected token found", "expected identifier after keyword `fn`");
             }
 
+            let params = self.parse_generic_params();
+            if !params.is_empty() && (!self.current_impl.is_null() || !self.current_trait.is_null()) {
+                token_expected(params[0].loc(), "generic member functions are not supported", "remove the generic parameters");
+            }
+            let start = self.index;
+
             if self.eat().typ != TokenType::ParanLeft {
                 token_expected(&self.peek(-1).loc, "unexpected token found", "expected `(`");
             }
@@ -221,7 +287,7 @@ pub mod parser {
                 }
 
                 if self.eat().typ != TokenType::Comma {
-                    token_expected(&self.peek(-1).loc, "unexpected token found", "function arguments need to be comma seperated");
+                    token_expected(&self.peek(-1).loc, "unexpected token found", "function arguments need to be comma separated");
                 }
             }
             self.eat();
@@ -233,13 +299,16 @@ pub mod parser {
             };
 
             let c_impl = if !self.current_impl.is_null() {
-                Some(Impl::new(self.current_impl.as_ref().typ.clone(), Block::new(), self.current_impl.as_ref().trat.clone()))
+                Some(Impl::new(self.current_impl.as_ref().typ.clone(), Block::new(), self.current_impl.as_ref().trat.clone(), self.current_impl.as_ref().trat_args.clone()))
             }else {
                 None
             };
 
             if self.at().typ == TokenType::Semi {
                 self.eat();
+                if !params.is_empty() {
+                    token_expected(params[0].loc(), "generic functions require a body", "generic parameters declared here");
+                }
                 return AST::Function(Function::new(id.into(), c_impl, args, Block::new(), ret_type, false, false));
             }
 
@@ -249,41 +318,72 @@ pub mod parser {
 
             let mut body = Block::new();
             while self.at().typ != TokenType::CurlyRight {
-                self.current_block = containers::Pointer::from(&body);
-                body.body.push(self.parse_stmt());
+                if let Some(stmt) = self.recover(Self::parse_stmt) {
+                    body.body.push(stmt);
+                }
             }
             self.eat();
+
+            if !params.is_empty() {
+                let template = symbol::Template::new(params, vec![kw], self.capture_tokens(start));
+                return AST::Generic(Generic::new(id.into(), GenericKind::Function(args, ret_type), template));
+            }
             return AST::Function(Function::new(id.into(), c_impl, args, body, ret_type, true, false));
         }
 
         fn parse_impl(&mut self) -> AST {
-            self.eat();
+            let mut header = vec![self.eat().clone()];
+            let params = self.parse_generic_params();
 
             let mut trat = None;
-            if self.at().typ == TokenType::Id && self.peek(1).typ == TokenType::KwFor {
+            let mut trat_args = Vec::new();
+            if self.at().typ == TokenType::Id && self.is_trait_for() {
+                let start = self.index;
                 trat = Some(self.eat().clone());
-                self.eat();
+                if self.at().typ == TokenType::AngleLeft {
+                    trat_args = self.parse_type_args();
+                }
+                header.extend(self.capture_tokens(start));
+                header.push(self.eat().clone());
             }
 
-            let struct_impl = self.eat().clone();
+            let mut struct_impl = self.eat().clone();
 
             if struct_impl.typ != TokenType::Id {
                 token_expected(&struct_impl.loc, "unexpected token found", "expected identifier after keyword `impl`");
             }
 
+            let base = struct_impl.clone();
+            if self.at().typ == TokenType::AngleLeft {
+                let args = self.parse_type_args();
+                struct_impl.value = instance_name(&base.value, &args);
+            }
+
+            if !params.is_empty() {
+                let expected = format!("{}<{}>", base.value, params.iter().map(|p| p.inner().as_str()).collect::<Vec<&str>>().join(", "));
+                if struct_impl.value != expected {
+                    token_expected(&struct_impl.loc, "unexpected type in generic impl", format!("expected `{}`", expected).as_str());
+                }
+            }
+            let start = self.index;
+
             if self.eat().typ != TokenType::CurlyLeft {
                 token_expected_help(&self.peek(-1).loc, "unexpected token found", "expected `{`", "try adding `{`");
             }
 
-            let mut r#impl = Impl::new(struct_impl.into(), Block::new(), trat.map(|v| v.into()));
+            let mut r#impl = Impl::new(struct_impl.into(), Block::new(), trat.map(|v| v.into()), trat_args);
             self.current_impl = containers::Pointer::from(&r#impl);
             while self.at().typ != TokenType::CurlyRight {
-                self.current_block = containers::Pointer::from(&r#impl.body);
                 r#impl.body.body.push(self.parse_func());
             }
             self.eat();
 
             self.current_impl = containers::Pointer::new();
+
+            if !params.is_empty() {
+                let template = symbol::Template::new(params, header, self.capture_tokens(start));
+                return AST::Generic(Generic::new(base.into(), GenericKind::Impl, template));
+            }
             return AST::Impl(r#impl);
         }
 
@@ -324,15 +424,21 @@ pub mod parser {
                 token_expected(&id.loc, "unexpected token found", "expected identifier after keyword `impl`");
             }
 
+            let params = self.parse_generic_params();
+
             if self.eat().typ != TokenType::CurlyLeft {
                 token_expected_help(&self.peek(-1).loc, "unexpected token found", "expected `{`", "try adding `{`");
             }
 
-            let mut trat = Trait::new(Tagged::new(id.loc.clone(), id.value.clone()), Block::new());
+            let mut trat = Trait::new(Tagged::new(id.loc.clone(), id.value.clone()), params, Block::new());
             self.current_trait = containers::Pointer::from(&trat);
             while self.at().typ != TokenType::CurlyRight {
-                self.current_block = containers::Pointer::from(&trat.funcs);
-                trat.funcs.body.push(self.parse_func());
+                let start = self.index;
+                let func = self.parse_func();
+                if self.peek(-1).typ == TokenType::CurlyRight {
+                    trat.provided.push((func.get_value(), self.capture_tokens(start)));
+                }
+                trat.funcs.body.push(func);
             }
             self.eat();
 
@@ -393,7 +499,7 @@ pub mod parser {
 
                     for MacroArg { id, .. } in &conds {
                         if *id.inner() == tok_name.value {
-                            token_expected(&tok_name.loc, "douplicate symbol found", format!("found duplicate identifier in macro arm param `{}`", tok_name.value).as_str());
+                            token_expected(&tok_name.loc, "duplicate symbol found", format!("found duplicate identifier in macro arm param `{}`", tok_name.value).as_str());
                         }
                     }
                     conds.push(MacroArg { id: tok_name.into(), typ });
@@ -401,7 +507,7 @@ pub mod parser {
                         break;
                     }
                     if self.at().typ != TokenType::Comma {
-                        token_expected(&self.at().loc, "unexpected token found", "macro args need to be `,` seperated");
+                        token_expected(&self.at().loc, "unexpected token found", "macro args need to be `,` separated");
                     }else {
                         self.eat();
                     }
@@ -501,14 +607,14 @@ pub mod parser {
                     token_expected(&self.at().loc, "unexpected token found", "expected identifier in enum declaration");
                 }
 
-                variants.push(self.eat().value.clone());
+                variants.push(self.parse_enum_variant());
 
                 if self.at().typ == TokenType::CurlyRight {
                     break;
                 }
 
                 if self.eat().typ != TokenType::Comma {
-                    token_expected(&self.peek(-1).loc, "unexpected token found", "enum variants need to be comma `,` seperated");
+                    token_expected(&self.peek(-1).loc, "unexpected token found", "enum variants need to be comma `,` separated");
                 }
             }
             self.eat();
@@ -516,6 +622,51 @@ pub mod parser {
             return AST::EnumDef(EnumDef::new(id.into(), variants, ty));
         }
 
+        fn parse_enum_variant(&mut self) -> EnumVariant {
+            let id: Tagged<String> = self.eat().clone().into();
+            let mut fields = Vec::new();
+
+            match self.at().typ {
+                TokenType::ParanLeft => {
+                    self.eat();
+                    while self.at().typ != TokenType::ParanRight {
+                        let typ = self.parse_type_spec();
+                        fields.push(StructField{ id: Tagged::new(typ.get_loc(), fields.len().to_string()), typ });
+                        if self.at().typ == TokenType::ParanRight {
+                            break;
+                        }
+                        if self.eat().typ != TokenType::Comma {
+                            token_expected(&self.peek(-1).loc, "unexpected token found", "variant fields need to be comma `,` separated");
+                        }
+                    }
+                    self.eat();
+                    return EnumVariant::new(id, fields, true);
+                },
+                TokenType::CurlyLeft => {
+                    self.eat();
+                    while self.at().typ != TokenType::CurlyRight {
+                        let field = self.eat().clone();
+                        if field.typ != TokenType::Id {
+                            token_expected(&field.loc, "unexpected token found", "expected identifier in variant field declaration");
+                        }
+                        if self.eat().typ != TokenType::Colon {
+                            token_expected(&self.peek(-1).loc, "missing type specifier", format!("field `{}` requires a type", field.value).as_str());
+                        }
+                        fields.push(StructField{ id: field.into(), typ: self.parse_type_spec() });
+                        if self.at().typ == TokenType::CurlyRight {
+                            break;
+                        }
+                        if self.eat().typ != TokenType::Comma {
+                            token_expected(&self.peek(-1).loc, "unexpected token found", "variant fields need to be comma `,` separated");
+                        }
+                    }
+                    self.eat();
+                    return EnumVariant::new(id, fields, false);
+                },
+                _ => return EnumVariant::new(id, fields, true),
+            }
+        }
+
         fn parse_type_def(&mut self) -> AST {
             self.eat();
 
@@ -540,8 +691,15 @@ pub mod parser {
         fn parse_stmt(&mut self) -> AST {
             return match self.at().typ {
                 TokenType::KwIf => self.parse_if_stmt(),
-                TokenType::KwFor => self.parse_for_stmt(),
+                TokenType::KwFor => self.parse_for_stmt(None),
+                TokenType::KwWhile => self.parse_while_stmt(None),
+                TokenType::KwLoop => self.parse_loop_stmt(None),
                 TokenType::KwMatch => self.parse_match_stmt(),
+                TokenType::KwDefer => {
+                    let loc = self.eat().loc.clone();
+                    AST::DeferStmt(DeferStmt::new(loc, Box::new(self.parse_stmt())))
+                },
+                TokenType::Label if self.peek(1).typ == TokenType::Colon => self.parse_labeled_stmt(),
                 _ => {
                     let node = self.parse_expr();
                     if self.eat().typ != TokenType::Semi {
@@ -553,43 +711,127 @@ pub mod parser {
         }
 
         fn parse_if_stmt(&mut self) -> AST {
-            self.eat();
+            let loc = self.eat().loc.clone();
             let cond = self.parse_expr();
 
+            let then_branch = self.parse_if_body();
+
+            let else_branch = if self.at().typ == TokenType::KwElse {
+                self.eat();
+
+                if self.at().typ == TokenType::KwIf {
+                    let mut else_branch = Block::new();
+                    let nested = self.parse_if_stmt();
+                    else_branch.yields = matches!(&nested, AST::IfStmt(nested) if nested.yields());
+                    else_branch.body.push(nested);
+                    Some(else_branch)
+                }else {
+                    Some(self.parse_if_body())
+                }
+            }else {
+                None
+            };
+
+            return AST::IfStmt(IfStmt::new(loc, Box::new(cond), then_branch, else_branch));
+        }
+
+        /*
+         *  NOTE(S): the last expression of a branch may omit the `;`, it is then the value of the
+         *  branch
+         */
+        fn parse_if_body(&mut self) -> Block {
             if self.eat().typ != TokenType::CurlyLeft {
                 token_expected(&self.peek(-1).loc, "unexpected token found", "expected `{` after if statement");
             }
 
-            let mut then_branch = Block::new();
+            let mut block = Block::new();
             while self.at().typ != TokenType::CurlyRight {
-                self.current_block = containers::Pointer::from(&then_branch);
-                then_branch.body.push(self.parse_stmt());
+                if let Some((stmt, yields)) = self.recover(Self::parse_branch_stmt) {
+                    block.yields = yields;
+                    block.body.push(stmt);
+                }
             }
             self.eat();
+            return block;
+        }
 
-            let else_branch = if self.at().typ == TokenType::KwElse {
-                self.eat();
+        /*
+         *  returns the statement and whether it is the value of the branch
+         */
+        fn parse_branch_stmt(&mut self) -> (AST, bool) {
+            if matches!(self.at().typ, TokenType::KwIf | TokenType::KwFor | TokenType::KwWhile | TokenType::KwLoop | TokenType::KwMatch | TokenType::KwDefer | TokenType::Label) {
+                let stmt = self.parse_stmt();
+                let yields = self.at().typ == TokenType::CurlyRight && matches!(&stmt, AST::IfStmt(stmt) if stmt.yields());
+                return (stmt, yields);
+            }
 
-                if self.eat().typ != TokenType::CurlyLeft {
-                    token_expected(&self.peek(-1).loc, "unexpected token found", "expected `{` after if statement");
-                }
+            let node = self.parse_expr();
+            if self.at().typ == TokenType::CurlyRight {
+                return (node, true);
+            }
+            if self.eat().typ != TokenType::Semi {
+                token_expected_help(&self.peek(-1).loc, "unexpected token found", "expected `;` at the end of expression", "add `;` to mark end of expression");
+            }
+            return (node, false);
+        }
 
-                let mut else_branch = Block::new();
-                while self.at().typ != TokenType::CurlyRight {
-                    self.current_block = containers::Pointer::from(&else_branch);
-                    else_branch.body.push(self.parse_stmt());
-                }
-                self.eat();
-                Some(else_branch)
-            }else {
-                None
+        fn parse_labeled_stmt(&mut self) -> AST {
+            let label: Tagged<String> = self.eat().clone().into();
+            self.eat();
+
+            return match self.at().typ {
+                TokenType::KwFor => self.parse_for_stmt(Some(label)),
+                TokenType::KwWhile => self.parse_while_stmt(Some(label)),
+                TokenType::KwLoop => self.parse_loop_stmt(Some(label)),
+                _ => token_expected(&self.at().loc, "unexpected token found", "expected loop after label"),
             };
+        }
 
-            return AST::IfStmt(IfStmt::new(Box::new(cond), then_branch, else_branch));
+        fn parse_loop_label(&mut self) -> Option<Tagged<String>> {
+            if self.at().typ == TokenType::Label {
+                return Some(self.eat().clone().into());
+            }
+            return None;
         }
 
-        fn parse_for_stmt(&mut self) -> AST {
+        fn parse_loop_body(&mut self, kw: &str) -> Block {
+            if self.eat().typ != TokenType::CurlyLeft {
+                token_expected(&self.peek(-1).loc, "unexpected token found", format!("expected `{{` after {} statement", kw).as_str());
+            }
+
+            let mut block = Block::new();
+            while self.at().typ != TokenType::CurlyRight {
+                if let Some(stmt) = self.recover(Self::parse_stmt) {
+                    block.body.push(stmt);
+                }
+            }
             self.eat();
+            return block;
+        }
+
+        fn parse_while_stmt(&mut self, label: Option<Tagged<String>>) -> AST {
+            let loc = self.eat().loc.clone();
+            let cond = self.parse_expr();
+            let block = self.parse_loop_body("while");
+            return AST::WhileStmt(WhileStmt::new(loc, label, Some(Box::new(cond)), block));
+        }
+
+        fn parse_loop_stmt(&mut self, label: Option<Tagged<String>>) -> AST {
+            let loc = self.eat().loc.clone();
+            let block = self.parse_loop_body("loop");
+            return AST::WhileStmt(WhileStmt::new(loc, label, None, block));
+        }
+
+        fn parse_for_stmt(&mut self, label: Option<Tagged<String>>) -> AST {
+            let loc = self.eat().loc.clone();
+
+            if self.at().typ == TokenType::Id && self.peek(1).typ == TokenType::KwIn {
+                let var: Tagged<String> = self.eat().clone().into();
+                self.eat();
+                let iter = self.parse_expr();
+                let block = self.parse_loop_body("for");
+                return AST::ForInStmt(ForInStmt::new(loc, label, var, Box::new(iter), block));
+            }
 
             let var = self.parse_expr();
 
@@ -603,23 +845,157 @@ pub mod parser {
 
             let cond = self.parse_expr();
 
+            let step = if self.at().typ == TokenType::Semi {
+                self.eat();
+                Some(Box::new(self.parse_expr()))
+            }else {
+                None
+            };
+
+            let block = self.parse_loop_body("for");
+
+            return AST::ForStmt(ForStmt::new(loc, label, Box::new(cond), Box::new(var), step, block));
+        }
+
+        fn parse_match_stmt(&mut self) -> AST {
+            let loc = self.eat().loc.clone();
+            let value = self.parse_expr();
 
             if self.eat().typ != TokenType::CurlyLeft {
-                token_expected(&self.peek(-1).loc, "unexpected token found", "expected `{` after for statement");
+                token_expected(&self.peek(-1).loc, "unexpected token found", "expected `{` after match statement");
             }
 
-            let mut block = Block::new();
+            let mut arms = Vec::new();
             while self.at().typ != TokenType::CurlyRight {
-                self.current_block = containers::Pointer::from(&block);
-                block.body.push(self.parse_stmt());
+                let mut patterns = vec![self.parse_pattern()];
+                while self.at().typ == TokenType::Pipe {
+                    self.eat();
+                    patterns.push(self.parse_pattern());
+                }
+
+                if self.eat().typ != TokenType::FatArrow {
+                    token_expected(&self.peek(-1).loc, "unexpected token found", "expected `=>` after match pattern");
+                }
+
+                let mut body = Block::new();
+                if self.at().typ == TokenType::CurlyLeft {
+                    self.eat();
+                    while self.at().typ != TokenType::CurlyRight {
+                        if let Some(stmt) = self.recover(Self::parse_stmt) {
+                            body.body.push(stmt);
+                        }
+                    }
+                    self.eat();
+                }else {
+                    body.body.push(self.parse_expr());
+                    if self.at().typ != TokenType::Comma && self.at().typ != TokenType::CurlyRight {
+                        token_expected(&self.at().loc, "unexpected token found", "expected `,` after match arm");
+                    }
+                }
+                arms.push(MatchArm::new(patterns, body));
+
+                if self.at().typ == TokenType::Comma {
+                    self.eat();
+                }
             }
             self.eat();
 
-            return AST::ForStmt(ForStmt::new(Box::new(cond), Box::new(var), block));
+            return AST::MatchStmt(MatchStmt::new(loc, Box::new(value), arms));
         }
 
-        fn parse_match_stmt(&mut self) -> AST {
-            todo!();
+        fn parse_pattern(&mut self) -> Pattern {
+            let tok = self.at().clone();
+            match tok.typ {
+                TokenType::Id if tok.value == "_" => {
+                    self.eat();
+                    Pattern::Wildcard(tok.loc)
+                },
+                TokenType::Id => {
+                    self.eat();
+                    if self.eat().typ != TokenType::OpScope {
+                        token_expected(&self.peek(-1).loc, "unexpected token found", "expected `::` after enum identifier in pattern");
+                    }
+                    let variant = self.eat().clone();
+                    if variant.typ != TokenType::Id {
+                        token_expected(&variant.loc, "unexpected token found", "expected enum variant in pattern");
+                    }
+                    let bindings = match self.at().typ {
+                        TokenType::ParanLeft => Some(self.parse_pattern_bindings(TokenType::ParanRight)),
+                        TokenType::CurlyLeft => Some(self.parse_pattern_bindings(TokenType::CurlyRight)),
+                        _ => None,
+                    };
+                    Pattern::Variant(tok.into(), variant.into(), bindings)
+                },
+                _ => {
+                    let lo = self.parse_pattern_literal();
+                    if self.at().typ != TokenType::OpRange && self.at().typ != TokenType::OpRangeInc {
+                        return Pattern::Literal(lo);
+                    }
+                    let inclusive = self.eat().typ == TokenType::OpRangeInc;
+                    let hi = self.parse_pattern_literal();
+                    let loc = lo.loc().clone() + hi.loc().clone();
+                    let hi_value = if inclusive { *hi.inner() } else { *hi.inner() - 1 };
+                    if *lo.inner() > hi_value {
+                        token_expected(&loc, "invalid range pattern", "lower bound of range is greater than upper bound");
+                    }
+                    Pattern::Range(loc, *lo.inner(), hi_value)
+                },
+            }
+        }
+
+        fn parse_pattern_bindings(&mut self, close: TokenType) -> Vec<PatternBinding> {
+            self.eat();
+            let mut bindings = Vec::new();
+            while self.at().typ != close {
+                if close == TokenType::CurlyRight && self.at().typ == TokenType::OpRange {
+                    self.eat();
+                    if self.at().typ != close {
+                        token_expected(&self.at().loc, "unexpected token found", "`..` must be the last element of a pattern");
+                    }
+                    break;
+                }
+
+                let id = self.eat().clone();
+                if id.typ != TokenType::Id {
+                    token_expected(&id.loc, "unexpected token found", "expected identifier in pattern");
+                }
+
+                let binding = if close == TokenType::ParanRight {
+                    PatternBinding::new(Tagged::new(id.loc.clone(), bindings.len().to_string()), id.into())
+                }else if self.at().typ == TokenType::Colon {
+                    self.eat();
+                    let name = self.eat().clone();
+                    if name.typ != TokenType::Id {
+                        token_expected(&name.loc, "unexpected token found", "expected identifier after `:` in pattern");
+                    }
+                    PatternBinding::new(id.into(), name.into())
+                }else {
+                    PatternBinding::new(id.clone().into(), id.into())
+                };
+                bindings.push(binding);
+
+                if self.at().typ == close {
+                    break;
+                }
+                if self.eat().typ != TokenType::Comma {
+                    token_expected(&self.peek(-1).loc, "unexpected token found", "pattern fields need to be comma `,` separated");
+                }
+            }
+            self.eat();
+            return bindings;
+        }
+
+        fn parse_pattern_literal(&mut self) -> Tagged<i64> {
+            let negative = if self.at().typ == TokenType::OpMinus { self.eat(); true } else { false };
+            let tok = self.eat().clone();
+            let value = match tok.typ {
+                TokenType::Integer => tok.value.parse::<i64>().unwrap_or_else(|_| {
+                    token_expected(&tok.loc, "invalid integer literal", "literal does not fit into 64 bits");
+                }),
+                TokenType::Char => tok.value.as_bytes()[0] as i64,
+                _ => token_expected(&tok.loc, "unexpected token found", "expected pattern"),
+            };
+            return Tagged::new(tok.loc, if negative { -value } else { value });
         }
 
         /*fn parse_block_expr(&mut self) -> AST {
@@ -627,7 +1003,6 @@ pub mod parser {
             let mut block = Block::new();
             let mut returns = false;
             while self.at().typ != TokenType::CurlyRight {
-                self.current_block = containers::Pointer::from(&block);
                 let expr = self.parse_stmt();
                 if let AST::ClaimExpr(_) = &expr {
                     if self.at().typ != TokenType::CurlyRight {
@@ -647,8 +1022,18 @@ pub mod parser {
             return match self.at().typ {
                 TokenType::KwLet => self.parse_var_decl(),
                 TokenType::KwRet => self.parse_ret_expr(),
-                _ => self.parse_cond_expr(),
+                _ => self.parse_range_expr(),
+            }
+        }
+
+        fn parse_range_expr(&mut self) -> AST {
+            let lhs = self.parse_cond_expr();
+            if self.at().typ == TokenType::OpRange || self.at().typ == TokenType::OpRangeInc {
+                let inclusive = self.eat().typ == TokenType::OpRangeInc;
+                let rhs = self.parse_cond_expr();
+                return AST::RangeExpr(RangeExpr::new(Box::new(lhs), Box::new(rhs), inclusive));
             }
+            return lhs;
         }
 
         fn parse_var_decl(&mut self) -> AST {
@@ -662,6 +1047,10 @@ pub mod parser {
                 tok = self.eat().clone();
             }
 
+            if tok.typ == TokenType::ParanLeft {
+                return self.parse_tuple_decl(tok.loc, is_const);
+            }
+
             if tok.typ != TokenType::Id {
                 token_expected(&tok.loc, "unexpected token found", "expected identifier after keyword `let`");
             }
@@ -690,13 +1079,45 @@ pub mod parser {
             }
         }
 
+        /*
+         *  `let (a, b): (A, B) = value;`, the opening paren was consumed already
+         */
+        fn parse_tuple_decl(&mut self, start: Loc, is_const: bool) -> AST {
+            let mut ids = Vec::new();
+            while self.at().typ != TokenType::ParanRight {
+                let id = self.eat().clone();
+                if id.typ != TokenType::Id {
+                    token_expected(&id.loc, "unexpected token found", "expected identifier in tuple destructuring");
+                }
+                ids.push(id.into());
+                if self.at().typ == TokenType::ParanRight {
+                    break;
+                }
+                if self.eat().typ != TokenType::Comma {
+                    token_expected(&self.peek(-1).loc, "unexpected token found", "tuple elements have to be comma separated");
+                }
+            }
+            let loc = start + self.eat().loc.clone();
+
+            let typ = if self.at().typ == TokenType::Colon {
+                self.eat();
+                Some(self.parse_type_spec())
+            }else {
+                None
+            };
+            if self.eat().typ != TokenType::Equals {
+                token_expected(&self.peek(-1).loc, "unexpected token found", "expected `=` after tuple destructuring, a tuple has to be assigned when declared");
+            }
+            let value = self.parse_expr();
+            return AST::TupleDecl(TupleDecl::new(loc, ids, is_const, typ, Box::new(value)));
+        }
+
         /*fn parse_claim_expr(&mut self) -> AST {
             return AST::ClaimExpr(ClaimExpr::new(self.eat().loc.clone(), Box::new(self.parse_expr())));
         }*/
 
         fn parse_ret_expr(&mut self) -> AST {
             let loc = self.eat().loc.clone();
-            self.current_block.as_mut().returns = true;
             if self.at().typ == TokenType::Semi {
                 return AST::ReturnExpr(ReturnExpr::new(loc, None));
             }
@@ -704,7 +1125,27 @@ pub mod parser {
         }
 
         fn parse_cond_expr(&mut self) -> AST {
-            let mut lhs = self.parse_additive_expr();
+            let mut lhs = self.parse_and_expr();
+            while self.at().typ == TokenType::OpOr {
+                let op = self.eat().clone();
+                let rhs = self.parse_and_expr();
+                lhs = AST::BinaryExpr(BinaryExpr::new(Box::new(lhs), Box::new(rhs), op.into()));
+            }
+            return lhs;
+        }
+
+        fn parse_and_expr(&mut self) -> AST {
+            let mut lhs = self.parse_cmp_expr();
+            while self.at().typ == TokenType::OpAnd {
+                let op = self.eat().clone();
+                let rhs = self.parse_cmp_expr();
+                lhs = AST::BinaryExpr(BinaryExpr::new(Box::new(lhs), Box::new(rhs), op.into()));
+            }
+            return lhs;
+        }
+
+        fn parse_cmp_expr(&mut self) -> AST {
+            let mut lhs = self.parse_bit_or_expr();
             while self.at().typ == TokenType::OpEqEq
                || self.at().typ == TokenType::OpNeEq
                || self.at().typ == TokenType::AngleLeft
@@ -712,12 +1153,48 @@ pub mod parser {
                || self.at().typ == TokenType::OpLeEq
                || self.at().typ == TokenType::OpGrEq {
                    let op = self.eat().clone();
-                   let rhs = self.parse_additive_expr();
+                   let rhs = self.parse_bit_or_expr();
                    lhs = AST::BinaryExpr(BinaryExpr::new(Box::new(lhs), Box::new(rhs), op.into()));
-                   while self.at().typ == TokenType::OpAnd || self.at().typ == TokenType::OpOr {
-                       let op = self.eat().clone();
-                       lhs = AST::BinaryExpr(BinaryExpr::new(Box::new(lhs), Box::new(self.parse_cond_expr()), op.into()));
-                   }
+            }
+            return lhs;
+        }
+
+        fn parse_bit_or_expr(&mut self) -> AST {
+            let mut lhs = self.parse_bit_xor_expr();
+            while self.at().typ == TokenType::Pipe {
+                let op = self.eat().clone();
+                let rhs = self.parse_bit_xor_expr();
+                lhs = AST::BinaryExpr(BinaryExpr::new(Box::new(lhs), Box::new(rhs), op.into()));
+            }
+            return lhs;
+        }
+
+        fn parse_bit_xor_expr(&mut self) -> AST {
+            let mut lhs = self.parse_bit_and_expr();
+            while self.at().typ == TokenType::OpXor {
+                let op = self.eat().clone();
+                let rhs = self.parse_bit_and_expr();
+                lhs = AST::BinaryExpr(BinaryExpr::new(Box::new(lhs), Box::new(rhs), op.into()));
+            }
+            return lhs;
+        }
+
+        fn parse_bit_and_expr(&mut self) -> AST {
+            let mut lhs = self.parse_shift_expr();
+            while self.at().typ == TokenType::Ampercent {
+                let op = self.eat().clone();
+                let rhs = self.parse_shift_expr();
+                lhs = AST::BinaryExpr(BinaryExpr::new(Box::new(lhs), Box::new(rhs), op.into()));
+            }
+            return lhs;
+        }
+
+        fn parse_shift_expr(&mut self) -> AST {
+            let mut lhs = self.parse_additive_expr();
+            while self.at().typ == TokenType::OpShl || self.at().typ == TokenType::OpShr {
+                let op = self.eat().clone();
+                let rhs = self.parse_additive_expr();
+                lhs = AST::BinaryExpr(BinaryExpr::new(Box::new(lhs), Box::new(rhs), op.into()));
             }
             return lhs;
         }
@@ -749,6 +1226,14 @@ pub mod parser {
                 let rhs = self.parse_expr();
                 return AST::AssignmentExpr(AssignmentExpr::new(Box::new(lhs), Box::new(rhs)));
             }
+            if matches!(self.at().typ,
+                TokenType::OpPlusEq | TokenType::OpMinusEq | TokenType::OpMulEq | TokenType::OpDivEq | TokenType::OpModEq |
+                TokenType::OpPipeEq | TokenType::OpAmpercentEq | TokenType::OpXorEq | TokenType::OpShlEq | TokenType::OpShrEq) {
+                let tok = self.eat().clone();
+                let op = Tagged::new(tok.loc, tok.value.trim_end_matches('=').to_owned());
+                let rhs = self.parse_expr();
+                return AST::AssignmentExpr(AssignmentExpr::new_compound(Box::new(lhs), Box::new(rhs), op));
+            }
             return lhs;
         }
 
@@ -769,6 +1254,20 @@ pub mod parser {
             let mut obj = self.parse_call_expr();
             while self.at().typ == TokenType::Dot || self.at().typ == TokenType::Arrow {
                 let deref = self.eat().typ == TokenType::Arrow;
+
+                /*
+                 *  NOTE(S): `t.0.1` is lexed as `t`, `.`, `0.1`
+                 */
+                if self.at().typ == TokenType::Integer || self.at().typ == TokenType::Float {
+                    let index = self.eat().clone();
+                    for field in index.value.split('.') {
+                        let Ok(field) = field.parse::<usize>() else {
+                            token_expected(&index.loc, "unexpected token found", "expected field position after `.`");
+                        };
+                        obj = AST::MemberExpr(MemberExpr::new_index(Box::new(obj), field, deref));
+                    }
+                    continue;
+                }
                 let member = self.parse_call_expr();
                 obj = match member {
                     AST::CallExpr(_) => AST::MemberCallExpr(MemberCallExpr::new(Box::new(obj), Box::new(member), deref)),
@@ -809,7 +1308,7 @@ pub mod parser {
                     }
 
                     if self.eat().typ != TokenType::Comma {
-                        token_expected(&self.peek(-1).loc, "unexpected token found", "macro call parameters need to be comma seperated");
+                        token_expected(&self.peek(-1).loc, "unexpected token found", "macro call parameters need to be comma separated");
                     }
                 }
                 self.eat();
@@ -846,7 +1345,7 @@ pub mod parser {
                         break;
                     }
                     if self.eat().typ != TokenType::Comma {
-                        token_expected(&self.peek(-1).loc, "unexpected token found", "function parameters need to be comma seperated");
+                        token_expected(&self.peek(-1).loc, "unexpected token found", "function parameters need to be comma separated");
                     }
                 }
                 self.eat();
@@ -860,6 +1359,16 @@ pub mod parser {
             let mut obj = self.parse_unary_expr();
             while self.at().typ == TokenType::OpScope {
                 self.eat();
+                if self.at().typ == TokenType::AngleLeft {
+                    let AST::Id(id) = &obj else {
+                        token_expected(&self.at().loc, "unexpected token found", "type arguments can only be applied to identifiers");
+                    };
+                    let loc = id.loc().clone();
+                    let base = id.get_value();
+                    let args = self.parse_type_args();
+                    obj = AST::Id(Identifier::new(Tagged::new(loc, instance_name(&base, &args))));
+                    continue;
+                }
                 let member = self.parse_expr();
                 obj = AST::ResolutionExpr(ResolutionExpr::new(Box::new(obj), Box::new(member)));
             }
@@ -905,7 +1414,7 @@ pub mod parser {
                     }
 
                     if self.eat().typ != TokenType::Comma {
-                        token_expected(&self.peek(-1).loc, "unexpected token found", "initializer list values need to be comma `,` seperated");
+                        token_expected(&self.peek(-1).loc, "unexpected token found", "initializer list values need to be comma `,` separated");
                     }
                 }
                 self.eat();
@@ -922,34 +1431,67 @@ pub mod parser {
                     self.eat();
                     let typ = self.parse_type_spec();
                     if self.eat().typ != TokenType::ParanRight {
-                        token_expected(&self.peek(-1).loc, "unexpected token found", "expected closing paran `)`");
+                        token_expected(&self.peek(-1).loc, "unexpected token found", "expected closing paren `)`");
                     }
                     let expr = self.parse_expr();
                     return AST::CastExpr(CastExpr::new(Box::new(expr), typ));
                 }
                 let expr = self.parse_expr();
+                if self.at().typ == TokenType::Comma {
+                    return self.parse_tuple_expr(expr);
+                }
                 if self.eat().typ != TokenType::ParanRight {
-                    token_expected(&self.peek(-1).loc, "unexpected token found", "expected closing paran `)`");
+                    token_expected(&self.peek(-1).loc, "unexpected token found", "expected closing paren `)`");
                 }
                 return expr;
             }
             return self.parse_primary_expr();
         }
 
+        /*
+         *  NOTE(S): called after the first element and the opening paren were parsed already
+         */
+        fn parse_tuple_expr(&mut self, first: AST) -> AST {
+            let start = first.loc().clone();
+            let mut elems = vec![first];
+            while self.at().typ == TokenType::Comma {
+                self.eat();
+                if self.at().typ == TokenType::ParanRight {
+                    break;
+                }
+                elems.push(self.parse_expr());
+            }
+            if self.eat().typ != TokenType::ParanRight {
+                token_expected(&self.peek(-1).loc, "unexpected token found", "expected closing paren `)` after tuple");
+            }
+            let loc = start + self.peek(-1).loc.clone();
+            return AST::TupleExpr(TupleExpr::new(loc, elems));
+        }
+
         fn parse_closure(&mut self) -> AST {
+            let by_move = self.at().typ == TokenType::KwMove;
+            if by_move {
+                self.eat();
+                if self.at().typ != TokenType::Pipe && self.at().typ != TokenType::OpOr {
+                    token_expected(&self.at().loc, "unexpected token found", "expected closure after keyword `move`");
+                }
+            }
+            let no_args = self.at().typ == TokenType::OpOr;
             let loc = self.eat().loc.clone();
             let mut args = Vec::new();
-            while self.at().typ != TokenType::Pipe {
+            while !no_args && self.at().typ != TokenType::Pipe {
                 args.push(self.parse_func_arg());
-                if self.at().typ == TokenType::ParanRight {
+                if self.at().typ == TokenType::Pipe {
                     break;
                 }
 
                 if self.eat().typ != TokenType::Comma {
-                    token_expected(&self.peek(-1).loc, "unexpected token found", "closure arguments need to be comma seperated");
+                    token_expected(&self.peek(-1).loc, "unexpected token found", "closure arguments need to be comma separated");
                 }
             }
-            self.eat();
+            if !no_args {
+                self.eat();
+            }
             let ret_type = if self.at().typ == TokenType::Arrow {
                 self.eat();
                 self.parse_type_spec()
@@ -961,26 +1503,40 @@ pub mod parser {
                 token_expected(&self.peek(-1).loc, "unexpected token found", "expected `{` after for statement");
             }
             let mut body = Block::new();
+            self.closure_idents.push(Vec::new());
             while self.at().typ != TokenType::CurlyRight {
-                self.current_block = containers::Pointer::from(&body);
-                body.body.push(self.parse_stmt());
+                if let Some(stmt) = self.recover(Self::parse_stmt) {
+                    body.body.push(stmt);
+                }
             }
             self.eat();
+            let captures = self.closure_idents.pop().unwrap();
 
-            return AST::ClosureExpr(ClosureExpr::new(loc.clone(), Function::new(Tagged::new(loc, "".into()), None, args, body, ret_type, true, false)));
+            return AST::ClosureExpr(ClosureExpr::new(loc.clone(), Function::new(Tagged::new(loc, "".into()), None, args, body, ret_type, true, false), captures, by_move));
         }
 
         fn parse_primary_expr(&mut self) -> AST {
             let tok = self.at().clone();
             match &tok.typ {
-                TokenType::Id => AST::Id(Identifier::new(self.eat().clone().into())),
+                TokenType::Id => {
+                    let id: Tagged<String> = self.eat().clone().into();
+                    self.closure_idents.iter_mut().for_each(|idents| idents.push(id.clone()));
+                    AST::Id(Identifier::new(id))
+                },
                 TokenType::Integer => AST::NumberLiteral(NumberLiteral::new(self.eat().clone().into(), NumberType::Int)),
                 TokenType::Float => AST::NumberLiteral(NumberLiteral::new(self.eat().clone().into(), NumberType::Float)),
                 TokenType::Char => AST::NumberLiteral(NumberLiteral::new(self.eat().clone().into(), NumberType::Char)),
                 TokenType::String => AST::StringLiteral(StringLiteral::new(self.eat().clone().into())),
-                TokenType::KwBreak => AST::BreakExpr(BreakExpr::new(self.eat().loc.clone())),
-                TokenType::KwContinue => todo!("implement parsing of `KwContinue`"),
-                TokenType::Pipe => self.parse_closure(),
+                TokenType::KwBreak => {
+                    let loc = self.eat().loc.clone();
+                    AST::BreakExpr(BreakExpr::new(loc, self.parse_loop_label()))
+                },
+                TokenType::KwContinue => {
+                    let loc = self.eat().loc.clone();
+                    AST::ContinueExpr(ContinueExpr::new(loc, self.parse_loop_label()))
+                },
+                TokenType::Pipe | TokenType::OpOr | TokenType::KwMove => self.parse_closure(),
+                TokenType::KwIf => self.parse_if_stmt(),
                 _ => {
                     token_expected(&self.at().loc, format!("unexpected token found: `{:?}`", tok.typ).as_str(), "unexpected token found");
                 }
@@ -993,11 +1549,20 @@ pub mod parser {
                 let is_ref = self.eat().typ == TokenType::Ampercent;
                 let is_mut = if self.at().typ == TokenType::KwMut { self.eat(); true } else { false };
 
+                if self.at().typ == TokenType::KwDyn {
+                    self.eat();
+                    let trat = self.eat().clone();
+                    if trat.typ != TokenType::Id {
+                        token_expected(&trat.loc, "unexpected token found", "expected trait after keyword `dyn`");
+                    }
+                    return Type::Dyn(DynTrait::new(pos.clone() + trat.loc, trat.value, is_ref, is_mut));
+                }
+
                 return Type::Pointer(Pointer::new(pos.clone(), Box::new(self.parse_type_spec()), is_ref, is_mut))
             }
 
             if self.at().typ == TokenType::BraceLeft {
-                let start = self.eat().loc.start;
+                let start = self.eat().loc.clone();
                 let ty = self.parse_type_spec();
 
                 let delim = self.eat();
@@ -1019,35 +1584,44 @@ pub mod parser {
                     token_expected(&self.peek(-1).loc, "unexpected token found", "expected closing brace `]`");
                 }
 
-                return Type::Array(Array::new(Loc::new(self.peek(-1).loc.file.clone(), start, self.peek(-1).loc.end), ty, elems));
+                return Type::Array(Array::new(start + self.peek(-1).loc.clone(), ty, elems));
             }
 
-            if self.at().typ == TokenType::KwFn {
-                let start = self.eat().loc.start;
-                if self.eat().typ != TokenType::ParanLeft {
-                    token_expected(&self.peek(-1).loc, "unexpected token found", "expected `(` after keyword `fn`");
-                }
-
-                let mut params = Vec::new();
+            if self.at().typ == TokenType::ParanLeft {
+                let start = self.eat().loc.clone();
+                let mut elems = Vec::new();
+                let mut trailing_comma = false;
                 while self.at().typ != TokenType::ParanRight {
-                    params.push(self.parse_type_spec());
+                    elems.push(self.parse_type_spec());
+                    trailing_comma = self.at().typ == TokenType::Comma;
                     if self.at().typ == TokenType::ParanRight {
                         break;
                     }
                     if self.eat().typ != TokenType::Comma {
-                        token_expected(&self.peek(-1).loc, "unexpected token found", "function params have to be comma seperated");
+                        token_expected(&self.peek(-1).loc, "unexpected token found", "tuple elements have to be comma separated");
                     }
                 }
                 self.eat();
 
-                let ret_type = if self.at().typ == TokenType::Arrow {
-                    self.eat();
-                    self.parse_type_spec()
-                }else {
-                    Type::Path(Path::new(Loc::new("internal".into(), 0, 1), "void".to_string()))
-                };
+                /*
+                 *  NOTE(S): `(T)` is a parenthesized type, a tuple with a single element is written `(T,)`
+                 */
+                if elems.len() == 1 && !trailing_comma {
+                    return elems.pop().unwrap();
+                }
+                return Type::Tuple(Tuple::new(start + self.peek(-1).loc.clone(), elems));
+            }
+
+            if self.at().typ == TokenType::KwFn {
+                let start = self.eat().loc.clone();
+                let (params, ret_type) = self.parse_fn_type_signature("fn");
+                return Type::FuncPtr(FuncPtr::new(start + self.peek(-1).loc.clone(), params, ret_type));
+            }
 
-                return Type::FuncPtr(FuncPtr::new(Loc::new(self.peek(-1).loc.file.clone(), start, self.peek(-1).loc.end), params, ret_type));
+            if self.at().typ == TokenType::Id && self.at().value == "Fn" && self.peek(1).typ == TokenType::ParanLeft {
+                let start = self.eat().loc.clone();
+                let (params, ret_type) = self.parse_fn_type_signature("Fn");
+                return Type::Closure(Closure::new(start + self.peek(-1).loc.clone(), params, ret_type));
             }
 
             let path_loc = self.at().loc.clone();
@@ -1060,8 +1634,142 @@ pub mod parser {
                 if !self.current_impl.is_null() {
                     path = self.current_impl.as_ref().typ.inner().into();
                 }
+            }else if self.at().typ == TokenType::AngleLeft {
+                let args = self.parse_type_args();
+                path = instance_name(&path, &args);
             }
             return Type::Path(Path::new(path_loc, path));
         }
+
+        /*
+         *  parses the `(params) -> ret` of a function pointer or closure type
+         */
+        fn parse_fn_type_signature(&mut self, kw: &str) -> (Vec<Type>, Type) {
+            if self.eat().typ != TokenType::ParanLeft {
+                token_expected(&self.peek(-1).loc, "unexpected token found", format!("expected `(` after `{}`", kw).as_str());
+            }
+
+            let mut params = Vec::new();
+            while self.at().typ != TokenType::ParanRight {
+                params.push(self.parse_type_spec());
+                if self.at().typ == TokenType::ParanRight {
+                    break;
+                }
+                if self.eat().typ != TokenType::Comma {
+                    token_expected(&self.peek(-1).loc, "unexpected token found", "function params have to be comma separated");
+                }
+            }
+            self.eat();
+
+            let ret_type = if self.at().typ == TokenType::Arrow {
+                self.eat();
+                self.parse_type_spec()
+            }else {
+                Type::Path(Path::new(Loc::new("internal".into(), 0, 1), "void".to_string()))
+            };
+            return (params, ret_type);
+        }
+
+        pub fn parse_type(&mut self) -> Type {
+            return self.parse_type_spec();
+        }
+
+        /*
+         *  NOTE(S): expanded macros leave the `;` inserted after their expansion behind, which
+         *  has to be dropped so the captured tokens can be parsed again
+         */
+        fn capture_tokens(&self, start: usize) -> Vec<Token> {
+            return self.tokens[start..self.index]
+                .iter()
+                .filter(|t| !(t.typ == TokenType::Semi && t.loc.file.as_os_str() == "inte"))
+                .cloned()
+                .collect();
+        }
+
+        /*
+         *  whether the impl names a trait (`impl Index<u8> for Buf`), the type arguments of the
+         *  trait have to be skipped to find the `for`
+         */
+        fn is_trait_for(&self) -> bool {
+            let mut offset = 1;
+            if self.peek(offset).typ == TokenType::AngleLeft {
+                let mut depth = 0;
+                loop {
+                    match self.peek(offset).typ {
+                        TokenType::AngleLeft => depth += 1,
+                        TokenType::AngleRight => depth -= 1,
+                        TokenType::OpShr => depth -= 2,
+                        TokenType::CurlyLeft | TokenType::Semi => return false,
+                        _ => {},
+                    }
+                    offset += 1;
+                    if depth <= 0 {
+                        break;
+                    }
+                }
+            }
+            return self.peek(offset).typ == TokenType::KwFor;
+        }
+
+        fn parse_generic_params(&mut self) -> Vec<Tagged<String>> {
+            let mut params = Vec::new();
+            if self.at().typ != TokenType::AngleLeft {
+                return params;
+            }
+            self.eat();
+
+            while self.at().typ != TokenType::AngleRight {
+                let param = self.eat().clone();
+                if param.typ != TokenType::Id {
+                    token_expected(&param.loc, "unexpected token found", "expected identifier in generic parameter list");
+                }
+                params.push(param.into());
+
+                if self.at().typ == TokenType::AngleRight {
+                    break;
+                }
+                if self.eat().typ != TokenType::Comma {
+                    token_expected(&self.peek(-1).loc, "unexpected token found", "generic parameters need to be comma separated");
+                }
+            }
+            self.eat();
+
+            if params.is_empty() {
+                token_expected(&self.peek(-1).loc, "empty generic parameter list", "expected at least one generic parameter");
+            }
+            return params;
+        }
+
+        /*
+         *  NOTE(S): the closing brackets of `Vec<Vec<i32>>` get lexed as `>>`, split it back into
+         *  two `>` when parsing type arguments
+         */
+        fn split_shr(&mut self) {
+            if self.at().typ != TokenType::OpShr {
+                return;
+            }
+            let loc = self.at().loc.clone();
+            let first = Loc::new(loc.file.clone(), loc.start, loc.start + 1).with_position(loc.byte_start, loc.byte_start + 1, loc.line, loc.column);
+            let second = Loc::new(loc.file, loc.start + 1, loc.start + 2).with_position(loc.byte_start + 1, loc.byte_start + 2, loc.line, loc.column + 1);
+            self.tokens[self.index] = Token::new(">", TokenType::AngleRight, first);
+            self.tokens.insert(self.index + 1, Token::new(">", TokenType::AngleRight, second));
+        }
+
+        fn parse_type_args(&mut self) -> Vec<Type> {
+            self.eat();
+            let mut args = Vec::new();
+            while self.at().typ != TokenType::AngleRight {
+                args.push(self.parse_type_spec());
+                self.split_shr();
+                if self.at().typ == TokenType::AngleRight {
+                    break;
+                }
+                if self.eat().typ != TokenType::Comma {
+                    token_expected(&self.peek(-1).loc, "unexpected token found", "type arguments need to be comma separated");
+                }
+            }
+            self.eat();
+            return args;
+        }
     }
 }
@@ -10,10 +10,12 @@ pub enum TokenType {
     Float,   //every float is trated as double pres
     Id,
     Char,
+    Label,
 
     //general
     Equals,
     Arrow,
+    FatArrow,
     Dot,
     Comma,
     Semi,
@@ -37,9 +39,25 @@ pub enum TokenType {
     OpGrEq,
     OpOr,
     OpAnd,
+    OpXor,
+    OpShl,
+    OpShr,
+
+    OpPlusEq,
+    OpMinusEq,
+    OpMulEq,
+    OpDivEq,
+    OpModEq,
+    OpPipeEq,
+    OpAmpercentEq,
+    OpXorEq,
+    OpShlEq,
+    OpShrEq,
 
     OpScope,
     OpVarArg,
+    OpRange,
+    OpRangeInc,
 
     BraceLeft,
     BraceRight,
@@ -59,6 +77,7 @@ pub enum TokenType {
     KwElse,
     KwFor,
     KwWhile,
+    KwLoop,
     KwFn,
     KwRet,
     KwIn,
@@ -72,7 +91,9 @@ pub enum TokenType {
     KwEnum,
     KwBreak,
     KwContinue,
-    //KwDefer
+    KwDyn,
+    KwDefer,
+    KwMove,
     //KwClaim,
 
     Eof,
@@ -89,6 +110,7 @@ lazy_static! {
         map.insert("else", TokenType::KwElse);
         map.insert("for", TokenType::KwFor);
         map.insert("while", TokenType::KwWhile);
+        map.insert("loop", TokenType::KwLoop);
         map.insert("fn", TokenType::KwFn);
         map.insert("return", TokenType::KwRet);
         map.insert("in", TokenType::KwIn);
@@ -102,6 +124,9 @@ lazy_static! {
         map.insert("enum", TokenType::KwEnum);
         map.insert("break", TokenType::KwBreak);
         map.insert("continue", TokenType::KwContinue);
+        map.insert("dyn", TokenType::KwDyn);
+        map.insert("defer", TokenType::KwDefer);
+        map.insert("move", TokenType::KwMove);
         //map.insert("claim", TokenType::KwClaim);
 
         map
@@ -169,6 +194,40 @@ pub fn base_to_type<'s>(ty: &'s str, ctx: &crate::GenerationContext) -> Option<l
     }
 }
 
+/*
+ *  NOTE(S): turns the name of an instantiated generic (`Vec<i32>`) into a valid symbol name
+ *  (`Vec$Li32$G`). every other character becomes `$` and a letter, `$` is never part of an
+ *  identifier so two different names can not end up with the same symbol. a space is only kept
+ *  where it separates two words (`&mut dyn Debug`)
+ */
+pub fn mangle_name<S: AsRef<str>>(name: S) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let chars: Vec<char> = name.as_ref().chars().collect();
+    let mut mangled = String::with_capacity(chars.len());
+    for (i, c) in chars.iter().copied().enumerate() {
+        match c {
+            ' ' => {
+                if i > 0 && i + 1 < chars.len() && is_word(chars[i - 1]) && is_word(chars[i + 1]) {
+                    mangled += "$_";
+                }
+            },
+            '<' => mangled += "$L",
+            '>' => mangled += "$G",
+            ',' => mangled += "$C",
+            '*' => mangled += "$P",
+            '&' => mangled += "$R",
+            '[' => mangled += "$A",
+            ']' => mangled += "$E",
+            ';' => mangled += "$S",
+            '(' => mangled += "$T",
+            ')' => mangled += "$U",
+            c if is_word(c) => mangled.push(c),
+            c => mangled += &format!("$x{:x}$", c as u32),
+        }
+    }
+    return mangled;
+}
+
 #[derive(Debug, Clone)]
 pub struct Path {
     pub loc: Loc,
@@ -225,6 +284,39 @@ impl FuncPtr {
     }
 }
 
+/*
+ *  a `Fn(..) -> T`, represented as `{ *fn, *env, *env_drop }` where the function takes the
+ *  environment as its first argument. `env_drop` frees the environment of a `move` closure and is
+ *  null otherwise
+ */
+#[derive(Debug, Clone)]
+pub struct Closure {
+    pub loc: Loc,
+    pub params: Vec<Type>,
+    pub ret_type: Box<Type>,
+}
+
+impl Closure {
+    pub fn new(loc: Loc, params: Vec<Type>, ret_type: Type) -> Closure {
+        Self{ loc, params, ret_type: Box::new(ret_type) }
+    }
+}
+
+/*
+ *  an anonymous struct `(A, B, ..)` whose fields are accessed by position
+ */
+#[derive(Debug, Clone)]
+pub struct Tuple {
+    pub loc: Loc,
+    pub elems: Vec<Type>,
+}
+
+impl Tuple {
+    pub fn new(loc: Loc, elems: Vec<Type>) -> Tuple {
+        Self{ loc, elems }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ArraySlice {
     pub loc: Loc,
@@ -237,13 +329,33 @@ impl ArraySlice {
     }
 }
 
+/*
+ *  a `&dyn Trait` or `*dyn Trait`, represented as a fat pointer `{ *i8, *vtable }`
+ */
+#[derive(Debug, Clone)]
+pub struct DynTrait {
+    pub loc: Loc,
+    pub trat: String,
+    pub is_ref: bool,
+    pub is_mut: bool,
+}
+
+impl DynTrait {
+    pub fn new(loc: Loc, trat: String, is_ref: bool, is_mut: bool) -> DynTrait {
+        Self{ loc, trat, is_ref, is_mut }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Type {
     Path(Path),
     Pointer(Pointer),
     Array(Array),
     FuncPtr(FuncPtr),
+    Closure(Closure),
+    Tuple(Tuple),
     Slice(ArraySlice),
+    Dyn(DynTrait),
 }
 
 impl Type {
@@ -254,6 +366,9 @@ impl Type {
             Type::Array(arr) => arr.loc.clone(),
             Type::Slice(slice) => slice.loc.clone(),
             Type::FuncPtr(fn_ptr) => fn_ptr.loc.clone(),
+            Type::Closure(c) => c.loc.clone(),
+            Type::Tuple(t) => t.loc.clone(),
+            Type::Dyn(d) => d.loc.clone(),
         };
     }
 
@@ -264,6 +379,9 @@ impl Type {
             Type::Array(arr) => arr.typ.get_name(),
             Type::Slice(slice) => slice.typ.get_name(),
             Type::FuncPtr(_) => todo!(),
+            Type::Closure(_) => "Fn".to_owned(),
+            Type::Tuple(_) => self.get_full_name(),
+            Type::Dyn(d) => d.trat.clone(),
         }
     }
 
@@ -288,6 +406,18 @@ impl Type {
                 string += &format!(") -> {}", func.ret_type.get_full_name());
                 return string;
             }
+            Type::Closure(c) => {
+                let params: Vec<String> = c.params.iter().map(|param| param.get_full_name()).collect();
+                return format!("Fn({}) -> {}", params.join(", "), c.ret_type.get_full_name());
+            }
+            Type::Tuple(t) => {
+                let elems: Vec<String> = t.elems.iter().map(|elem| elem.get_full_name()).collect();
+                return format!("({})", elems.join(", "));
+            }
+            Type::Dyn(d) => {
+                let prefix = if d.is_ref { "&" } else { "*" }.to_owned();
+                prefix + if d.is_mut { "mut " } else { "" } + "dyn " + &d.trat
+            }
         }
     }
 
@@ -296,6 +426,9 @@ impl Type {
             (Type::Pointer(lhs), Type::Pointer(rhs)) => {
                 return lhs.typ.matches(&rhs.typ);
             }
+            (Type::Dyn(lhs), Type::Dyn(rhs)) => {
+                return lhs.trat == rhs.trat;
+            }
             _ => return self.get_full_name() == rhs.get_full_name(),
         }
     }
@@ -0,0 +1,245 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/*
+ *  NOTE(S): every program in `tests/programs` is compiled to an object file, linked with `cc`
+ *  together with `runtime.c` and run. a program reports a failure through the exit code of `main`
+ */
+
+fn program(name: &str) -> PathBuf {
+    return Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("programs").join(format!("{}.ly", name));
+}
+
+fn compile(name: &str, args: &[&str]) -> (Output, PathBuf) {
+    let obj = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.o", name));
+    let output = Command::new(env!("CARGO_BIN_EXE_lygosc-rust"))
+        .arg("-c").arg(program(name))
+        .arg("-o").arg(&obj)
+        .args(args)
+        .output()
+        .expect("could not run the compiler");
+    return (output, obj);
+}
+
+fn link(name: &str, args: &[&str]) -> PathBuf {
+    let (output, obj) = compile(name, args);
+    assert!(output.status.success(), "`{}` did not compile:\n{}", name, String::from_utf8_lossy(&output.stderr));
+
+    let exe = obj.with_extension("");
+    let runtime = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("programs").join("runtime.c");
+    let status = Command::new("cc").arg(&obj).arg(&runtime).arg("-o").arg(&exe).status().expect("could not run `cc`");
+    assert!(status.success(), "could not link `{}`", name);
+    return exe;
+}
+
+fn run(name: &str, args: &[&str]) -> i32 {
+    let status = Command::new(link(name, args)).status().expect("could not run the program");
+    return status.code().unwrap_or(-1);
+}
+
+#[test]
+fn match_i64_patterns() {
+    assert_eq!(run("match_i64", &[]), 0);
+}
+
+#[test]
+fn mangled_names_do_not_collide() {
+    assert_eq!(run("mangle_pointer_args", &[]), 0);
+}
+
+#[test]
+fn loops_inside_closures() {
+    assert_eq!(run("closure_loops", &[]), 0);
+}
+
+#[test]
+fn break_in_closure_does_not_leave_enclosing_loop() {
+    let (output, _) = compile("closure_break", &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(stderr.contains("`break` outside of a loop"), "{}", stderr);
+}
+
+#[test]
+fn move_closures_drop_their_environment() {
+    assert_eq!(run("closure_drop", &[]), 0);
+}
+
+#[test]
+fn checked_arithmetic_in_a_loop() {
+    assert_eq!(run("checked_loop", &["--checks"]), 0);
+}
+
+#[test]
+fn poisoned_let_still_declares_its_variables() {
+    let (output, _) = compile("poisoned_let", &["--error-format=json"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let errors: Vec<&str> = stderr.lines().filter(|line| line.contains("\"code\":\"E")).collect();
+    assert_eq!(errors.len(), 2, "{}", stderr);
+    assert!(errors[0].contains("unknown field `z`"), "{}", stderr);
+    assert!(errors[1].contains("could not resolve variable `unknown_value`"), "{}", stderr);
+}
+
+#[test]
+fn unreachable_code_is_not_generated() {
+    let (output, _) = compile("unreachable", &["--error-format=json"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.lines().filter(|line| line.contains("unreachable statement")).count(), 2, "{}", stderr);
+    assert_eq!(run("unreachable", &[]), 0);
+}
+
+#[test]
+fn unreachable_code_is_still_checked() {
+    let (output, _) = compile("unreachable_typo", &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(stderr.contains("could not resolve variable `valeu`"), "{}", stderr);
+}
+
+#[test]
+fn json_diagnostics() {
+    let (output, _) = compile("json_diagnostics", &["--error-format=json", "-D", "unused_variables"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    let diags: Vec<serde_json::Value> = stderr.lines().map(|line| serde_json::from_str(line).expect(line)).collect();
+    assert_eq!(diags.len(), 2, "{}", stderr);
+
+    let unused = &diags[0];
+    assert_eq!(unused["severity"], "error");
+    assert_eq!(unused["code"], "unused_variables");
+    assert_eq!(unused["message"], "unused variable: `unused`");
+    assert_eq!(unused["note"], "`-D unused_variables` was given on the command line");
+    assert_eq!(unused["help"], "if this is intentional, prefix it with an underscore: `_unused`");
+    let span = &unused["spans"][0];
+    assert!(span["file"].as_str().unwrap().ends_with("json_diagnostics.ly"), "{}", span);
+    assert_eq!((&span["line"], &span["column"], &span["primary"]), (&2.into(), &9.into(), &true.into()));
+
+    let unreachable = &diags[1];
+    assert_eq!(unreachable["severity"], "warning");
+    assert_eq!(unreachable["code"], "unreachable_code");
+    assert_eq!(unreachable["help"], serde_json::Value::Null);
+    let spans = unreachable["spans"].as_array().unwrap();
+    assert_eq!(spans.len(), 2);
+    assert_eq!((&spans[0]["line"], &spans[0]["column"], &spans[0]["primary"]), (&4.into(), &5.into(), &true.into()));
+    assert_eq!((&spans[1]["line"], &spans[1]["column"], &spans[1]["primary"]), (&3.into(), &5.into(), &false.into()));
+}
+
+#[test]
+fn return_inside_an_expression() {
+    assert_eq!(run("return_in_expression", &[]), 0);
+}
+
+#[test]
+fn string_map() {
+    assert_eq!(run("string_map", &[]), 0);
+}
+
+#[test]
+fn copies_out_of_places_are_not_dropped() {
+    assert_eq!(run("drop_copies", &["-A", "warnings"]), 0);
+}
+
+#[test]
+fn compound_assignment_drops_the_old_value() {
+    assert_eq!(run("drop_compound", &["-A", "warnings"]), 0);
+}
+
+#[test]
+fn locals_are_dropped_once() {
+    assert_eq!(run("drop", &["-A", "warnings"]), 0);
+}
+
+#[test]
+fn generic_index_trait() {
+    assert_eq!(run("index_trait", &["-A", "warnings"]), 0);
+}
+
+#[test]
+fn index_impl_has_to_match_the_element_type() {
+    let (output, _) = compile("index_trait_mismatch", &["-A", "warnings"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(stderr.contains("return type of method `index` does not match trait `Index`"), "{}", stderr);
+    assert!(stderr.contains("expected `*i64`, found `*i32`"), "{}", stderr);
+}
+
+#[test]
+fn generic_iterator_trait() {
+    assert_eq!(run("iterator_trait", &["-A", "warnings"]), 0);
+}
+
+#[test]
+fn iterator_impl_has_to_return_an_option_of_the_element_type() {
+    let (output, _) = compile("iterator_trait_mismatch", &["-A", "warnings"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(stderr.contains("return type of method `next` does not match trait `Iterator`"), "{}", stderr);
+    assert!(stderr.contains("expected `Option<i32>`, found `Option<i64>`"), "{}", stderr);
+}
+
+#[test]
+fn if_expressions() {
+    assert_eq!(run("if_expression", &["-A", "warnings"]), 0);
+}
+
+#[test]
+fn if_branches_have_to_agree_on_a_type() {
+    let (output, _) = compile("if_incompatible", &["-A", "warnings"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(stderr.contains("`if` and `else` have incompatible types"), "{}", stderr);
+    assert!(stderr.contains("expected `i32` because of this"), "{}", stderr);
+    assert!(stderr.contains("found `i64`"), "{}", stderr);
+}
+
+#[test]
+fn deferred_statements_run_in_reverse_on_every_exit() {
+    assert_eq!(run("defer", &["-A", "warnings"]), 0);
+}
+
+#[test]
+fn enum_payloads_are_bound_in_match_arms() {
+    assert_eq!(run("payload_enum", &["-A", "warnings"]), 0);
+}
+
+#[test]
+fn trait_objects_dispatch_to_each_impl() {
+    assert_eq!(run("dyn_trait", &["-A", "warnings"]), 0);
+}
+
+#[test]
+fn shared_reference_is_not_a_mutable_trait_object() {
+    let (output, _) = compile("dyn_shared_to_mut", &["-A", "warnings"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(stderr.contains("`&Square` is a shared reference, it cannot be used as `&mut dyn Shape`"), "{}", stderr);
+}
+
+#[test]
+fn tuples_are_returned_indexed_and_destructured() {
+    assert_eq!(run("tuple", &["-A", "warnings"]), 0);
+}
+
+#[test]
+fn slices_of_arrays_and_vecs() {
+    assert_eq!(run("slice", &["-A", "warnings"]), 0);
+}
+
+#[test]
+fn out_of_bounds_subslice_aborts() {
+    let output = Command::new(link("slice_out_of_bounds", &["-A", "warnings"])).output().expect("could not run the program");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(stderr.contains("range out of bounds: the len is 5 but the range is 2..7"), "{}", stderr);
+    assert!(stderr.contains("slice_out_of_bounds.ly:5:17"), "{}", stderr);
+}
+
+#[test]
+fn generic_set() {
+    assert_eq!(run("set", &["-A", "warnings"]), 0);
+}
+
+#[test]
+fn generic_fixed_size_array() {
+    assert_eq!(run("array", &["-A", "warnings"]), 0);
+}
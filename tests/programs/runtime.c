@@ -0,0 +1,14 @@
+/*
+ *  the parts of the runtime `std/core/fmt.ly` expects to be linked in, so test programs can
+ *  include the containers and printing functions of the standard library
+ */
+#include <stdio.h>
+#include <stdint.h>
+
+int64_t int_to_str(char *buffer, int64_t i, int32_t base) {
+    return snprintf(buffer, 24, base == 16 ? "%llx" : "%lld", (long long)i);
+}
+
+int32_t ptr_to_str(char *buffer, uint64_t buf_size, void *ptr) {
+    return snprintf(buffer, buf_size, "%p", ptr);
+}